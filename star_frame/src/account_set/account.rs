@@ -0,0 +1,236 @@
+//! Account set for accounts owned by a `star_frame` program.
+
+use crate::{
+    account_set::{
+        modifiers::{HasInnerType, HasOwnerProgram, ReadAccount},
+        AccountPath, AccountSetAccounts, SingleSetMeta,
+    },
+    prelude::*,
+    util::FastPubkeyEq,
+    ErrorCode,
+};
+use bytemuck::pod_read_unaligned;
+use std::marker::PhantomData;
+
+/// The data of an account owned by a `star_frame` program, stored after the owner program's
+/// [`AccountDiscriminant`](StarFrameProgram::AccountDiscriminant).
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// # #[derive(Debug)]
+/// # pub struct MyProgram;
+/// # impl StarFrameProgram for MyProgram {
+/// #     type InstructionSet = ();
+/// #     type AccountDiscriminant = [u8; 8];
+/// #     type Errors = ();
+/// #     const ID: Pubkey = [7; 32];
+/// # }
+/// #[derive(Debug, Clone, Copy, Pod, Zeroable)]
+/// #[repr(C)]
+/// pub struct Counter {
+///     pub count: u64,
+///     pub bump: u8,
+///     pub padding: [u8; 7],
+/// }
+///
+/// impl ProgramAccount for Counter {
+///     type OwnerProgram = MyProgram;
+///     const DISCRIMINANT: [u8; 8] = *b"counter\0";
+///     const BUMP_OFFSET: Option<usize> = Some(core::mem::offset_of!(Counter, bump));
+/// }
+/// ```
+pub trait ProgramAccount: Pod {
+    /// The program that owns the account.
+    type OwnerProgram: StarFrameProgram;
+    /// The discriminant at the start of the account's data.
+    const DISCRIMINANT: <Self::OwnerProgram as StarFrameProgram>::AccountDiscriminant;
+    /// The offset in `Self` of a `u8` field storing the account's canonical PDA bump, for accounts that store it.
+    ///
+    /// [`Seeded`](crate::account_set::seeded::Seeded) reads it with [`SingleAccountSet::stored_bump`] to check the
+    /// address with a single `create_program_address` instead of searching for the bump. Using an [`Account`] of a type
+    /// whose offset is past its end fails to compile.
+    const BUMP_OFFSET: Option<usize> = None;
+}
+
+type Discriminant<T> =
+    <<T as ProgramAccount>::OwnerProgram as StarFrameProgram>::AccountDiscriminant;
+
+/// An account owned by `T::OwnerProgram` whose data is a `T`.
+///
+/// Validation checks the owner and the discriminant, and that the data is long enough for a `T`.
+#[derive(derive_where::DeriveWhere)]
+#[derive_where(Debug, Clone, Copy)]
+pub struct Account<T>
+where
+    T: ProgramAccount,
+{
+    account: AccountInfo,
+    phantom_t: PhantomData<fn() -> T>,
+}
+
+impl<T> Account<T>
+where
+    T: ProgramAccount,
+{
+    /// Fails to compile if `T::BUMP_OFFSET` doesn't point at a byte of `T`.
+    const BUMP_OFFSET_IN_BOUNDS: () = if let Some(offset) = T::BUMP_OFFSET {
+        assert!(
+            offset < size_of::<T>(),
+            "`ProgramAccount::BUMP_OFFSET` is past the end of the account"
+        );
+    };
+
+    /// Reads a copy of the account's data.
+    pub fn read(&self) -> Result<T> {
+        let data = self.account.try_borrow_data()?;
+        let start = size_of::<Discriminant<T>>();
+        let bytes = data
+            .get(start..start + size_of::<T>())
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        Ok(pod_read_unaligned(bytes))
+    }
+
+    fn check_account(self) -> Result<()> {
+        let owner = <T::OwnerProgram as StarFrameProgram>::ID;
+        ensure!(
+            self.owner_pubkey().fast_eq(&owner),
+            ErrorCode::OwnerMismatch,
+            "Expected account `{}` to be owned by `{}`, found `{}`",
+            self.pubkey().display(),
+            owner.display(),
+            self.owner_pubkey().display()
+        );
+        let data = self.account.try_borrow_data()?;
+        ensure!(
+            data.len() >= size_of::<Discriminant<T>>() + size_of::<T>(),
+            ProgramError::AccountDataTooSmall,
+            "Account `{}` is too small to hold its data",
+            self.pubkey().display()
+        );
+        let discriminant: Discriminant<T> =
+            pod_read_unaligned(&data[..size_of::<Discriminant<T>>()]);
+        ensure!(
+            discriminant == T::DISCRIMINANT,
+            ErrorCode::DiscriminantMismatch,
+            "Account `{}` has the wrong discriminant",
+            self.pubkey().display()
+        );
+        Ok(())
+    }
+}
+
+impl<T> SingleAccountSet for Account<T>
+where
+    T: ProgramAccount,
+{
+    fn meta() -> SingleSetMeta {
+        SingleSetMeta::default()
+    }
+
+    fn account_info(&self) -> &AccountInfo {
+        &self.account
+    }
+
+    fn stored_bump(&self) -> Result<Option<u8>> {
+        let () = Self::BUMP_OFFSET_IN_BOUNDS;
+        let Some(offset) = T::BUMP_OFFSET else {
+            return Ok(None);
+        };
+        let data = self.account.try_borrow_data()?;
+        let bump = data
+            .get(size_of::<Discriminant<T>>() + offset)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        Ok(Some(*bump))
+    }
+}
+
+impl<T> HasInnerType for Account<T>
+where
+    T: ProgramAccount,
+{
+    type Inner = T;
+}
+
+impl<T> ReadAccount for Account<T>
+where
+    T: ProgramAccount,
+{
+    type Data = T;
+
+    fn read_data(&self) -> Result<T> {
+        self.read()
+    }
+}
+
+impl<T> HasOwnerProgram for Account<T>
+where
+    T: ProgramAccount,
+{
+    type OwnerProgram = T::OwnerProgram;
+}
+
+impl<'a, T> AccountSetDecode<'a, ()> for Account<T>
+where
+    T: ProgramAccount,
+{
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        (): (),
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        Ok(Self {
+            account: AccountInfo::decode_accounts(accounts, (), ctx)?,
+            phantom_t: PhantomData,
+        })
+    }
+}
+
+impl<T> AccountSetValidate<()> for Account<T>
+where
+    T: ProgramAccount,
+{
+    fn validate_accounts(&mut self, (): (), _ctx: &mut Context) -> Result<()> {
+        self.check_account()
+    }
+}
+
+impl<T> AccountSetCleanup<()> for Account<T>
+where
+    T: ProgramAccount,
+{
+    fn cleanup_accounts(&mut self, (): (), _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<T> AccountSetAccounts for Account<T>
+where
+    T: ProgramAccount,
+{
+    fn visit_accounts(
+        &self,
+        path: &AccountPath<'_>,
+        visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    ) {
+        visit(path, &self.account, Self::meta().writable);
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use crate::idl::AccountSetToIdl;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<T> AccountSetToIdl<()> for Account<T>
+    where
+        T: ProgramAccount,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            (): (),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            AccountInfo::account_set_to_idl(idl_definition, ())
+        }
+    }
+}
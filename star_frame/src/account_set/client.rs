@@ -0,0 +1,258 @@
+//! Off-chain keys for passing account sets to instructions.
+//!
+//! Every account set has [`ClientAccountSet::ClientAccounts`], the keys a client fills in to build an instruction.
+//! Single account sets take a [`Pubkey`], and the [`AccountSet`](derive@crate::account_set::AccountSet) derive
+//! generates a `<Name>ClientAccounts` struct for other sets, with a field for each of the set's fields. Optional sets
+//! take an `Option`, with the program id passed in for `None`.
+
+use crate::{account_set::many::Many, prelude::*};
+use std::fmt::Debug;
+
+/// An account passed to an instruction, with the flags its account set requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientAccountMeta {
+    pub pubkey: Pubkey,
+    pub signer: bool,
+    pub writable: bool,
+}
+
+impl ClientAccountMeta {
+    /// A read-only account that doesn't sign.
+    #[must_use]
+    pub fn readonly(pubkey: Pubkey) -> Self {
+        Self {
+            pubkey,
+            signer: false,
+            writable: false,
+        }
+    }
+}
+
+/// An account set that a client can pass to an instruction.
+///
+/// Derivable with [`AccountSet`](derive@crate::account_set::AccountSet).
+pub trait ClientAccountSet {
+    /// The keys a client fills in for the set.
+    type ClientAccounts: Debug + Clone;
+
+    /// Appends the set's accounts to `metas`, in the order the set decodes them.
+    ///
+    /// `program_id` is the program the instruction is for, which stands in for `None` optional sets.
+    fn extend_account_metas(
+        program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+        metas: &mut Vec<ClientAccountMeta>,
+    );
+
+    /// The set's accounts, in the order the set decodes them.
+    fn account_metas(
+        program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+    ) -> Vec<ClientAccountMeta> {
+        let mut metas = vec![];
+        Self::extend_account_metas(program_id, accounts, &mut metas);
+        metas
+    }
+}
+
+impl<T> ClientAccountSet for T
+where
+    T: SingleAccountSet,
+{
+    type ClientAccounts = Pubkey;
+
+    fn extend_account_metas(
+        _program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+        metas: &mut Vec<ClientAccountMeta>,
+    ) {
+        let meta = T::meta();
+        metas.push(ClientAccountMeta {
+            pubkey: *accounts,
+            signer: meta.signer,
+            writable: meta.writable,
+        });
+    }
+}
+
+/// `None` is passed as a single read-only account with the program id, even if `T` is made of several accounts.
+impl<T> ClientAccountSet for Option<T>
+where
+    T: ClientAccountSet,
+{
+    type ClientAccounts = Option<T::ClientAccounts>;
+
+    fn extend_account_metas(
+        program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+        metas: &mut Vec<ClientAccountMeta>,
+    ) {
+        match accounts {
+            Some(accounts) => T::extend_account_metas(program_id, accounts, metas),
+            None => metas.push(ClientAccountMeta::readonly(*program_id)),
+        }
+    }
+}
+
+impl ClientAccountSet for () {
+    type ClientAccounts = ();
+
+    fn extend_account_metas(
+        _program_id: &Pubkey,
+        (): &Self::ClientAccounts,
+        _metas: &mut Vec<ClientAccountMeta>,
+    ) {
+    }
+}
+
+impl<T> ClientAccountSet for Vec<T>
+where
+    T: ClientAccountSet,
+{
+    type ClientAccounts = Vec<T::ClientAccounts>;
+
+    fn extend_account_metas(
+        program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+        metas: &mut Vec<ClientAccountMeta>,
+    ) {
+        for accounts in accounts {
+            T::extend_account_metas(program_id, accounts, metas);
+        }
+    }
+}
+
+impl<T, const N: usize> ClientAccountSet for [T; N]
+where
+    T: ClientAccountSet,
+{
+    type ClientAccounts = [T::ClientAccounts; N];
+
+    fn extend_account_metas(
+        program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+        metas: &mut Vec<ClientAccountMeta>,
+    ) {
+        for accounts in accounts {
+            T::extend_account_metas(program_id, accounts, metas);
+        }
+    }
+}
+
+impl<T> ClientAccountSet for Many<T>
+where
+    T: ClientAccountSet,
+{
+    type ClientAccounts = Vec<T::ClientAccounts>;
+
+    fn extend_account_metas(
+        program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+        metas: &mut Vec<ClientAccountMeta>,
+    ) {
+        Vec::<T>::extend_account_metas(program_id, accounts, metas);
+    }
+}
+
+/// The members that sign, each passed as a read-only signer.
+impl<const M: usize, const N: usize> ClientAccountSet for MultisigSigners<M, N> {
+    type ClientAccounts = Vec<Pubkey>;
+
+    fn extend_account_metas(
+        _program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+        metas: &mut Vec<ClientAccountMeta>,
+    ) {
+        metas.extend(accounts.iter().map(|pubkey| ClientAccountMeta {
+            signer: true,
+            ..ClientAccountMeta::readonly(*pubkey)
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+
+    #[derive(AccountSet, Debug)]
+    struct Pool {
+        authority: Signer,
+        vault: Mut<SystemAccount>,
+    }
+
+    #[derive(AccountSet, Debug)]
+    struct SwapAccounts {
+        user: Signer<Mut<SystemAccount>>,
+        referrer: Option<SystemAccount>,
+        pool: Option<Pool>,
+        #[account_set(skip = 0)]
+        _skipped: u64,
+        hops: Many<Pool>,
+    }
+
+    fn signer(key: u8) -> ClientAccountMeta {
+        ClientAccountMeta {
+            signer: true,
+            ..ClientAccountMeta::readonly([key; 32])
+        }
+    }
+
+    fn writable(key: u8) -> ClientAccountMeta {
+        ClientAccountMeta {
+            writable: true,
+            ..ClientAccountMeta::readonly([key; 32])
+        }
+    }
+
+    #[test]
+    fn test_client_accounts() {
+        let accounts = SwapClientAccounts {
+            user: [1; 32],
+            referrer: Some([2; 32]),
+            pool: Some(PoolClientAccounts {
+                authority: [3; 32],
+                vault: [4; 32],
+            }),
+            hops: vec![PoolClientAccounts {
+                authority: [5; 32],
+                vault: [6; 32],
+            }],
+        };
+        let user = ClientAccountMeta {
+            signer: true,
+            writable: true,
+            ..ClientAccountMeta::readonly([1; 32])
+        };
+        assert_eq!(
+            SwapAccounts::account_metas(&PROGRAM_ID, &accounts),
+            [
+                user,
+                ClientAccountMeta::readonly([2; 32]),
+                signer(3),
+                writable(4),
+                signer(5),
+                writable(6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_none_is_program_id() {
+        let accounts = SwapClientAccounts {
+            user: [1; 32],
+            referrer: None,
+            pool: None,
+            hops: vec![],
+        };
+        let metas = SwapAccounts::account_metas(&PROGRAM_ID, &accounts);
+        assert_eq!(
+            metas[1..],
+            [
+                ClientAccountMeta::readonly(PROGRAM_ID),
+                ClientAccountMeta::readonly(PROGRAM_ID),
+            ]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Account set modifier that closes the account during cleanup.
+
+use crate::{account_set::modifiers::WritableAccount, prelude::*, ErrorCode};
+use derive_more::{Deref, DerefMut};
+
+/// A modifier that checks the account is writable, and closes it during [`AccountSetCleanup`].
+///
+/// Closing moves all of the account's lamports to the recipient, zeroes its data, and reassigns it to the system
+/// program with a data length of zero. The default cleanup sends the lamports to the [`Context`] recipient, set with
+/// `#[validate(recipient)]`. Cleaning up with a reference to another [`CanAddLamports`] account sends them there
+/// instead, like `#[cleanup(arg = &self.destination)]`.
+///
+/// Closed accounts can't be revived. If lamports are added back to the account later in the transaction, it's an empty
+/// account owned by the system program, which fails the owner checks of the program that closed it. The runtime only
+/// allows reassigning accounts with zeroed data, so no closed discriminant is left behind in the data.
+///
+/// Decoding and validation arguments are passed through to `T`, and `T` is cleaned up with `()` before the account
+/// is closed.
+#[derive(AccountSet, Debug, Deref, DerefMut, Clone, Copy)]
+#[decode(generics = [<A>], arg = A)]
+#[validate(generics = [<A>], arg = A, extra_validation = self.check_writable())]
+#[cleanup(extra_cleanup = self.close_to_recipient(ctx))]
+#[cleanup(
+    id = "recipient",
+    generics = [<'__r, R> where R: CanAddLamports],
+    arg = &'__r R,
+    extra_cleanup = self.close_account(arg, ctx)
+)]
+#[repr(transparent)]
+pub struct Close<T = AccountInfo>(
+    #[single_account_set(writable, skip_writable_account)]
+    #[decode(arg = arg)]
+    #[validate(arg = arg)]
+    T,
+);
+
+impl<T> WritableAccount for Close<T> where T: SingleAccountSet {}
+
+impl<T> Close<T>
+where
+    T: SingleAccountSet,
+{
+    /// Closes the account, sending its lamports to the [`Context`] recipient.
+    fn close_to_recipient(&self, ctx: &Context) -> Result<()> {
+        let recipient = ctx.get_recipient().ok_or_else(|| {
+            error!(
+                ErrorCode::EmptyRecipientCache,
+                "Closing `{}` needs a recipient",
+                self.pubkey().display()
+            )
+        })?;
+        self.close_account(recipient, ctx)
+    }
+
+    /// Closes the account, sending its lamports to `recipient`.
+    fn close_account(
+        &self,
+        recipient: &(impl CanAddLamports + ?Sized),
+        ctx: &Context,
+    ) -> Result<()> {
+        let info = self.account_info();
+        ensure!(
+            info.is_owned_by(ctx.current_program_id()),
+            ProgramError::IllegalOwner,
+            "Account `{}` must be owned by the current program to be closed",
+            self.pubkey().display()
+        );
+        ensure!(
+            !recipient.pubkey().fast_eq(self.pubkey()),
+            ProgramError::InvalidArgument,
+            "Account `{}` can't be closed to itself",
+            self.pubkey().display()
+        );
+        recipient.add_lamports(info.lamports())?;
+        info.try_borrow_mut_data()?.fill(0);
+        // Zeroes the lamports and data length, and assigns the account to the system program.
+        info.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestAccount;
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+
+    #[derive(AccountSet, Debug)]
+    struct CloseAccounts {
+        #[validate(recipient)]
+        recipient: Mut<SystemAccount>,
+        account: Close,
+    }
+
+    #[derive(AccountSet, Debug)]
+    struct CloseToDestination {
+        destination: Mut<SystemAccount>,
+        #[cleanup(arg = &self.destination)]
+        account: Close,
+    }
+
+    fn program_account() -> TestAccount {
+        TestAccount::new([1; 32], PROGRAM_ID, 5_000, 16)
+            .writable()
+            .with_data(&[0xab; 16])
+    }
+
+    fn wallet() -> TestAccount {
+        TestAccount::new([2; 32], pinocchio_system::ID, 1_000, 0).writable()
+    }
+
+    fn run<T>(infos: &[AccountInfo]) -> Result<()>
+    where
+        T: for<'a> AccountSetDecode<'a, ()> + AccountSetValidate<()> + AccountSetCleanup<()>,
+    {
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = T::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.validate_accounts((), &mut ctx)?;
+        set.cleanup_accounts((), &mut ctx)
+    }
+
+    fn assert_closed(account: &TestAccount) {
+        assert_eq!(account.lamports(), 0);
+        assert_eq!(account.data_len(), 0);
+        assert_eq!(account.owner(), pinocchio_system::ID);
+    }
+
+    #[test]
+    fn test_close_to_recipient() -> Result<()> {
+        let mut recipient = wallet();
+        let mut account = program_account();
+        run::<CloseAccounts>(&[recipient.account_info(), account.account_info()])?;
+        assert_eq!(recipient.lamports(), 6_000);
+        assert_closed(&account);
+        Ok(())
+    }
+
+    #[test]
+    fn test_close_to_destination() -> Result<()> {
+        let mut destination = wallet();
+        let mut account = program_account();
+        let info = account.account_info();
+        run::<CloseToDestination>(&[destination.account_info(), info])?;
+        assert_eq!(destination.lamports(), 6_000);
+        // The data is zeroed before it's truncated.
+        // SAFETY: The account isn't borrowed.
+        assert!(unsafe { std::slice::from_raw_parts(info.data_ptr(), 16) }
+            .iter()
+            .all(|byte| *byte == 0));
+        assert_closed(&account);
+        Ok(())
+    }
+
+    #[test]
+    fn test_close_errors() -> Result<()> {
+        let mut account = program_account();
+        let infos = [account.account_info()];
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut close = Close::<AccountInfo>::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        let error = close.cleanup_accounts((), &mut ctx).unwrap_err();
+        assert!(error.is(ErrorCode::EmptyRecipientCache));
+
+        let itself = Mut::<AccountInfo>::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        let error = close.cleanup_accounts(&itself, &mut ctx).unwrap_err();
+        assert_eq!(ProgramError::from(error), ProgramError::InvalidArgument);
+        assert_eq!(account.lamports(), 5_000);
+
+        let mut recipient = wallet();
+        let mut not_owned = TestAccount::new([3; 32], [9; 32], 5_000, 0).writable();
+        let error = run::<CloseAccounts>(&[recipient.account_info(), not_owned.account_info()])
+            .unwrap_err();
+        assert_eq!(ProgramError::from(error), ProgramError::IllegalOwner);
+        assert_eq!(not_owned.lamports(), 5_000);
+
+        let mut read_only = TestAccount::new([1; 32], PROGRAM_ID, 5_000, 16);
+        let error = run::<CloseAccounts>(&[recipient.account_info(), read_only.account_info()])
+            .unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedWritable));
+        Ok(())
+    }
+}
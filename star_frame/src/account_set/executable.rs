@@ -0,0 +1,21 @@
+//! Account set modifier that checks the account is an executable program.
+
+use crate::prelude::*;
+use derive_more::{Deref, DerefMut};
+
+/// A modifier that checks the account is an executable program, for programs that aren't known ahead of time, like
+/// the target of a CPI chosen by the caller. Use [`Program`] to check for a specific program.
+///
+/// Decoding, validation, and cleanup arguments are passed through to `T`.
+#[derive(AccountSet, Debug, Deref, DerefMut, Clone, Copy)]
+#[decode(generics = [<A>], arg = A)]
+#[validate(generics = [<A>], arg = A, extra_validation = self.check_executable())]
+#[cleanup(generics = [<A>], arg = A)]
+#[repr(transparent)]
+pub struct Executable<T = AccountInfo>(
+    #[single_account_set]
+    #[decode(arg = arg)]
+    #[validate(arg = arg)]
+    #[cleanup(arg = arg)]
+    T,
+);
@@ -0,0 +1,525 @@
+//! Account set implementations for foreign types.
+
+use crate::{
+    account_set::{
+        AccountPath, AccountSetAccounts, AccountSetCleanup, AccountSetDecode, AccountSetValidate,
+        PathSegment,
+    },
+    prelude::*,
+    ErrorCode,
+};
+
+impl<'a> AccountSetDecode<'a, ()> for AccountInfo {
+    #[inline]
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        (): (),
+        _ctx: &mut Context,
+    ) -> Result<Self> {
+        let Some((account, rest)) = accounts.split_first() else {
+            bail!(
+                ProgramError::NotEnoughAccountKeys,
+                "Ran out of accounts to decode"
+            );
+        };
+        *accounts = rest;
+        Ok(*account)
+    }
+}
+
+impl AccountSetValidate<()> for AccountInfo {
+    #[inline]
+    fn validate_accounts(&mut self, (): (), _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AccountSetCleanup<()> for AccountInfo {
+    #[inline]
+    fn cleanup_accounts(&mut self, (): (), _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AccountSetAccounts for AccountInfo {
+    #[inline]
+    fn visit_accounts(
+        &self,
+        path: &AccountPath<'_>,
+        visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    ) {
+        visit(path, self, Self::meta().writable);
+    }
+}
+
+/// An empty account set, for instructions that don't take any accounts.
+impl<'a> AccountSetDecode<'a, ()> for () {
+    #[inline]
+    fn decode_accounts(
+        _accounts: &mut &'a [AccountInfo],
+        (): (),
+        _ctx: &mut Context,
+    ) -> Result<Self> {
+        Ok(())
+    }
+}
+
+impl AccountSetValidate<()> for () {
+    #[inline]
+    fn validate_accounts(&mut self, (): (), _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AccountSetCleanup<()> for () {
+    #[inline]
+    fn cleanup_accounts(&mut self, (): (), _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AccountSetAccounts for () {
+    #[inline]
+    fn visit_accounts(
+        &self,
+        _path: &AccountPath<'_>,
+        _visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    ) {
+    }
+}
+
+/// An optional account set. Passing the executing program's id in place of the set's accounts decodes it as `None`,
+/// like in Anchor.
+///
+/// A `None` set takes up a single account, even if `T` is made of several.
+impl<'a, T, A> AccountSetDecode<'a, A> for Option<T>
+where
+    T: AccountSetDecode<'a, A>,
+{
+    #[inline]
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        decode_input: A,
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        let Some((account, rest)) = accounts.split_first() else {
+            bail!(
+                ErrorCode::MissingOptionalProgram,
+                "Ran out of accounts to decode, pass the program id for `None`"
+            );
+        };
+        if account.key().fast_eq(ctx.current_program_id()) {
+            *accounts = rest;
+            return Ok(None);
+        }
+        T::decode_accounts(accounts, decode_input, ctx).map(Some)
+    }
+}
+
+impl<T, A> AccountSetValidate<A> for Option<T>
+where
+    T: AccountSetValidate<A>,
+{
+    #[inline]
+    fn validate_accounts(&mut self, validate_input: A, ctx: &mut Context) -> Result<()> {
+        match self {
+            Some(set) => set.validate_accounts(validate_input, ctx),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T, A> AccountSetCleanup<A> for Option<T>
+where
+    T: AccountSetCleanup<A>,
+{
+    #[inline]
+    fn cleanup_accounts(&mut self, cleanup_input: A, ctx: &mut Context) -> Result<()> {
+        match self {
+            Some(set) => set.cleanup_accounts(cleanup_input, ctx),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T> AccountSetAccounts for Option<T>
+where
+    T: AccountSetAccounts,
+{
+    #[inline]
+    fn visit_accounts(
+        &self,
+        path: &AccountPath<'_>,
+        visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    ) {
+        if let Some(set) = self {
+            set.visit_accounts(path, visit);
+        }
+    }
+
+    #[inline]
+    fn prefix_path(&mut self, segment: PathSegment) {
+        if let Some(set) = self {
+            set.prefix_path(segment);
+        }
+    }
+}
+
+/// Decodes `len` account sets, each with a clone of `decode_input` and its index added to its path.
+pub(crate) fn decode_each<'a, 'b, T, A>(
+    accounts: &'b mut &'a [AccountInfo],
+    len: usize,
+    decode_input: &'b A,
+    ctx: &'b mut Context,
+) -> impl Iterator<Item = Result<T>> + use<'a, 'b, T, A>
+where
+    T: AccountSetDecode<'a, A> + AccountSetAccounts,
+    A: Clone,
+{
+    (0..len).map(move |index| {
+        let mut set = T::decode_accounts(accounts, decode_input.clone(), ctx)
+            .with_ctx(|| format!("Account set at index {index}"))?;
+        set.prefix_path(PathSegment::Index(index));
+        Ok(set)
+    })
+}
+
+/// Validates each account set with a clone of `validate_input`.
+pub(crate) fn validate_each<T, A>(
+    sets: &mut [T],
+    validate_input: &A,
+    ctx: &mut Context,
+) -> Result<()>
+where
+    T: AccountSetValidate<A>,
+    A: Clone,
+{
+    for (index, set) in sets.iter_mut().enumerate() {
+        set.validate_accounts(validate_input.clone(), ctx)
+            .with_ctx(|| format!("Account set at index {index}"))?;
+    }
+    Ok(())
+}
+
+/// Cleans up each account set with a clone of `cleanup_input`.
+pub(crate) fn cleanup_each<T, A>(sets: &mut [T], cleanup_input: &A, ctx: &mut Context) -> Result<()>
+where
+    T: AccountSetCleanup<A>,
+    A: Clone,
+{
+    for (index, set) in sets.iter_mut().enumerate() {
+        set.cleanup_accounts(cleanup_input.clone(), ctx)
+            .with_ctx(|| format!("Account set at index {index}"))?;
+    }
+    Ok(())
+}
+
+/// Visits the accounts of each account set, with its index added to the path.
+pub(crate) fn visit_each<T>(
+    sets: &[T],
+    path: &AccountPath<'_>,
+    visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+) where
+    T: AccountSetAccounts,
+{
+    for (index, set) in sets.iter().enumerate() {
+        set.visit_accounts(&path.join(PathSegment::Index(index)), visit);
+    }
+}
+
+/// Adds `segment` to the paths of each account set.
+pub(crate) fn prefix_each<T>(sets: &mut [T], segment: PathSegment)
+where
+    T: AccountSetAccounts,
+{
+    for set in sets {
+        set.prefix_path(segment);
+    }
+}
+
+/// A variable number of account sets, decoded with the number of sets to take, like a count from the instruction
+/// data.
+///
+/// Decoding with `(usize, A)` decodes each set with a clone of `A`. Validation and cleanup pass a clone of their
+/// argument to each set.
+impl<'a, T> AccountSetDecode<'a, usize> for Vec<T>
+where
+    T: AccountSetDecode<'a, ()> + AccountSetAccounts,
+{
+    #[inline]
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        len: usize,
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        decode_each(accounts, len, &(), ctx).collect()
+    }
+}
+
+impl<'a, T, A> AccountSetDecode<'a, (usize, A)> for Vec<T>
+where
+    T: AccountSetDecode<'a, A> + AccountSetAccounts,
+    A: Clone,
+{
+    #[inline]
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        (len, decode_input): (usize, A),
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        decode_each(accounts, len, &decode_input, ctx).collect()
+    }
+}
+
+impl<T, A> AccountSetValidate<A> for Vec<T>
+where
+    T: AccountSetValidate<A>,
+    A: Clone,
+{
+    #[inline]
+    fn validate_accounts(&mut self, validate_input: A, ctx: &mut Context) -> Result<()> {
+        validate_each(self, &validate_input, ctx)
+    }
+}
+
+impl<T, A> AccountSetCleanup<A> for Vec<T>
+where
+    T: AccountSetCleanup<A>,
+    A: Clone,
+{
+    #[inline]
+    fn cleanup_accounts(&mut self, cleanup_input: A, ctx: &mut Context) -> Result<()> {
+        cleanup_each(self, &cleanup_input, ctx)
+    }
+}
+
+impl<T> AccountSetAccounts for Vec<T>
+where
+    T: AccountSetAccounts,
+{
+    #[inline]
+    fn visit_accounts(
+        &self,
+        path: &AccountPath<'_>,
+        visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    ) {
+        visit_each(self, path, visit);
+    }
+
+    #[inline]
+    fn prefix_path(&mut self, segment: PathSegment) {
+        prefix_each(self, segment);
+    }
+}
+
+/// A fixed number of account sets, each decoded, validated, and cleaned up with a clone of the argument.
+impl<'a, T, A, const N: usize> AccountSetDecode<'a, A> for [T; N]
+where
+    T: AccountSetDecode<'a, A> + AccountSetAccounts,
+    A: Clone,
+{
+    #[inline]
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        decode_input: A,
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        // Collected without allocating, so the sets after an error are left as `None`.
+        let mut sets = decode_each(accounts, N, &decode_input, ctx);
+        let mut error = None;
+        let decoded: [Option<T>; N] = std::array::from_fn(|_| match sets.next()? {
+            Ok(set) => Some(set),
+            Err(decode_error) => {
+                error.get_or_insert(decode_error);
+                None
+            }
+        });
+        if let Some(error) = error {
+            return Err(error);
+        }
+        Ok(decoded.map(|set| set.expect("Every set was decoded")))
+    }
+}
+
+impl<T, A, const N: usize> AccountSetValidate<A> for [T; N]
+where
+    T: AccountSetValidate<A>,
+    A: Clone,
+{
+    #[inline]
+    fn validate_accounts(&mut self, validate_input: A, ctx: &mut Context) -> Result<()> {
+        validate_each(self, &validate_input, ctx)
+    }
+}
+
+impl<T, A, const N: usize> AccountSetCleanup<A> for [T; N]
+where
+    T: AccountSetCleanup<A>,
+    A: Clone,
+{
+    #[inline]
+    fn cleanup_accounts(&mut self, cleanup_input: A, ctx: &mut Context) -> Result<()> {
+        cleanup_each(self, &cleanup_input, ctx)
+    }
+}
+
+impl<T, const N: usize> AccountSetAccounts for [T; N]
+where
+    T: AccountSetAccounts,
+{
+    #[inline]
+    fn visit_accounts(
+        &self,
+        path: &AccountPath<'_>,
+        visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    ) {
+        visit_each(self, path, visit);
+    }
+
+    #[inline]
+    fn prefix_path(&mut self, segment: PathSegment) {
+        prefix_each(self, segment);
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use crate::idl::AccountSetToIdl;
+    use star_frame_idl::{
+        account_set::{IdlAccountSetDef, IdlSingleAccountSet},
+        IdlDefinition,
+    };
+
+    impl AccountSetToIdl<()> for AccountInfo {
+        fn account_set_to_idl(
+            _idl_definition: &mut IdlDefinition,
+            (): (),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            Ok(IdlAccountSetDef::Single(IdlSingleAccountSet::default()))
+        }
+    }
+
+    impl AccountSetToIdl<()> for () {
+        fn account_set_to_idl(
+            _idl_definition: &mut IdlDefinition,
+            (): (),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            Ok(IdlAccountSetDef::Struct(vec![]))
+        }
+    }
+
+    impl<T, A> AccountSetToIdl<A> for Option<T>
+    where
+        T: AccountSetToIdl<A>,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: A,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            let mut set = T::account_set_to_idl(idl_definition, arg)?;
+            if let IdlAccountSetDef::Single(single) = &mut set {
+                single.optional = true;
+                return Ok(set);
+            }
+            // A `None` set is the program id in place of all of the set's accounts.
+            let none = IdlSingleAccountSet {
+                optional: true,
+                ..IdlSingleAccountSet::default()
+            };
+            Ok(IdlAccountSetDef::Or(vec![
+                set,
+                IdlAccountSetDef::Single(none),
+            ]))
+        }
+    }
+
+    impl<T, A> AccountSetToIdl<A> for Vec<T>
+    where
+        T: AccountSetToIdl<A>,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: A,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            Ok(IdlAccountSetDef::Many {
+                account_set: Box::new(T::account_set_to_idl(idl_definition, arg)?),
+                min: 0,
+                max: None,
+            })
+        }
+    }
+
+    impl<T, A, const N: usize> AccountSetToIdl<A> for [T; N]
+    where
+        T: AccountSetToIdl<A>,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: A,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            Ok(IdlAccountSetDef::Many {
+                account_set: Box::new(T::account_set_to_idl(idl_definition, arg)?),
+                min: N,
+                max: Some(N),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestAccount;
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+
+    #[derive(AccountSet, Debug)]
+    struct OptionalAccounts {
+        optional: Option<Mut<SystemAccount>>,
+        after: AccountInfo,
+    }
+
+    fn decode_and_validate(accounts: &mut [TestAccount]) -> Result<OptionalAccounts> {
+        let infos: Vec<_> = accounts.iter_mut().map(TestAccount::account_info).collect();
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = OptionalAccounts::decode_accounts(&mut infos.as_slice(), (), &mut ctx)?;
+        set.validate_accounts((), &mut ctx)?;
+        set.cleanup_accounts((), &mut ctx)?;
+        Ok(set)
+    }
+
+    fn wallet(key: u8) -> TestAccount {
+        TestAccount::new([key; 32], pinocchio_system::ID, 1_000, 0)
+    }
+
+    #[test]
+    fn test_some() -> Result<()> {
+        let mut accounts = [wallet(1).writable(), wallet(2)];
+        let set = decode_and_validate(&mut accounts)?;
+        assert_eq!(set.optional.unwrap().pubkey(), &[1; 32]);
+        assert_eq!(set.after.key(), &[2; 32]);
+
+        let error = decode_and_validate(&mut [wallet(1), wallet(2)]).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedWritable));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: optional"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_none() -> Result<()> {
+        let program = TestAccount::new(PROGRAM_ID, [0; 32], 1, 0).executable();
+        let mut accounts = [program, wallet(2)];
+        let set = decode_and_validate(&mut accounts)?;
+        assert!(set.optional.is_none());
+        assert_eq!(set.after.key(), &[2; 32]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_sentinel() {
+        let error = decode_and_validate(&mut []).unwrap_err();
+        assert!(error.is(ErrorCode::MissingOptionalProgram));
+    }
+}
@@ -0,0 +1,301 @@
+//! Account set that defers decoding and validating an account until it's first used.
+
+use crate::{
+    account_set::{AccountPath, AccountSetAccounts, PathSegment, SingleSetMeta},
+    prelude::*,
+};
+
+/// An account that is only decoded and validated as a `T` the first time it's accessed with [`Lazy::get`].
+///
+/// Decoding takes one account without borrowing its data, and validation does nothing, so instructions with many
+/// accounts that only some branches use don't pay for checking the rest. Errors from the first access are annotated
+/// with the path to the `Lazy`, like `hops[2].vault`, which its parent sets add with
+/// [`AccountSetAccounts::prefix_path`] as they're decoded.
+///
+/// Cleanup only cleans up `T` if it was loaded.
+#[derive(Debug, Clone, Copy)]
+pub struct Lazy<T> {
+    account: AccountInfo,
+    set: Option<T>,
+    account_path: LazyPath,
+}
+
+/// The most path segments a [`Lazy`] keeps. Deeper paths keep the innermost segments.
+#[cfg(not(feature = "compact_errors"))]
+const MAX_LAZY_PATH: usize = 8;
+
+/// The path to a [`Lazy`], innermost segment first like an [`Error`]'s. Kept inline so decoding doesn't allocate, and
+/// not kept at all with the `compact_errors` feature, whose errors don't have paths.
+#[derive(Debug, Clone, Copy, Default)]
+struct LazyPath {
+    #[cfg(not(feature = "compact_errors"))]
+    segments: [Option<PathSegment>; MAX_LAZY_PATH],
+}
+
+// Without paths to keep, the methods only stand in for the real ones.
+#[cfg_attr(
+    feature = "compact_errors",
+    allow(clippy::unused_self, clippy::trivially_copy_pass_by_ref)
+)]
+impl LazyPath {
+    fn push(&mut self, segment: PathSegment) {
+        #[cfg(not(feature = "compact_errors"))]
+        if let Some(slot) = self.segments.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(segment);
+        }
+        #[cfg(feature = "compact_errors")]
+        let _ = segment;
+    }
+
+    fn annotate<T>(&self, result: Result<T>) -> Result<T> {
+        #[cfg(not(feature = "compact_errors"))]
+        let result = self
+            .segments
+            .iter()
+            .flatten()
+            .fold(result, |result, segment| result.account_path(*segment));
+        result
+    }
+}
+
+impl<T> Lazy<T>
+where
+    T: SingleAccountSet,
+{
+    /// Decodes and validates the account as a `T` with `()` arguments if it hasn't been yet.
+    pub fn get(&mut self, ctx: &mut Context) -> Result<&mut T>
+    where
+        T: for<'a> AccountSetDecode<'a, ()> + AccountSetValidate<()>,
+    {
+        self.get_with((), (), ctx)
+    }
+
+    /// Decodes and validates the account as a `T` with the given arguments if it hasn't been yet.
+    ///
+    /// The arguments are ignored once `T` is loaded.
+    pub fn get_with<D, V>(
+        &mut self,
+        decode_arg: D,
+        validate_arg: V,
+        ctx: &mut Context,
+    ) -> Result<&mut T>
+    where
+        T: for<'a> AccountSetDecode<'a, D> + AccountSetValidate<V>,
+    {
+        let set = match self.set.take() {
+            Some(set) => set,
+            None => self.load(decode_arg, validate_arg, ctx)?,
+        };
+        Ok(self.set.insert(set))
+    }
+
+    fn load<D, V>(&self, decode_arg: D, validate_arg: V, ctx: &mut Context) -> Result<T>
+    where
+        T: for<'a> AccountSetDecode<'a, D> + AccountSetValidate<V>,
+    {
+        let accounts = [self.account];
+        let loaded = T::decode_accounts(&mut &accounts[..], decode_arg, ctx)
+            .and_then(|mut set| {
+                set.validate_accounts(validate_arg, ctx)?;
+                Ok(set)
+            })
+            .ctx("Failed to lazily load account");
+        self.account_path.annotate(loaded)
+    }
+}
+
+impl<T> Lazy<T> {
+    /// Whether `T` has been loaded.
+    #[must_use]
+    pub fn is_loaded(&self) -> bool {
+        self.set.is_some()
+    }
+
+    /// `T`, if it has been loaded.
+    #[must_use]
+    pub fn loaded(&self) -> Option<&T> {
+        self.set.as_ref()
+    }
+}
+
+impl<T> SingleAccountSet for Lazy<T>
+where
+    T: SingleAccountSet,
+{
+    fn meta() -> SingleSetMeta {
+        T::meta()
+    }
+
+    fn account_info(&self) -> &AccountInfo {
+        &self.account
+    }
+}
+
+impl<'a, T> AccountSetDecode<'a, ()> for Lazy<T> {
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        (): (),
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        Ok(Self {
+            account: AccountInfo::decode_accounts(accounts, (), ctx)?,
+            set: None,
+            account_path: LazyPath::default(),
+        })
+    }
+}
+
+impl<T> AccountSetValidate<()> for Lazy<T> {
+    fn validate_accounts(&mut self, (): (), _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<T, A> AccountSetCleanup<A> for Lazy<T>
+where
+    T: AccountSetCleanup<A>,
+{
+    fn cleanup_accounts(&mut self, arg: A, ctx: &mut Context) -> Result<()> {
+        match &mut self.set {
+            Some(set) => set.cleanup_accounts(arg, ctx),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T> AccountSetAccounts for Lazy<T>
+where
+    T: SingleAccountSet,
+{
+    fn visit_accounts(
+        &self,
+        path: &AccountPath<'_>,
+        visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    ) {
+        visit(path, &self.account, Self::meta().writable);
+    }
+
+    fn prefix_path(&mut self, segment: PathSegment) {
+        self.account_path.push(segment);
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use crate::idl::AccountSetToIdl;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<T, A> AccountSetToIdl<A> for Lazy<T>
+    where
+        T: AccountSetToIdl<A>,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: A,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            T::account_set_to_idl(idl_definition, arg)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_helpers::TestAccount, ErrorCode};
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+
+    #[derive(AccountSet, Debug)]
+    struct Withdraw {
+        authority: Signer,
+        vault: Lazy<Mut<AccountInfo>>,
+        config: Lazy<Signer>,
+    }
+
+    #[test]
+    fn test_lazy() -> Result<()> {
+        let mut accounts = [
+            TestAccount::new([1; 32], pinocchio_system::ID, 1_000, 0).signer(),
+            TestAccount::new([2; 32], PROGRAM_ID, 1_000, 0).writable(),
+            TestAccount::new([3; 32], PROGRAM_ID, 1_000, 0),
+        ];
+        let infos = accounts.each_mut().map(TestAccount::account_info);
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = Withdraw::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        // The unsigned config isn't checked until it's used.
+        set.validate_accounts((), &mut ctx)?;
+        assert!(!set.vault.is_loaded());
+        assert_eq!(set.config.pubkey(), &[3; 32]);
+
+        assert_eq!(set.vault.get(&mut ctx)?.pubkey(), &[2; 32]);
+        assert!(set.vault.is_loaded());
+        assert!(set.vault.loaded().is_some());
+
+        let error = set.config.get(&mut ctx).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedSigner));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: config"));
+        assert!(!set.config.is_loaded());
+
+        set.cleanup_accounts((), &mut ctx)?;
+        Ok(())
+    }
+
+    #[derive(AccountSet, Debug)]
+    struct Hop {
+        pool: Lazy<Signer>,
+    }
+
+    #[derive(AccountSet, Debug)]
+    struct Route {
+        payer: Signer,
+        first: Hop,
+        hops: Many<Hop>,
+    }
+
+    #[test]
+    fn test_nested_lazy_path() -> Result<()> {
+        let mut accounts = [
+            TestAccount::new([1; 32], pinocchio_system::ID, 1_000, 0).signer(),
+            TestAccount::new([2; 32], PROGRAM_ID, 1_000, 0),
+            TestAccount::new([3; 32], PROGRAM_ID, 1_000, 0).signer(),
+            TestAccount::new([4; 32], PROGRAM_ID, 1_000, 0).signer(),
+            TestAccount::new([5; 32], PROGRAM_ID, 1_000, 0),
+        ];
+        let infos = accounts.each_mut().map(TestAccount::account_info);
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = Route::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.validate_accounts((), &mut ctx)?;
+
+        let error = set.first.pool.get(&mut ctx).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedSigner));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: first.pool\n"));
+
+        assert_eq!(set.hops[1].pool.get(&mut ctx)?.pubkey(), &[4; 32]);
+        let error = set.hops[2].pool.get(&mut ctx).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedSigner));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: hops[2].pool\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_visit_lazy() -> Result<()> {
+        let mut accounts = [
+            TestAccount::new([1; 32], pinocchio_system::ID, 1_000, 0).signer(),
+            TestAccount::new([2; 32], PROGRAM_ID, 1_000, 0).writable(),
+            TestAccount::new([3; 32], PROGRAM_ID, 1_000, 0),
+        ];
+        let infos = accounts.each_mut().map(TestAccount::account_info);
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let set = Withdraw::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        let mut keys = vec![];
+        set.visit_accounts(&AccountPath::ROOT, &mut |_, account, _| {
+            keys.push(*account.key());
+        });
+        assert_eq!(keys, [[1; 32], [2; 32], [3; 32]]);
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+//! Account set that takes all of the remaining accounts.
+
+use crate::{
+    account_set::{
+        impls::{cleanup_each, prefix_each, validate_each, visit_each},
+        AccountPath, AccountSetAccounts, PathSegment,
+    },
+    prelude::*,
+};
+use derive_more::{Deref, DerefMut};
+
+/// Account sets made from all of the remaining accounts, like the accounts for each hop of a swap.
+///
+/// Every element is decoded, validated, and cleaned up with a clone of the argument. Since `Many` takes every account
+/// left, it must be the last field of an [`AccountSet`](derive@crate::account_set::AccountSet), which the derive
+/// checks. Use a `Vec<T>` decoded with a count to take a number of accounts from the middle of a set instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deref, DerefMut)]
+pub struct Many<T>(Vec<T>);
+
+impl<T> Many<T> {
+    /// The decoded account sets.
+    #[must_use]
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<'a, T, A> AccountSetDecode<'a, A> for Many<T>
+where
+    T: AccountSetDecode<'a, A> + AccountSetAccounts,
+    A: Clone,
+{
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        decode_input: A,
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        let mut sets = vec![];
+        while !accounts.is_empty() {
+            let remaining = accounts.len();
+            let index = sets.len();
+            let mut set = T::decode_accounts(accounts, decode_input.clone(), ctx)
+                .with_ctx(|| format!("Account set at index {index}"))?;
+            set.prefix_path(PathSegment::Index(index));
+            ensure!(
+                accounts.len() < remaining,
+                ProgramError::InvalidArgument,
+                "Account sets in `Many` must take at least one account"
+            );
+            sets.push(set);
+        }
+        Ok(Self(sets))
+    }
+}
+
+impl<T, A> AccountSetValidate<A> for Many<T>
+where
+    T: AccountSetValidate<A>,
+    A: Clone,
+{
+    fn validate_accounts(&mut self, validate_input: A, ctx: &mut Context) -> Result<()> {
+        validate_each(self, &validate_input, ctx)
+    }
+}
+
+impl<T, A> AccountSetCleanup<A> for Many<T>
+where
+    T: AccountSetCleanup<A>,
+    A: Clone,
+{
+    fn cleanup_accounts(&mut self, cleanup_input: A, ctx: &mut Context) -> Result<()> {
+        cleanup_each(self, &cleanup_input, ctx)
+    }
+}
+
+impl<T> AccountSetAccounts for Many<T>
+where
+    T: AccountSetAccounts,
+{
+    fn visit_accounts(
+        &self,
+        path: &AccountPath<'_>,
+        visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    ) {
+        visit_each(self, path, visit);
+    }
+
+    fn prefix_path(&mut self, segment: PathSegment) {
+        prefix_each(self, segment);
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use crate::idl::AccountSetToIdl;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<T, A> AccountSetToIdl<A> for Many<T>
+    where
+        T: AccountSetToIdl<A>,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: A,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            Ok(IdlAccountSetDef::Many {
+                account_set: Box::new(T::account_set_to_idl(idl_definition, arg)?),
+                min: 0,
+                max: None,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_helpers::TestAccount, ErrorCode};
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+
+    #[derive(AccountSet, Debug)]
+    #[decode(arg = usize)]
+    struct Payouts {
+        authority: Signer,
+        pair: [AccountInfo; 2],
+        #[decode(arg = arg)]
+        counted: Vec<Mut<SystemAccount>>,
+        rest: Many<Mut<SystemAccount>>,
+    }
+
+    fn wallet(key: u8) -> TestAccount {
+        TestAccount::new([key; 32], pinocchio_system::ID, 1_000, 0).writable()
+    }
+
+    fn run(accounts: &mut [TestAccount], count: usize) -> Result<Payouts> {
+        let infos: Vec<_> = accounts.iter_mut().map(TestAccount::account_info).collect();
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = Payouts::decode_accounts(&mut infos.as_slice(), count, &mut ctx)?;
+        set.validate_accounts((), &mut ctx)?;
+        set.cleanup_accounts((), &mut ctx)?;
+        Ok(set)
+    }
+
+    #[test]
+    fn test_decode_counts() -> Result<()> {
+        let mut accounts: Vec<_> = (1..=7).map(wallet).collect();
+        accounts[0] = wallet(1).signer();
+        let set = run(&mut accounts, 2)?;
+        assert_eq!(set.pair.map(|info| info.key()[0]), [2, 3]);
+        assert_eq!(set.counted.len(), 2);
+        assert_eq!(set.counted[1].pubkey(), &[5; 32]);
+        let rest: Vec<_> = set.rest.iter().map(|set| set.pubkey()[0]).collect();
+        assert_eq!(rest, [6, 7]);
+
+        let set = run(&mut accounts, 4)?;
+        assert!(set.rest.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut accounts: Vec<_> = (1..=7).map(wallet).collect();
+        accounts[0] = wallet(1).signer();
+        let error = run(&mut accounts, 5).unwrap_err();
+        assert_eq!(
+            ProgramError::from(error),
+            ProgramError::NotEnoughAccountKeys
+        );
+
+        accounts[6] = TestAccount::new([7; 32], pinocchio_system::ID, 1_000, 0);
+        let error = run(&mut accounts, 2).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedWritable));
+        #[cfg(not(feature = "compact_errors"))]
+        {
+            let message = error.to_string();
+            assert!(message.contains("For account: rest"));
+            assert!(message.contains("Account set at index 1"));
+        }
+
+        let error = run(&mut accounts[..2], 0).unwrap_err();
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: pair"));
+        assert_eq!(
+            ProgramError::from(error),
+            ProgramError::NotEnoughAccountKeys
+        );
+    }
+}
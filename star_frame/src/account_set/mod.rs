@@ -0,0 +1,782 @@
+//! Account sets, the accounts an instruction takes in.
+//!
+//! An account set goes through three steps while an instruction is processed. It is decoded from the accounts passed
+//! to the program with [`AccountSetDecode`], validated with [`AccountSetValidate`], and cleaned up after the instruction
+//! runs with [`AccountSetCleanup`]. Each step takes an argument, which lets the same set be decoded, validated, or
+//! cleaned up in different ways.
+//!
+//! Account sets are usually made by deriving [`AccountSet`](derive@AccountSet) on a struct of other account sets.
+//! Sets that wrap a single account, like [`Signer`], [`Mut`], and [`Close`], implement [`SingleAccountSet`] and the
+//! marker traits in [`modifiers`].
+
+pub mod account;
+#[cfg(not(target_os = "solana"))]
+pub mod client;
+pub mod close;
+pub mod executable;
+pub mod lazy;
+pub mod many;
+pub mod modifiers;
+pub mod multisig;
+pub mod mutable;
+#[cfg(not(target_os = "solana"))]
+pub mod pda_resolver;
+pub mod program;
+pub mod program_data;
+pub mod resize;
+pub mod seeded;
+pub mod signer;
+pub mod single_set;
+pub mod system_account;
+pub mod with_seed;
+
+mod impls;
+
+pub use account::{Account, ProgramAccount};
+pub use close::Close;
+pub use executable::Executable;
+pub use lazy::Lazy;
+pub use many::Many;
+pub use multisig::MultisigSigners;
+pub use mutable::Mut;
+pub use program::Program;
+pub use program_data::{ProgramData, UpgradeAuthority};
+pub use resize::Resize;
+pub use signer::Signer;
+pub use single_set::{SingleAccountSet, SingleSetMeta};
+pub use star_frame_proc::AccountSet;
+pub use system_account::SystemAccount;
+
+use crate::{
+    account_set::{modifiers::SignedAccount, seeded::SignerSeeds},
+    prelude::*,
+    ErrorCode,
+};
+use modifiers::WritableAccount;
+use std::fmt::Debug;
+
+/// Decodes an account set from the accounts passed to an instruction.
+///
+/// Derivable with [`AccountSet`](derive@AccountSet).
+pub trait AccountSetDecode<'a, A>: Sized {
+    /// Decodes the set from the start of `accounts`, advancing the slice past the accounts it used.
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        decode_input: A,
+        ctx: &mut Context,
+    ) -> Result<Self>;
+}
+
+/// Validates a decoded account set before the instruction runs.
+///
+/// Derivable with [`AccountSet`](derive@AccountSet).
+pub trait AccountSetValidate<A> {
+    fn validate_accounts(&mut self, validate_input: A, ctx: &mut Context) -> Result<()>;
+}
+
+/// Cleans up an account set after the instruction runs.
+///
+/// Derivable with [`AccountSet`](derive@AccountSet).
+pub trait AccountSetCleanup<A> {
+    fn cleanup_accounts(&mut self, cleanup_input: A, ctx: &mut Context) -> Result<()>;
+}
+
+/// A step in the path to an account within an account set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A struct field, by name or tuple index.
+    Field(&'static str),
+    /// An element of a `Vec`, array, or [`Many`].
+    Index(usize),
+}
+
+/// The path to an account set, passed to [`AccountSetAccounts::visit_accounts`].
+///
+/// Each segment lives on the stack of the set that joined it, so visiting an account set doesn't allocate.
+#[derive(Debug, Clone, Copy)]
+pub struct AccountPath<'a> {
+    parent: Option<&'a AccountPath<'a>>,
+    segment: Option<PathSegment>,
+}
+
+impl AccountPath<'static> {
+    /// The empty path, to the set being visited.
+    pub const ROOT: Self = Self {
+        parent: None,
+        segment: None,
+    };
+}
+
+impl AccountPath<'_> {
+    /// The path to a set within this one.
+    #[must_use]
+    pub fn join(&self, segment: PathSegment) -> AccountPath<'_> {
+        AccountPath {
+            parent: Some(self),
+            segment: Some(segment),
+        }
+    }
+
+    /// The segments of the path, from outermost to innermost.
+    #[must_use]
+    pub fn segments(&self) -> Vec<PathSegment> {
+        let mut segments = vec![];
+        let mut path = Some(self);
+        while let Some(AccountPath { parent, segment }) = path {
+            segments.extend(*segment);
+            path = *parent;
+        }
+        segments.reverse();
+        segments
+    }
+}
+
+/// Lists the accounts in an account set along with their paths, which [`check_duplicate_writable`] uses to find
+/// accounts passed in more than once.
+///
+/// Derivable with [`AccountSet`](derive@AccountSet). Fields marked `#[account_set(allow_duplicate)]` are left out.
+pub trait AccountSetAccounts {
+    /// Calls `visit` with each account in the set, its path joined onto `path`, and whether its account set declares
+    /// it writable through [`SingleSetMeta::writable`].
+    fn visit_accounts(
+        &self,
+        path: &AccountPath<'_>,
+        visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    );
+
+    /// Adds `segment` to the front of the path of each account in the set, for sets like [`Lazy`] that report errors
+    /// after decoding. Called once by the parent set after decoding this one, and passed on to the sets inside it.
+    #[inline]
+    fn prefix_path(&mut self, _segment: PathSegment) {}
+}
+
+/// Formats a path like `hops[1].pool`, from outermost to innermost segment.
+pub(crate) fn format_path<'a>(path: impl IntoIterator<Item = &'a PathSegment>) -> String {
+    path.into_iter()
+        .enumerate()
+        .map(|(index, segment)| match segment {
+            PathSegment::Field(name) if index == 0 => (*name).to_string(),
+            PathSegment::Field(name) => format!(".{name}"),
+            PathSegment::Index(index) => format!("[{index}]"),
+        })
+        .collect()
+}
+
+/// Checks that no account declared writable in `set`, like through [`Mut`], was passed in more than once.
+///
+/// The entrypoint hands out the same account for each copy of a duplicated key, so two writable fields with the same
+/// key would silently alias each other. Read-only fields are left out even if the transaction marks their account
+/// writable, so a read-only field can share a key with a writable one. Run during validation by
+/// `#[account_set(check_duplicates)]`.
+pub fn check_duplicate_writable(set: &(impl AccountSetAccounts + ?Sized)) -> Result<()> {
+    // Compares each writable account against the ones before it, so the happy path doesn't allocate.
+    let mut duplicate = None;
+    let mut second = 0;
+    set.visit_accounts(&AccountPath::ROOT, &mut |_, account, writable| {
+        if !writable || duplicate.is_some() {
+            return;
+        }
+        let mut first = 0;
+        set.visit_accounts(&AccountPath::ROOT, &mut |_, other, writable| {
+            if writable && first < second && duplicate.is_none() {
+                if other.key().fast_eq(account.key()) {
+                    duplicate = Some((first, second, *account.key()));
+                }
+                first += 1;
+            }
+        });
+        second += 1;
+    });
+    let Some((first, second, key)) = duplicate else {
+        return Ok(());
+    };
+    bail!(
+        ErrorCode::DuplicateWritableAccount,
+        "Writable account `{}` was passed in as both `{}` and `{}`",
+        key.display(),
+        writable_path(set, first),
+        writable_path(set, second)
+    );
+}
+
+/// The path to the `index`th writable account in `set`, for [`check_duplicate_writable`]'s error.
+fn writable_path(set: &(impl AccountSetAccounts + ?Sized), index: usize) -> String {
+    let mut path = String::new();
+    let mut current = 0;
+    set.visit_accounts(&AccountPath::ROOT, &mut |account_path, _, writable| {
+        if writable {
+            if current == index {
+                path = format_path(&account_path.segments());
+            }
+            current += 1;
+        }
+    });
+    path
+}
+
+/// An account that can receive lamports, like the [`Context`] recipient.
+///
+/// Implemented for every [`WritableAccount`].
+pub trait CanAddLamports: SingleAccountSet + Debug {
+    /// The account whose lamports are modified.
+    fn account_to_modify(&self) -> AccountInfo;
+
+    /// Adds `lamports` directly to the account's balance. The lamports must have been taken from an account owned by
+    /// the current program, or the transaction fails.
+    fn add_lamports(&self, lamports: u64) -> Result<()> {
+        let account = self.account_to_modify();
+        let mut balance = account.try_borrow_mut_lamports()?;
+        *balance = balance
+            .checked_add(lamports)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// An account that can pay for rent, like the [`Context`] funder.
+///
+/// Implemented for every writable [`SignedAccount`].
+pub trait CanFundRent: CanAddLamports {
+    /// Whether the funder can pay for a system program `CreateAccount`, which requires a signer owned by the system
+    /// program.
+    fn can_create_account(&self) -> bool;
+
+    /// Moves `lamports` to `recipient` to pay for its rent.
+    fn fund_rent(
+        &self,
+        recipient: &dyn SingleAccountSet,
+        lamports: u64,
+        ctx: &Context,
+    ) -> Result<()>;
+
+    /// The seeds the funder signs with if it's a PDA.
+    fn signer_seeds(&self) -> Option<SignerSeeds<'_>>;
+}
+
+impl<T> CanAddLamports for T
+where
+    T: WritableAccount + Debug,
+{
+    fn account_to_modify(&self) -> AccountInfo {
+        *self.account_info()
+    }
+}
+
+impl<T> CanFundRent for T
+where
+    T: CanAddLamports + SignedAccount,
+{
+    fn can_create_account(&self) -> bool {
+        self.account_info().is_owned_by(&pinocchio_system::ID)
+    }
+
+    fn fund_rent(
+        &self,
+        recipient: &dyn SingleAccountSet,
+        lamports: u64,
+        ctx: &Context,
+    ) -> Result<()> {
+        let from = self.account_info();
+        let to = recipient.account_info();
+        if !from.is_owned_by(&pinocchio_system::ID) {
+            // Program owned accounts can't transfer through the system program, so the lamports are moved directly.
+            ensure!(
+                from.is_owned_by(ctx.current_program_id()),
+                ProgramError::IllegalOwner,
+                "Funder must be owned by the system program or the current program"
+            );
+            let mut from_lamports = from.try_borrow_mut_lamports()?;
+            *from_lamports = from_lamports
+                .checked_sub(lamports)
+                .ok_or(ProgramError::InsufficientFunds)?;
+            let mut to_lamports = to.try_borrow_mut_lamports()?;
+            *to_lamports = to_lamports
+                .checked_add(lamports)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            return Ok(());
+        }
+        let transfer = pinocchio_system::instructions::Transfer { from, to, lamports };
+        match SignedAccount::signer_seeds(self) {
+            Some(seeds) => seeds.with_signer(|signers| transfer.invoke_signed(signers)),
+            None => transfer.invoke(),
+        }
+        .cpi_err::<System>()
+    }
+
+    fn signer_seeds(&self) -> Option<SignerSeeds<'_>> {
+        SignedAccount::signer_seeds(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_helpers::TestAccount, ErrorCode};
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+
+    fn wallet(key: u8) -> TestAccount {
+        TestAccount::new([key; 32], pinocchio_system::ID, 1_000_000, 0)
+    }
+
+    #[derive(AccountSet, Debug)]
+    struct TransferAccounts {
+        #[validate(funder)]
+        authority: Mut<Signer>,
+        #[validate(recipient)]
+        destination: Mut<SystemAccount>,
+        #[validate(address = &pinocchio_system::ID)]
+        system_program: AccountInfo,
+        #[account_set(skip = 5)]
+        skipped: u8,
+    }
+
+    fn transfer_accounts() -> [TestAccount; 3] {
+        [
+            wallet(1).signer().writable(),
+            wallet(2).writable(),
+            TestAccount::new(pinocchio_system::ID, [0; 32], 1, 0).executable(),
+        ]
+    }
+
+    fn decode_and_validate(accounts: &mut [TestAccount]) -> Result<(TransferAccounts, Context)> {
+        let infos: Vec<_> = accounts.iter_mut().map(TestAccount::account_info).collect();
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut remaining = infos.as_slice();
+        let mut set = TransferAccounts::decode_accounts(&mut remaining, (), &mut ctx)?;
+        set.validate_accounts((), &mut ctx)?;
+        Ok((set, ctx))
+    }
+
+    #[test]
+    fn test_decode_in_order() -> Result<()> {
+        let mut accounts = transfer_accounts();
+        let mut infos: Vec<_> = accounts.iter_mut().map(TestAccount::account_info).collect();
+        let extra = wallet(3).account_info();
+        infos.push(extra);
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut remaining = infos.as_slice();
+        let set = TransferAccounts::decode_accounts(&mut remaining, (), &mut ctx)?;
+        assert_eq!(set.authority.pubkey(), &[1; 32]);
+        assert_eq!(set.destination.pubkey(), &[2; 32]);
+        assert_eq!(set.system_program.key(), &pinocchio_system::ID);
+        assert_eq!(set.skipped, 5);
+        assert_eq!(remaining.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_not_enough_accounts() {
+        let mut accounts = transfer_accounts();
+        let infos: Vec<_> = accounts[..2]
+            .iter_mut()
+            .map(TestAccount::account_info)
+            .collect();
+        let mut remaining = infos.as_slice();
+        let error =
+            TransferAccounts::decode_accounts(&mut remaining, (), &mut Context::new(&PROGRAM_ID))
+                .unwrap_err();
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: system_program"));
+        assert_eq!(
+            ProgramError::from(error),
+            ProgramError::NotEnoughAccountKeys
+        );
+    }
+
+    #[test]
+    fn test_validate_sets_funder_and_recipient() -> Result<()> {
+        let mut accounts = transfer_accounts();
+        let (_set, ctx) = decode_and_validate(&mut accounts)?;
+        assert_eq!(
+            ctx.get_funder().unwrap().account_to_modify().key(),
+            &[1; 32]
+        );
+        assert_eq!(
+            ctx.get_recipient().unwrap().account_to_modify().key(),
+            &[2; 32]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_signer_and_writable() {
+        let mut accounts = transfer_accounts();
+        accounts[0] = wallet(1).writable();
+        let error = decode_and_validate(&mut accounts).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedSigner));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: authority"));
+
+        let mut accounts = transfer_accounts();
+        accounts[1] = wallet(2);
+        let error = decode_and_validate(&mut accounts).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedWritable));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: destination"));
+    }
+
+    #[test]
+    fn test_validate_owner_and_address() {
+        let mut accounts = transfer_accounts();
+        accounts[1] = TestAccount::new([2; 32], PROGRAM_ID, 1_000_000, 0).writable();
+        let error = decode_and_validate(&mut accounts).unwrap_err();
+        assert_eq!(ProgramError::from(error), ProgramError::IllegalOwner);
+
+        let mut accounts = transfer_accounts();
+        accounts[2] = wallet(3);
+        let error = decode_and_validate(&mut accounts).unwrap_err();
+        assert!(error.is(ErrorCode::AddressMismatch));
+    }
+
+    #[derive(AccountSet, Debug)]
+    #[validate(id = "authority", arg = Pubkey, extra_validation = self.check_authority(&arg))]
+    #[cleanup(arg = u64, extra_cleanup = self.check_count(arg))]
+    struct WithIds {
+        #[validate(id = "authority", skip)]
+        authority: Signer,
+        program: Program<TestProgram>,
+    }
+
+    impl WithIds {
+        fn check_authority(&self, expected: &Pubkey) -> Result<()> {
+            ensure!(
+                self.authority.pubkey().fast_eq(expected),
+                ProgramError::InvalidArgument
+            );
+            Ok(())
+        }
+
+        #[allow(clippy::unused_self)]
+        fn check_count(&self, count: u64) -> Result<()> {
+            ensure!(count == 2, ProgramError::InvalidArgument);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct TestProgram;
+
+    impl StarFrameProgram for TestProgram {
+        type InstructionSet = ();
+        type AccountDiscriminant = [u8; 8];
+        type Errors = ();
+        const ID: Pubkey = PROGRAM_ID;
+    }
+
+    #[test]
+    fn test_ids() -> Result<()> {
+        let mut authority = wallet(1);
+        let mut program = TestAccount::new(PROGRAM_ID, [0; 32], 1, 0).executable();
+        let infos = [authority.account_info(), program.account_info()];
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = WithIds::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+
+        // The authority's signer check is skipped for the `authority` id.
+        set.validate_accounts([1; 32], &mut ctx)?;
+        assert!(set.validate_accounts([2; 32], &mut ctx).is_err());
+        let error = set.validate_accounts((), &mut ctx).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedSigner));
+
+        set.cleanup_accounts(2, &mut ctx)?;
+        assert!(set.cleanup_accounts(3, &mut ctx).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_address() {
+        let mut authority = wallet(1).signer();
+        let mut program = wallet(2);
+        let infos = [authority.account_info(), program.account_info()];
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = WithIds::decode_accounts(&mut &infos[..], (), &mut ctx).unwrap();
+        let error = set.validate_accounts((), &mut ctx).unwrap_err();
+        assert!(error.is(ErrorCode::AddressMismatch));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: program"));
+    }
+
+    fn decode_single<T>(info: AccountInfo) -> T
+    where
+        T: for<'a> AccountSetDecode<'a, ()>,
+    {
+        let infos = [info];
+        T::decode_accounts(&mut &infos[..], (), &mut Context::new(&PROGRAM_ID)).unwrap()
+    }
+
+    #[test]
+    fn test_single_set_meta() {
+        fn can_fund_rent<T: CanFundRent>() {}
+        can_fund_rent::<Mut<Signer>>();
+        can_fund_rent::<Signer<Mut<SystemAccount>>>();
+
+        assert_eq!(
+            <Mut<Signer>>::meta(),
+            SingleSetMeta {
+                signer: true,
+                writable: true
+            }
+        );
+        assert_eq!(SystemAccount::meta(), SingleSetMeta::default());
+
+        let mut account = wallet(1);
+        let info = account.account_info();
+        let set: Mut<Signer<SystemAccount>> = decode_single(info);
+        let meta = set.account_meta();
+        assert!(meta.is_signer && meta.is_writable);
+        assert_eq!(set.pubkey(), &[1; 32]);
+        assert_eq!(set.account_to_modify().key(), info.key());
+    }
+
+    #[test]
+    fn test_fund_rent_from_program_account() -> Result<()> {
+        let mut funder = TestAccount::new([1; 32], PROGRAM_ID, 1_000, 0)
+            .signer()
+            .writable();
+        let mut recipient = wallet(2).writable();
+        let ctx = Context::new(&PROGRAM_ID);
+        let funder_set: Mut<Signer> = decode_single(funder.account_info());
+        let recipient_set: Mut = decode_single(recipient.account_info());
+        funder_set.fund_rent(&recipient_set, 400, &ctx)?;
+        recipient_set.add_lamports(100)?;
+        assert_eq!(funder.lamports(), 600);
+        assert_eq!(recipient.lamports(), 1_000_500);
+        assert!(!funder_set.can_create_account());
+        Ok(())
+    }
+
+    #[derive(AccountSet, Debug)]
+    struct Hop {
+        pool: Mut<SystemAccount>,
+        authority: AccountInfo,
+    }
+
+    #[derive(AccountSet, Debug)]
+    #[account_set(check_duplicates)]
+    struct Swap {
+        #[account_set(allow_duplicate)]
+        payer: Mut<Signer>,
+        destination: Mut<SystemAccount>,
+        hops: Many<Hop>,
+    }
+
+    fn validate_swap(infos: &[AccountInfo]) -> Result<Swap> {
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = Swap::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.validate_accounts((), &mut ctx)?;
+        Ok(set)
+    }
+
+    #[test]
+    fn test_duplicate_writable() -> Result<()> {
+        let mut payer = wallet(1).signer().writable();
+        let mut destination = wallet(2).writable();
+        let mut pools = [wallet(3).writable(), wallet(4).writable()];
+        let mut authority = wallet(5);
+        let payer = payer.account_info();
+        let destination = destination.account_info();
+        let [pool_0, pool_1] = pools.each_mut().map(TestAccount::account_info);
+        let authority = authority.account_info();
+
+        validate_swap(&[payer, destination, pool_0, authority, pool_1, authority])?;
+        // The payer is allowed to alias other accounts.
+        validate_swap(&[payer, payer, pool_0, authority, pool_1, authority])?;
+        // A read-only field can share a writable field's key, even though the account is writable.
+        validate_swap(&[payer, destination, pool_0, pool_0, pool_1, authority])?;
+
+        let error = validate_swap(&[
+            payer,
+            destination,
+            pool_0,
+            authority,
+            destination,
+            authority,
+        ])
+        .unwrap_err();
+        assert!(error.is(ErrorCode::DuplicateWritableAccount));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error
+            .to_string()
+            .contains("both `destination` and `hops[1].pool`"));
+
+        let error =
+            validate_swap(&[payer, destination, pool_0, authority, pool_0, authority]).unwrap_err();
+        assert!(error.is(ErrorCode::DuplicateWritableAccount));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error
+            .to_string()
+            .contains("both `hops[0].pool` and `hops[1].pool`"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_visit_accounts() -> Result<()> {
+        let mut accounts = [
+            wallet(1).signer().writable(),
+            wallet(2).writable(),
+            wallet(3).writable(),
+            wallet(4),
+        ];
+        let infos = accounts.each_mut().map(TestAccount::account_info);
+        let set = validate_swap(&infos)?;
+        let mut visited = vec![];
+        set.visit_accounts(&AccountPath::ROOT, &mut |path, account, writable| {
+            visited.push((format_path(&path.segments()), account.key()[0], writable));
+        });
+        assert_eq!(
+            visited,
+            [
+                ("destination".to_string(), 2, true),
+                ("hops[0].pool".to_string(), 3, true),
+                ("hops[0].authority".to_string(), 4, false),
+            ]
+        );
+        Ok(())
+    }
+
+    #[derive(AccountSet, Debug, derive_more::Deref)]
+    struct Vault(#[single_account_set] AccountInfo);
+
+    impl Vault {
+        fn authority(&self) -> Result<Pubkey> {
+            let data = self.0.try_borrow_data()?;
+            let authority = data
+                .first_chunk::<32>()
+                .ok_or(ProgramError::AccountDataTooSmall)?;
+            Ok(*authority)
+        }
+    }
+
+    #[derive(AccountSet, Debug)]
+    #[validate(arg = u64)]
+    struct VaultAccounts {
+        authority: Signer,
+        #[validate(
+            owner = TestProgram,
+            has_one = authority @ authority,
+            constraint = self.vault.lamports() >= arg,
+            error = ProgramError::InsufficientFunds
+        )]
+        vault: Vault,
+        #[validate(address = pinocchio_system::ID, constraint = self.system_program.executable())]
+        system_program: AccountInfo,
+    }
+
+    fn validate_vault(accounts: &mut [TestAccount; 3], min_lamports: u64) -> Result<()> {
+        let infos = accounts.each_mut().map(TestAccount::account_info);
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = VaultAccounts::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.validate_accounts(min_lamports, &mut ctx)
+    }
+
+    fn vault_accounts() -> [TestAccount; 3] {
+        [
+            wallet(1).signer(),
+            TestAccount::new([2; 32], PROGRAM_ID, 1_000, 32).with_data(&[1; 32]),
+            TestAccount::new(pinocchio_system::ID, [0; 32], 1, 0).executable(),
+        ]
+    }
+
+    #[test]
+    fn test_field_checks() -> Result<()> {
+        validate_vault(&mut vault_accounts(), 1_000)?;
+
+        let error = validate_vault(&mut vault_accounts(), 1_001).unwrap_err();
+        #[cfg(not(feature = "compact_errors"))]
+        {
+            let message = error.to_string();
+            assert!(message.contains("For account: vault"));
+            assert!(
+                message.contains("Constraint `self.vault.lamports() >= arg` failed for `vault`")
+            );
+        }
+        assert_eq!(ProgramError::from(error), ProgramError::InsufficientFunds);
+
+        let mut accounts = vault_accounts();
+        accounts[1] = TestAccount::new([2; 32], [9; 32], 1_000, 32).with_data(&[1; 32]);
+        let error = validate_vault(&mut accounts, 0).unwrap_err();
+        assert!(error.is(ErrorCode::OwnerMismatch));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("to be owned by"));
+
+        let mut accounts = vault_accounts();
+        accounts[1] = TestAccount::new([2; 32], PROGRAM_ID, 1_000, 32).with_data(&[3; 32]);
+        let error = validate_vault(&mut accounts, 0).unwrap_err();
+        assert!(error.is(ErrorCode::HasOneMismatch));
+        #[cfg(not(feature = "compact_errors"))]
+        {
+            let message = error.to_string();
+            assert!(message.contains("For account: vault"));
+            assert!(message.contains(&format!(
+                "Expected `authority` stored in `vault` to be `{}`, found `{}`",
+                [1u8; 32].display(),
+                [3u8; 32].display()
+            )));
+        }
+
+        let mut accounts = vault_accounts();
+        accounts[1] = TestAccount::new([2; 32], PROGRAM_ID, 1_000, 16);
+        let error = validate_vault(&mut accounts, 0).unwrap_err();
+        assert_eq!(ProgramError::from(error), ProgramError::AccountDataTooSmall);
+
+        let mut accounts = vault_accounts();
+        accounts[2] = TestAccount::new(pinocchio_system::ID, [0; 32], 1, 0);
+        let error = validate_vault(&mut accounts, 0).unwrap_err();
+        assert!(error.is(ErrorCode::ConstraintViolated));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: system_program"));
+        Ok(())
+    }
+
+    #[derive(Debug, Clone, Copy, Pod, Zeroable)]
+    #[repr(C)]
+    struct Treasury {
+        authority: Pubkey,
+        amount: u64,
+    }
+
+    impl ProgramAccount for Treasury {
+        type OwnerProgram = TestProgram;
+        const DISCRIMINANT: [u8; 8] = *b"treasury";
+    }
+
+    #[derive(AccountSet, Debug)]
+    struct TreasuryAccounts {
+        authority: Signer,
+        #[validate(has_one = authority)]
+        treasury: Account<Treasury>,
+    }
+
+    fn treasury_account(authority: Pubkey) -> TestAccount {
+        let treasury = Treasury {
+            authority,
+            amount: 0,
+        };
+        let mut data = Treasury::DISCRIMINANT.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&treasury));
+        TestAccount::new([2; 32], PROGRAM_ID, 1_000, data.len()).with_data(&data)
+    }
+
+    fn validate_treasury(treasury: &mut TestAccount) -> Result<()> {
+        let mut authority = wallet(1).signer();
+        let infos = [authority.account_info(), treasury.account_info()];
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = TreasuryAccounts::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.validate_accounts((), &mut ctx)
+    }
+
+    #[test]
+    fn test_has_one_reads_account_data() -> Result<()> {
+        validate_treasury(&mut treasury_account([1; 32]))?;
+
+        let error = validate_treasury(&mut treasury_account([3; 32])).unwrap_err();
+        assert!(error.is(ErrorCode::HasOneMismatch));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains(&format!(
+            "Expected `authority` stored in `treasury` to be `{}`, found `{}`",
+            [1u8; 32].display(),
+            [3u8; 32].display()
+        )));
+        Ok(())
+    }
+}
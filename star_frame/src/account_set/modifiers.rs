@@ -0,0 +1,73 @@
+//! Marker traits for what a [`SingleAccountSet`] guarantees about its account.
+//!
+//! These are forwarded through wrappers by the [`AccountSet`](derive@crate::account_set::AccountSet) derive, so
+//! `Mut<Seeded<Signer>>` is both a [`WritableAccount`] and a [`SignedAccount`].
+
+use crate::{
+    account_set::{
+        seeded::{GetSeeds, SignerSeeds},
+        AccountSetValidate,
+    },
+    prelude::*,
+};
+
+/// An account that signs the transaction, either as a signer or as a PDA of the current program.
+pub trait SignedAccount: SingleAccountSet {
+    /// The seeds to sign with if the account is a PDA, or `None` if it's a transaction signer.
+    fn signer_seeds(&self) -> Option<SignerSeeds<'_>>;
+}
+
+/// An account that is checked to be writable.
+pub trait WritableAccount: SingleAccountSet {}
+
+/// An account whose data is a known type.
+pub trait HasInnerType: SingleAccountSet {
+    type Inner: ?Sized;
+}
+
+/// An account whose data can be read as a copy, like [`Account`].
+///
+/// `#[validate(has_one = <field>)]` reads the key the account stores in its `<field>` through this.
+pub trait ReadAccount: SingleAccountSet {
+    type Data;
+
+    /// Reads a copy of the account's data.
+    fn read_data(&self) -> Result<Self::Data>;
+}
+
+/// An account owned by a known program.
+pub trait HasOwnerProgram: SingleAccountSet {
+    type OwnerProgram: StarFrameProgram;
+}
+
+/// An account whose address is a PDA derived from known seeds.
+pub trait HasSeeds: SingleAccountSet {
+    type Seeds: GetSeeds + Clone;
+}
+
+/// An account that can find its seeds from its validation argument before it's initialized, so the seeds can be
+/// used to sign for the account's creation.
+pub trait CanInitSeeds<A>: SingleAccountSet + AccountSetValidate<A> {
+    fn init_seeds(&mut self, arg: &A, ctx: &Context) -> Result<()>;
+}
+
+/// An account that can be created and initialized with `A`.
+pub trait CanInitAccount<A>: SingleAccountSet {
+    /// The size of the account's data once initialized with `arg`, for wrappers like
+    /// [`WithSeed`](crate::account_set::with_seed::WithSeed) that create the account before `Self` initializes it.
+    ///
+    /// `None` if `Self` creates the account itself.
+    fn init_space(_arg: &A) -> Option<usize> {
+        None
+    }
+
+    /// Initializes the account, signing for its creation with `account_seeds` if it's a PDA.
+    ///
+    /// With `IF_NEEDED`, accounts that are already initialized are left as is.
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: A,
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<()>;
+}
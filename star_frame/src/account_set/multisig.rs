@@ -0,0 +1,226 @@
+//! Account set for the signers of an M-of-N multisig.
+
+use crate::{
+    account_set::{impls::decode_each, AccountPath, AccountSetAccounts, PathSegment},
+    prelude::*,
+    ErrorCode,
+};
+
+/// Up to `N` signers, of which at least `M` distinct ones must be members of a multisig.
+///
+/// Decoded with the number of signer accounts passed in, which can't be more than `N`. Validated with the member
+/// list, usually read from the multisig's program account, like `#[validate(arg = self.treasury.members()?)]`. Every
+/// account must be a signer and a member, and signing more than once doesn't count twice.
+#[derive(Debug, Clone, Copy)]
+pub struct MultisigSigners<const M: usize, const N: usize> {
+    accounts: [Option<AccountInfo>; N],
+    /// Indexes into the member list, in the order the signers were passed in.
+    signed: [usize; N],
+    signed_len: usize,
+}
+
+impl<const M: usize, const N: usize> MultisigSigners<M, N> {
+    /// The signer accounts that were passed in.
+    pub fn accounts(&self) -> impl Iterator<Item = &AccountInfo> {
+        self.accounts.iter().flatten()
+    }
+
+    /// The indexes of the members that signed in the member list the set was validated with.
+    #[must_use]
+    pub fn signed_members(&self) -> &[usize] {
+        &self.signed[..self.signed_len]
+    }
+
+    /// Whether the member at `index` in the member list signed.
+    #[must_use]
+    pub fn has_signed(&self, index: usize) -> bool {
+        self.signed_members().contains(&index)
+    }
+}
+
+impl<'a, const M: usize, const N: usize> AccountSetDecode<'a, usize> for MultisigSigners<M, N> {
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        len: usize,
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        ensure!(
+            len <= N,
+            ProgramError::InvalidArgument,
+            "At most {N} multisig signers can be passed in, found {len}"
+        );
+        let mut decoded = [None; N];
+        for (slot, account) in decoded.iter_mut().zip(decode_each(accounts, len, &(), ctx)) {
+            *slot = Some(account?);
+        }
+        Ok(Self {
+            accounts: decoded,
+            signed: [0; N],
+            signed_len: 0,
+        })
+    }
+}
+
+impl<A, const M: usize, const N: usize> AccountSetValidate<A> for MultisigSigners<M, N>
+where
+    A: AsRef<[Pubkey]>,
+{
+    fn validate_accounts(&mut self, members: A, _ctx: &mut Context) -> Result<()> {
+        const {
+            assert!(
+                0 < M && M <= N,
+                "Multisig threshold must be between 1 and N"
+            );
+        }
+        let members = members.as_ref();
+        self.signed_len = 0;
+        for (index, account) in self.accounts.iter().flatten().enumerate() {
+            account
+                .check_signer()
+                .with_ctx(|| format!("Multisig signer at index {index}"))?;
+            let Some(member) = members
+                .iter()
+                .position(|member| member.fast_eq(account.key()))
+            else {
+                bail!(
+                    ErrorCode::NotMultisigMember,
+                    "Signer `{}` at index {index} is not a multisig member",
+                    account.key().display()
+                );
+            };
+            if !self.has_signed(member) {
+                self.signed[self.signed_len] = member;
+                self.signed_len += 1;
+            }
+        }
+        ensure!(
+            self.signed_len >= M,
+            ErrorCode::NotEnoughMultisigSigners,
+            "{} of the {} multisig members signed, expected at least {M}",
+            self.signed_len,
+            members.len()
+        );
+        Ok(())
+    }
+}
+
+impl<const M: usize, const N: usize> AccountSetCleanup<()> for MultisigSigners<M, N> {
+    fn cleanup_accounts(&mut self, (): (), _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<const M: usize, const N: usize> AccountSetAccounts for MultisigSigners<M, N> {
+    fn visit_accounts(
+        &self,
+        path: &AccountPath<'_>,
+        visit: &mut dyn FnMut(&AccountPath<'_>, &AccountInfo, bool),
+    ) {
+        for (index, account) in self.accounts().enumerate() {
+            visit(&path.join(PathSegment::Index(index)), account, false);
+        }
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use crate::idl::AccountSetToIdl;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<A, const M: usize, const N: usize> AccountSetToIdl<A> for MultisigSigners<M, N> {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            _arg: A,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            let mut signer = AccountInfo::account_set_to_idl(idl_definition, ())?;
+            signer.single()?.signer = true;
+            Ok(IdlAccountSetDef::Many {
+                account_set: Box::new(signer),
+                min: M,
+                max: Some(N),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestAccount;
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+
+    /// A treasury whose data is its list of multisig members.
+    #[derive(AccountSet, Debug)]
+    #[decode(arg = usize)]
+    struct Treasury {
+        treasury: AccountInfo,
+        #[decode(arg = arg)]
+        #[validate(arg = members(self.treasury)?)]
+        signers: MultisigSigners<2, 3>,
+    }
+
+    fn members(treasury: AccountInfo) -> Result<Vec<Pubkey>> {
+        let data = treasury.try_borrow_data()?;
+        Ok(data
+            .chunks_exact(32)
+            .map(|member| member.try_into().unwrap())
+            .collect())
+    }
+
+    /// Returns the indexes of the members that signed.
+    fn run(signers: &[TestAccount]) -> Result<Vec<usize>> {
+        let mut treasury = TestAccount::new([9; 32], PROGRAM_ID, 1_000, 96)
+            .with_data(&[[1; 32], [2; 32], [3; 32]].concat());
+        let mut signers = signers.to_vec();
+        let infos: Vec<_> = std::iter::once(treasury.account_info())
+            .chain(signers.iter_mut().map(TestAccount::account_info))
+            .collect();
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = Treasury::decode_accounts(&mut &infos[..], signers.len(), &mut ctx)?;
+        set.validate_accounts((), &mut ctx)?;
+        assert_eq!(set.signers.accounts().count(), signers.len());
+        assert!(set
+            .signers
+            .signed_members()
+            .iter()
+            .all(|index| set.signers.has_signed(*index)));
+        Ok(set.signers.signed_members().to_vec())
+    }
+
+    fn member(key: u8) -> TestAccount {
+        TestAccount::new([key; 32], pinocchio_system::ID, 1_000, 0).signer()
+    }
+
+    #[test]
+    fn test_threshold() -> Result<()> {
+        assert_eq!(run(&[member(3), member(1)])?, [2, 0]);
+        assert_eq!(run(&[member(1), member(2), member(3)])?, [0, 1, 2]);
+
+        let error = run(&[member(2)]).unwrap_err();
+        assert!(error.is(ErrorCode::NotEnoughMultisigSigners));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: signers"));
+
+        // Signing twice only counts once.
+        let error = run(&[member(2), member(2)]).unwrap_err();
+        assert!(error.is(ErrorCode::NotEnoughMultisigSigners));
+        Ok(())
+    }
+
+    #[test]
+    fn test_signer_errors() {
+        let error = run(&[member(1), member(4)]).unwrap_err();
+        assert!(error.is(ErrorCode::NotMultisigMember));
+
+        let unsigned = TestAccount::new([2; 32], pinocchio_system::ID, 1_000, 0);
+        let error = run(&[member(1), unsigned]).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedSigner));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("Multisig signer at index 1"));
+
+        let error = run(&[member(1), member(2), member(3), member(1)]).unwrap_err();
+        assert_eq!(ProgramError::from(error), ProgramError::InvalidArgument);
+    }
+}
@@ -0,0 +1,22 @@
+//! Account set modifier that checks the account is writable.
+
+use crate::{account_set::modifiers::WritableAccount, prelude::*};
+use derive_more::{Deref, DerefMut};
+
+/// A modifier that checks the account is writable.
+///
+/// Decoding, validation, and cleanup arguments are passed through to `T`.
+#[derive(AccountSet, Debug, Deref, DerefMut, Clone, Copy)]
+#[decode(generics = [<A>], arg = A)]
+#[validate(generics = [<A>], arg = A, extra_validation = self.check_writable())]
+#[cleanup(generics = [<A>], arg = A)]
+#[repr(transparent)]
+pub struct Mut<T = AccountInfo>(
+    #[single_account_set(writable, skip_writable_account)]
+    #[decode(arg = arg)]
+    #[validate(arg = arg)]
+    #[cleanup(arg = arg)]
+    T,
+);
+
+impl<T> WritableAccount for Mut<T> where T: SingleAccountSet {}
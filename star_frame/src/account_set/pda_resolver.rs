@@ -0,0 +1,178 @@
+//! Off-chain PDA resolution with a bump cache.
+//!
+//! [`PdaResolver`] finds the address and canonical bump for any [`GetSeeds`] value once, and hands out
+//! [`SeedsWithBump`] arguments so clients can always use the cheaper `seeds_with_bump` validation on-chain.
+
+use crate::{
+    account_set::seeded::{check_seeds, SeedProgram, SeedsWithBump, WithProgram},
+    prelude::*,
+    ErrorCode,
+};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+type BumpCache = HashMap<Vec<Vec<u8>>, (Pubkey, u8)>;
+
+/// Derives and caches PDA addresses and bumps for a single program.
+///
+/// ```
+/// # use star_frame::{account_set::pda_resolver::PdaResolver, prelude::*};
+/// #[derive(Debug, Clone, GetSeeds)]
+/// #[get_seeds(seed_const = b"vault")]
+/// struct VaultSeeds {
+///     authority: Pubkey,
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let resolver = PdaResolver::new([7; 32]);
+/// let seeds = VaultSeeds { authority: [1; 32] };
+/// let address = resolver.address(&seeds)?;
+/// // Pass this as the `seeds_with_bump` validation argument.
+/// let seeds_with_bump = resolver.seeds_with_bump(seeds)?;
+/// assert_eq!(resolver.address(&seeds_with_bump.seeds)?, address);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PdaResolver {
+    program_id: Pubkey,
+    cache: Mutex<BumpCache>,
+}
+
+impl PdaResolver {
+    /// Creates a resolver for PDAs of `program_id`.
+    #[must_use]
+    pub fn new(program_id: Pubkey) -> Self {
+        Self {
+            program_id,
+            cache: Mutex::default(),
+        }
+    }
+
+    /// Creates a resolver for PDAs of the [`SeedProgram`] `P`.
+    ///
+    /// Fails if `P`'s id is only known on-chain, like [`CurrentProgram`] and [`RuntimeProgram`]. Use
+    /// [`PdaResolver::new`] with the program id instead.
+    pub fn for_program<P: SeedProgram>() -> Result<Self> {
+        let program_id = P::known_id().ok_or_else(|| {
+            error!(
+                ErrorCode::SeedProgramNotSet,
+                "`{}` has no known program id, use `PdaResolver::new`",
+                std::any::type_name::<P>()
+            )
+        })?;
+        Ok(Self::new(program_id))
+    }
+
+    /// The program the PDAs are derived from.
+    #[must_use]
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    /// Finds the address and canonical bump for `seeds`, deriving them on the first call.
+    pub fn find<S: GetSeeds>(&self, seeds: &S) -> Result<(Pubkey, u8)> {
+        let raw_seeds = seeds.seeds();
+        let raw_seeds = &raw_seeds.as_ref()[..S::SEED_COUNT];
+        check_seeds(raw_seeds)?;
+        let key: Vec<Vec<u8>> = raw_seeds.iter().map(|seed| seed.to_vec()).collect();
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        let found = *cache.entry(key).or_insert_with(|| {
+            let (address, bump) = solana_pubkey::Pubkey::find_program_address(
+                raw_seeds,
+                &solana_pubkey::Pubkey::new_from_array(self.program_id),
+            );
+            (address.to_bytes(), bump)
+        });
+        Ok(found)
+    }
+
+    /// Finds the address for `seeds`.
+    pub fn address<S: GetSeeds>(&self, seeds: &S) -> Result<Pubkey> {
+        Ok(self.find(seeds)?.0)
+    }
+
+    /// Pairs `seeds` with their canonical bump, for the `seeds_with_bump` validation argument.
+    pub fn seeds_with_bump<S: GetSeeds>(&self, seeds: S) -> Result<SeedsWithBump<S>> {
+        let (_, bump) = self.find(&seeds)?;
+        Ok(SeedsWithBump { seeds, bump })
+    }
+
+    /// Pairs `seeds` with their canonical bump and this resolver's program id, for the `program_seeds_with_bump`
+    /// validation argument of [`RuntimeProgram`] accounts.
+    pub fn with_program<S: GetSeeds>(&self, seeds: S) -> Result<WithProgram<SeedsWithBump<S>>> {
+        Ok(WithProgram {
+            seeds: self.seeds_with_bump(seeds)?,
+            program: self.program_id,
+        })
+    }
+
+    /// Clears the cached addresses and bumps.
+    pub fn clear(&self) {
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_set::seeded::derive_program_address;
+
+    #[derive(Debug, Clone, GetSeeds)]
+    #[get_seeds(seed_const = b"vault")]
+    struct VaultSeeds {
+        authority: Pubkey,
+        name: String,
+    }
+
+    #[test]
+    fn test_resolve() -> Result<()> {
+        let program_id = solana_pubkey::Pubkey::new_unique();
+        let authority = solana_pubkey::Pubkey::new_unique();
+        let resolver = PdaResolver::new(program_id.to_bytes());
+        let seeds = VaultSeeds {
+            authority: authority.to_bytes(),
+            name: "main".to_string(),
+        };
+
+        let (expected, expected_bump) = solana_pubkey::Pubkey::find_program_address(
+            &[b"vault", authority.as_ref(), b"main"],
+            &program_id,
+        );
+        assert_eq!(
+            resolver.find(&seeds)?,
+            (expected.to_bytes(), expected_bump)
+        );
+        // The second lookup is served from the cache.
+        assert_eq!(resolver.address(&seeds)?, expected.to_bytes());
+        assert_eq!(resolver.cache.lock().unwrap().len(), 1);
+
+        let seeds_with_bump = resolver.seeds_with_bump(seeds)?;
+        assert_eq!(seeds_with_bump.bump, expected_bump);
+        assert_eq!(
+            derive_program_address(
+                seeds_with_bump.seeds_with_bump().as_ref(),
+                resolver.program_id()
+            ),
+            expected.to_bytes()
+        );
+
+        let with_program = resolver.with_program(seeds_with_bump.seeds)?;
+        assert_eq!(with_program.program, program_id.to_bytes());
+
+        resolver.clear();
+        assert!(resolver.cache.lock().unwrap().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_program() {
+        let error = PdaResolver::for_program::<CurrentProgram>().unwrap_err();
+        assert!(error.is(ErrorCode::SeedProgramNotSet));
+    }
+}
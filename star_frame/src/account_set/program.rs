@@ -0,0 +1,53 @@
+//! Account set for a program passed in to be invoked.
+
+use crate::{prelude::*, util::FastPubkeyEq, ErrorCode};
+use std::marker::PhantomData;
+
+/// The account of the program `P`, checked by its address.
+#[derive(AccountSet, derive_where::DeriveWhere)]
+#[derive_where(Debug, Clone, Copy)]
+#[account_set(skip_default_idl)]
+#[validate(extra_validation = self.check_id())]
+pub struct Program<P>(
+    #[single_account_set] AccountInfo,
+    #[account_set(skip = PhantomData)] PhantomData<fn() -> P>,
+)
+where
+    P: StarFrameProgram;
+
+impl<P> Program<P>
+where
+    P: StarFrameProgram,
+{
+    fn check_id(self) -> Result<()> {
+        ensure!(
+            self.pubkey().fast_eq(&P::ID),
+            ErrorCode::AddressMismatch,
+            "Expected program `{}`, found `{}`",
+            P::ID.display(),
+            self.pubkey().display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use crate::idl::AccountSetToIdl;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<P> AccountSetToIdl<()> for Program<P>
+    where
+        P: StarFrameProgram,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            (): (),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            let mut set = AccountInfo::account_set_to_idl(idl_definition, ())?;
+            set.single()?.address = Some(solana_pubkey::Pubkey::new_from_array(P::ID));
+            Ok(set)
+        }
+    }
+}
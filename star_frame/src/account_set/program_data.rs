@@ -0,0 +1,274 @@
+//! Account sets for the upgradeable loader's program data, and the upgrade authority it stores.
+
+use crate::{account_set::seeded::find_program_address, prelude::*, ErrorCode};
+use derive_more::Deref;
+use std::marker::PhantomData;
+
+/// The id of the upgradeable BPF loader, `BPFLoaderUpgradeab1e11111111111111111111111`.
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey = [
+    2, 168, 246, 145, 78, 136, 161, 176, 226, 16, 21, 62, 247, 99, 174, 43, 0, 194, 185, 61, 22,
+    193, 36, 210, 192, 83, 122, 16, 4, 128, 0, 0,
+];
+
+/// The `UpgradeableLoaderState::ProgramData` tag at the start of the account.
+const PROGRAM_DATA_TAG: u32 = 3;
+
+/// The program data account of the upgradeable program `P`, which stores the slot it was last deployed in and its
+/// upgrade authority.
+///
+/// Validation checks that the account is owned by the upgradeable loader and is at the address derived from `P`'s
+/// id, then parses its header. The address is found with `find_program_address`, so validating it costs more compute
+/// than most account sets.
+#[derive(AccountSet, Deref, derive_where::DeriveWhere)]
+#[derive_where(Debug, Clone, Copy)]
+#[account_set(skip_default_idl)]
+#[validate(extra_validation = self.check_and_parse())]
+pub struct ProgramData<P>
+where
+    P: StarFrameProgram,
+{
+    #[single_account_set]
+    #[deref]
+    account: AccountInfo,
+    #[account_set(skip = 0)]
+    slot: u64,
+    #[account_set(skip = None)]
+    upgrade_authority: Option<Pubkey>,
+    #[account_set(skip = PhantomData)]
+    program: PhantomData<fn() -> P>,
+}
+
+impl<P> ProgramData<P>
+where
+    P: StarFrameProgram,
+{
+    /// The slot the program was last deployed in.
+    #[must_use]
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+
+    /// The key that can upgrade the program, or `None` if the program is immutable.
+    #[must_use]
+    pub fn upgrade_authority(&self) -> Option<&Pubkey> {
+        self.upgrade_authority.as_ref()
+    }
+
+    /// The key that can upgrade the program, erroring with [`ProgramError::Immutable`] if there isn't one.
+    ///
+    /// Read by `#[validate(has_one = <field> @ authority)]`, like in [`UpgradeAuthority`].
+    pub fn authority(&self) -> Result<Pubkey> {
+        self.upgrade_authority.ok_or_else(|| {
+            error!(
+                ProgramError::Immutable,
+                "Program `{}` has no upgrade authority",
+                P::ID.display()
+            )
+        })
+    }
+
+    fn check_and_parse(&mut self) -> Result<()> {
+        ensure!(
+            self.account.is_owned_by(&BPF_LOADER_UPGRADEABLE_ID),
+            ErrorCode::OwnerMismatch,
+            "Expected program data `{}` to be owned by the upgradeable loader, found `{}`",
+            self.pubkey().display(),
+            self.owner_pubkey().display()
+        );
+        let (expected, _) = find_program_address(&[&P::ID], &BPF_LOADER_UPGRADEABLE_ID);
+        ensure!(
+            self.pubkey().fast_eq(&expected),
+            ErrorCode::AddressMismatch,
+            "Expected the program data of `{}` to be `{}`, found `{}`",
+            P::ID.display(),
+            expected.display(),
+            self.pubkey().display()
+        );
+        let (slot, upgrade_authority) =
+            parse_header(&self.account.try_borrow_data()?).ok_or_else(|| {
+                error!(
+                    ProgramError::InvalidAccountData,
+                    "Account `{}` is not program data",
+                    self.pubkey().display()
+                )
+            })?;
+        self.slot = slot;
+        self.upgrade_authority = upgrade_authority;
+        Ok(())
+    }
+}
+
+/// Parses the slot and upgrade authority from a bincode encoded `UpgradeableLoaderState::ProgramData`.
+fn parse_header(data: &[u8]) -> Option<(u64, Option<Pubkey>)> {
+    let (tag, data) = data.split_first_chunk::<4>()?;
+    if u32::from_le_bytes(*tag) != PROGRAM_DATA_TAG {
+        return None;
+    }
+    let (slot, data) = data.split_first_chunk::<8>()?;
+    let upgrade_authority = match data.split_first()? {
+        (0, _) => None,
+        (1, authority) => Some(*authority.first_chunk::<32>()?),
+        _ => return None,
+    };
+    Some((u64::from_le_bytes(*slot), upgrade_authority))
+}
+
+/// The upgrade authority of the program `P`, which must sign.
+///
+/// Restricts instructions like initializing a program's config to whoever can upgrade the program, without
+/// hard-coding their key. Fails with [`ProgramError::Immutable`] if the program can't be upgraded.
+#[derive(AccountSet, derive_where::DeriveWhere)]
+#[derive_where(Debug, Clone, Copy)]
+pub struct UpgradeAuthority<P>
+where
+    P: StarFrameProgram,
+{
+    /// The program data of `P`, which stores the upgrade authority.
+    #[validate(has_one = authority @ authority)]
+    pub program_data: ProgramData<P>,
+    pub authority: Signer,
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use crate::idl::AccountSetToIdl;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<P> AccountSetToIdl<()> for ProgramData<P>
+    where
+        P: StarFrameProgram,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            (): (),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            let mut set = AccountInfo::account_set_to_idl(idl_definition, ())?;
+            let (address, _) = solana_pubkey::Pubkey::find_program_address(
+                &[&P::ID],
+                &solana_pubkey::Pubkey::new_from_array(BPF_LOADER_UPGRADEABLE_ID),
+            );
+            set.single()?.address = Some(address);
+            Ok(set)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{account_set::executable::Executable, test_helpers::TestAccount};
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+
+    #[derive(Debug)]
+    struct TestProgram;
+
+    impl StarFrameProgram for TestProgram {
+        type InstructionSet = ();
+        type AccountDiscriminant = [u8; 8];
+        type Errors = ();
+        const ID: Pubkey = PROGRAM_ID;
+    }
+
+    #[derive(AccountSet, Debug)]
+    struct Admin {
+        program: Executable,
+        upgrade_authority: UpgradeAuthority<TestProgram>,
+    }
+
+    fn program_data_address() -> Pubkey {
+        solana_pubkey::Pubkey::find_program_address(
+            &[&PROGRAM_ID],
+            &solana_pubkey::Pubkey::new_from_array(BPF_LOADER_UPGRADEABLE_ID),
+        )
+        .0
+        .to_bytes()
+    }
+
+    fn program_data_account(upgrade_authority: Option<Pubkey>) -> TestAccount {
+        let mut data = vec![];
+        data.extend_from_slice(&PROGRAM_DATA_TAG.to_le_bytes());
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.push(upgrade_authority.is_some().into());
+        data.extend_from_slice(&upgrade_authority.unwrap_or_default());
+        TestAccount::new(
+            program_data_address(),
+            BPF_LOADER_UPGRADEABLE_ID,
+            1_000,
+            data.len(),
+        )
+        .with_data(&data)
+    }
+
+    fn accounts() -> [TestAccount; 3] {
+        [
+            TestAccount::new(PROGRAM_ID, BPF_LOADER_UPGRADEABLE_ID, 1, 0).executable(),
+            program_data_account(Some([1; 32])),
+            TestAccount::new([1; 32], pinocchio_system::ID, 1_000, 0).signer(),
+        ]
+    }
+
+    fn validate(accounts: &mut [TestAccount; 3]) -> Result<Admin> {
+        let infos = accounts.each_mut().map(TestAccount::account_info);
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let mut set = Admin::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.validate_accounts((), &mut ctx)?;
+        Ok(set)
+    }
+
+    #[test]
+    fn test_upgrade_authority() -> Result<()> {
+        let set = validate(&mut accounts())?;
+        let program_data = &set.upgrade_authority.program_data;
+        assert_eq!(program_data.slot(), 42);
+        assert_eq!(program_data.upgrade_authority(), Some(&[1; 32]));
+
+        let mut wrong_authority = accounts();
+        wrong_authority[2] = TestAccount::new([2; 32], pinocchio_system::ID, 1_000, 0).signer();
+        let error = validate(&mut wrong_authority).unwrap_err();
+        assert!(error.is(ErrorCode::HasOneMismatch));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error.to_string().contains("For account: upgrade_authority"));
+
+        let mut not_signed = accounts();
+        not_signed[2] = TestAccount::new([1; 32], pinocchio_system::ID, 1_000, 0);
+        let error = validate(&mut not_signed).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedSigner));
+
+        let mut immutable = accounts();
+        immutable[1] = program_data_account(None);
+        let error = validate(&mut immutable).unwrap_err();
+        assert_eq!(ProgramError::from(error), ProgramError::Immutable);
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_data_errors() {
+        let mut not_executable = accounts();
+        not_executable[0] = TestAccount::new(PROGRAM_ID, BPF_LOADER_UPGRADEABLE_ID, 1, 0);
+        let error = validate(&mut not_executable).unwrap_err();
+        assert!(error.is(ErrorCode::ExpectedExecutable));
+
+        let mut wrong_address = accounts();
+        wrong_address[1] = TestAccount::new([3; 32], BPF_LOADER_UPGRADEABLE_ID, 1_000, 45);
+        let error = validate(&mut wrong_address).unwrap_err();
+        assert!(error.is(ErrorCode::AddressMismatch));
+        #[cfg(not(feature = "compact_errors"))]
+        assert!(error
+            .to_string()
+            .contains("For account: upgrade_authority.program_data"));
+
+        let mut wrong_owner = accounts();
+        wrong_owner[1] = TestAccount::new(program_data_address(), PROGRAM_ID, 1_000, 45);
+        let error = validate(&mut wrong_owner).unwrap_err();
+        assert!(error.is(ErrorCode::OwnerMismatch));
+
+        let mut not_program_data = accounts();
+        not_program_data[1] =
+            TestAccount::new(program_data_address(), BPF_LOADER_UPGRADEABLE_ID, 1_000, 45)
+                .with_data(&[2; 45]);
+        let error = validate(&mut not_program_data).unwrap_err();
+        assert_eq!(ProgramError::from(error), ProgramError::InvalidAccountData);
+    }
+}
@@ -0,0 +1,230 @@
+//! Account set modifier for accounts whose data can grow or shrink.
+
+use crate::{account_set::modifiers::WritableAccount, prelude::*, ErrorCode};
+use derive_more::{Deref, DerefMut};
+use pinocchio::account_info::MAX_PERMITTED_DATA_INCREASE;
+
+/// A modifier that checks the account is writable, and lets its data be resized with [`Resize::realloc`].
+///
+/// Growing the account pulls the extra rent from the [`Context`] funder, and shrinking it refunds the lamports above
+/// the new rent exempt minimum to the [`Context`] recipient. Within an instruction, the data can grow by at most
+/// [`MAX_PERMITTED_DATA_INCREASE`] bytes past its length when it was validated. New bytes are always zeroed.
+///
+/// Decoding and validation arguments are passed through to `T`. Cleaning up with a `usize` resizes the account to
+/// that length after `T` is cleaned up with `()`.
+#[derive(AccountSet, Debug, Deref, DerefMut, Clone, Copy)]
+#[decode(generics = [<A>], arg = A)]
+#[validate(generics = [<A>], arg = A, extra_validation = self.check_and_set_original_len())]
+#[cleanup(id = "len", arg = usize, extra_cleanup = self.realloc(arg, ctx))]
+pub struct Resize<T = AccountInfo> {
+    #[single_account_set(writable, skip_writable_account)]
+    #[decode(arg = arg)]
+    #[validate(arg = arg)]
+    #[deref]
+    #[deref_mut]
+    account: T,
+    /// The data length at validation, which growth within the instruction is measured from.
+    #[account_set(skip = 0)]
+    original_len: usize,
+}
+
+impl<T> WritableAccount for Resize<T> where T: SingleAccountSet {}
+
+impl<T> Resize<T>
+where
+    T: SingleAccountSet,
+{
+    fn check_and_set_original_len(&mut self) -> Result<()> {
+        self.check_writable()?;
+        self.original_len = self.account_info().data_len();
+        Ok(())
+    }
+
+    /// Resizes the account's data to `new_len` bytes, zeroing any new bytes.
+    ///
+    /// The account is kept rent exempt. Growing it funds the missing rent from the [`Context`] funder, and shrinking
+    /// it refunds the excess lamports to the [`Context`] recipient.
+    pub fn realloc(&self, new_len: usize, ctx: &Context) -> Result<()> {
+        let info = self.account_info();
+        let old_len = info.data_len();
+        if new_len == old_len {
+            return Ok(());
+        }
+        ensure!(
+            info.is_owned_by(ctx.current_program_id()),
+            ProgramError::IllegalOwner,
+            "Account `{}` must be owned by the current program to be resized",
+            self.pubkey().display()
+        );
+        ensure!(
+            new_len <= self.original_len.saturating_add(MAX_PERMITTED_DATA_INCREASE),
+            ProgramError::InvalidRealloc,
+            "Account `{}` can grow by at most {MAX_PERMITTED_DATA_INCREASE} bytes in an instruction, from {} to {new_len} bytes",
+            self.pubkey().display(),
+            self.original_len
+        );
+
+        let rent_exempt = ctx.get_rent()?.minimum_balance(new_len);
+        let lamports = info.lamports();
+        if new_len > old_len {
+            if let Some(missing) = rent_exempt
+                .checked_sub(lamports)
+                .filter(|missing| *missing > 0)
+            {
+                let funder = ctx.get_funder().ok_or_else(|| {
+                    error!(
+                        ErrorCode::EmptyFunderCache,
+                        "Growing `{}` needs a funder",
+                        self.pubkey().display()
+                    )
+                })?;
+                funder.fund_rent(self, missing, ctx)?;
+            }
+            info.resize(new_len)?;
+            info.try_borrow_mut_data()?[old_len..].fill(0);
+        } else {
+            info.resize(new_len)?;
+            if let Some(excess) = lamports
+                .checked_sub(rent_exempt)
+                .filter(|excess| *excess > 0)
+            {
+                let recipient = ctx.get_recipient().ok_or_else(|| {
+                    error!(
+                        ErrorCode::EmptyRecipientCache,
+                        "Shrinking `{}` needs a recipient",
+                        self.pubkey().display()
+                    )
+                })?;
+                *info.try_borrow_mut_lamports()? -= excess;
+                recipient.add_lamports(excess)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{TestAccount, TEST_RENT};
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+
+    #[derive(AccountSet, Debug)]
+    #[cleanup(arg = usize)]
+    struct ResizeAccounts {
+        #[validate(funder)]
+        funder: Mut<Signer>,
+        #[validate(recipient)]
+        recipient: Mut<SystemAccount>,
+        #[cleanup(arg = arg)]
+        list: Resize,
+    }
+
+    struct Accounts {
+        funder: TestAccount,
+        recipient: TestAccount,
+        list: TestAccount,
+    }
+
+    impl Accounts {
+        fn new(list_lamports: u64) -> Self {
+            Self {
+                funder: TestAccount::new([1; 32], PROGRAM_ID, 1_000_000_000, 0)
+                    .signer()
+                    .writable(),
+                recipient: TestAccount::new([2; 32], pinocchio_system::ID, 0, 0).writable(),
+                list: TestAccount::new([3; 32], PROGRAM_ID, list_lamports, 16)
+                    .writable()
+                    .with_data(&[0xab; 16]),
+            }
+        }
+
+        fn validate(&mut self) -> Result<(ResizeAccounts, Context)> {
+            let infos = [
+                self.funder.account_info(),
+                self.recipient.account_info(),
+                self.list.account_info(),
+            ];
+            let mut ctx = Context::new(&PROGRAM_ID).with_rent(TEST_RENT);
+            let mut set = ResizeAccounts::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+            set.validate_accounts((), &mut ctx)?;
+            Ok((set, ctx))
+        }
+    }
+
+    fn rent_exempt(len: usize) -> u64 {
+        TEST_RENT.minimum_balance(len)
+    }
+
+    #[test]
+    fn test_grow() -> Result<()> {
+        let mut accounts = Accounts::new(rent_exempt(16));
+        let (set, ctx) = accounts.validate()?;
+        set.list.realloc(64, &ctx)?;
+        assert_eq!(accounts.list.data_len(), 64);
+        assert_eq!(&accounts.list.data()[..16], &[0xab; 16]);
+        assert!(accounts.list.data()[16..].iter().all(|byte| *byte == 0));
+        assert_eq!(accounts.list.lamports(), rent_exempt(64));
+        assert_eq!(
+            accounts.funder.lamports(),
+            1_000_000_000 - (rent_exempt(64) - rent_exempt(16))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shrink_in_cleanup() -> Result<()> {
+        let mut accounts = Accounts::new(rent_exempt(16) + 500);
+        let (mut set, mut ctx) = accounts.validate()?;
+        set.cleanup_accounts(8, &mut ctx)?;
+        assert_eq!(accounts.list.data(), &[0xab; 8]);
+        assert_eq!(accounts.list.lamports(), rent_exempt(8));
+        assert_eq!(
+            accounts.recipient.lamports(),
+            rent_exempt(16) + 500 - rent_exempt(8)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_regrow_zeroes() -> Result<()> {
+        let mut accounts = Accounts::new(rent_exempt(16));
+        let (set, ctx) = accounts.validate()?;
+        set.list.realloc(4, &ctx)?;
+        set.list.realloc(16, &ctx)?;
+        assert_eq!(&accounts.list.data()[..4], &[0xab; 4]);
+        assert!(accounts.list.data()[4..].iter().all(|byte| *byte == 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_increase() -> Result<()> {
+        let mut accounts = Accounts::new(rent_exempt(16));
+        let (set, ctx) = accounts.validate()?;
+        set.list.realloc(16 + MAX_PERMITTED_DATA_INCREASE, &ctx)?;
+        set.list.realloc(16, &ctx)?;
+        let error = set
+            .list
+            .realloc(17 + MAX_PERMITTED_DATA_INCREASE, &ctx)
+            .unwrap_err();
+        assert_eq!(ProgramError::from(error), ProgramError::InvalidRealloc);
+        assert_eq!(accounts.list.data_len(), 16);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_funder() -> Result<()> {
+        let mut list = TestAccount::new([3; 32], PROGRAM_ID, rent_exempt(16), 16).writable();
+        let infos = [list.account_info()];
+        let mut ctx = Context::new(&PROGRAM_ID).with_rent(TEST_RENT);
+        let mut set = Resize::<AccountInfo>::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.validate_accounts((), &mut ctx)?;
+        let error = set.realloc(32, &ctx).unwrap_err();
+        assert!(error.is(ErrorCode::EmptyFunderCache));
+        assert_eq!(list.data_len(), 16);
+        let error = set.realloc(8, &ctx).unwrap_err();
+        assert!(error.is(ErrorCode::EmptyRecipientCache));
+        Ok(())
+    }
+}
@@ -17,17 +17,44 @@ use derive_more::{Deref, DerefMut};
 use std::marker::PhantomData;
 
 pub use star_frame_proc::GetSeeds;
-/// A trait for getting the seed bytes of an account. The last element of the returned vector should be an empty slice, in order to replace it with a bump later on without
-/// having to push to the vector.
+
+/// The maximum number of seeds for a PDA, including the bump.
+pub const MAX_SEEDS: usize = 16;
+/// The maximum length of a single seed in bytes.
+pub const MAX_SEED_LEN: usize = 32;
+
+/// Checks that there are at most [`MAX_SEEDS`] seeds, each at most [`MAX_SEED_LEN`] bytes long.
+pub fn check_seeds(seeds: &[&[u8]]) -> Result<()> {
+    ensure!(
+        seeds.len() <= MAX_SEEDS,
+        ErrorCode::TooManySeeds,
+        "Expected at most {MAX_SEEDS} seeds, found {}",
+        seeds.len()
+    );
+    for (index, seed) in seeds.iter().enumerate() {
+        ensure!(
+            seed.len() <= MAX_SEED_LEN,
+            ErrorCode::SeedTooLong,
+            "Seed {index} is {} bytes long, expected at most {MAX_SEED_LEN}",
+            seed.len()
+        );
+    }
+    Ok(())
+}
+
+/// A trait for getting the seed bytes of an account. The seeds are returned in a fixed size array with an extra
+/// empty slice at the end, in order to replace it with a bump later on without allocating.
 ///
 /// ## Derivable
 ///
-/// This trait can be derived for structs with named fields using the [`GetSeeds`](star_frame_proc::GetSeeds) derive macro.
+/// This trait can be derived for structs and enums using the [`GetSeeds`](star_frame_proc::GetSeeds) derive macro.
+/// Fields can be any [`Seed`], or strings and byte slices like `String`, `&str`, `Vec<u8>`, and `&[u8]`.
 ///
 /// ## Manually Implementing `GetSeeds`
 ///
-/// `GetSeeds` can be manually implemented by defining a `seeds` method that returns a `Vec<&[u8]>`.
-/// The `seeds` method should optionally include a constant seed at the beginning of the vector,
+/// `GetSeeds` can be manually implemented by setting `SEED_COUNT` to the number of seeds, `Seeds` to an array of
+/// `SEED_COUNT + 1` slices, and defining a `seeds` method that returns it.
+/// The `seeds` method should optionally include a constant seed at the beginning of the array,
 /// followed by calling the `seed` method on each field of the struct, with an empty slice at the end.
 ///
 /// ```
@@ -39,39 +66,67 @@ pub use star_frame_proc::GetSeeds;
 /// }
 ///
 /// impl GetSeeds for Cool {
-///    fn seeds(&self) -> Vec<&[u8]> {
-///       vec![b"TEST_CONST", self.key.seed(), self.number.seed(), &[]]
+///     const SEED_COUNT: usize = 3;
+///     type Seeds<'a> = [&'a [u8]; 4];
+///     fn seeds(&self) -> Self::Seeds<'_> {
+///         [b"TEST_CONST", self.key.seed(), self.number.seed(), &[]]
 ///     }
 /// }
-///
 /// ```
 pub trait GetSeeds: Debug {
+    /// The number of seeds, not counting the bump.
     const SEED_COUNT: usize;
-    fn seeds(&self) -> [&[u8]; Self::SEED_COUNT];
+    /// The seeds followed by an empty slot for the bump. This should always be `[&'a [u8]; Self::SEED_COUNT + 1]`.
+    type Seeds<'a>: AsRef<[&'a [u8]]> + AsMut<[&'a [u8]]> + Debug
+    where
+        Self: 'a;
+    fn seeds(&self) -> Self::Seeds<'_>;
 }
 impl<T> GetSeeds for T
 where
     T: Seed + Debug,
 {
-        const SEED_COUNT: usize = 1;
+    const SEED_COUNT: usize = 1;
+    type Seeds<'a>
+        = [&'a [u8]; 2]
+    where
+        T: 'a;
 
-    fn seeds(&self) -> [&[u8]; N] {
-        vec![self.seed(), &[]]
+    fn seeds(&self) -> Self::Seeds<'_> {
+        [self.seed(), &[]]
     }
 }
-/// here i modified the vec to array by yunohu
-/// will change the others when i deal with those instructions 
+
+/// A value that can be used as a single seed.
+///
+/// Implemented for all [`NoUninit`] types using their bytes, and for `str` and `[u8]`, which makes
+/// `String`, `Vec<u8>`, and references to them usable as seeds through deref.
 pub trait Seed {
+    /// The length of the seed if it's the same for every value, used to check [`MAX_SEED_LEN`] at compile time.
+    const SEED_LEN: Option<usize> = None;
+
     fn seed(&self) -> &[u8];
 }
 impl<T> Seed for T
 where
     T: NoUninit,
 {
+    const SEED_LEN: Option<usize> = Some(size_of::<T>());
+
     fn seed(&self) -> &[u8] {
         bytes_of(self)
     }
 }
+impl Seed for str {
+    fn seed(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+impl Seed for [u8] {
+    fn seed(&self) -> &[u8] {
+        self
+    }
+}
 
 /// A combination of seeds and bump value for deterministic PDA generation.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Hash, PartialOrd, Ord)]
@@ -84,19 +139,72 @@ impl<T> SeedsWithBump<T>
 where
     T: GetSeeds,
 {
-    pub fn seeds_with_bump(&self) -> Vec<&[u8]> {
+    /// Returns the seeds with the bump filled into the last slot.
+    pub fn seeds_with_bump(&self) -> T::Seeds<'_> {
         let mut seeds = self.seeds.seeds();
-        // TODO: Replace with let chains once stable
-        if let Some(last) = seeds.last_mut() {
-            if last.is_empty() {
-                *last = bytes_of(&self.bump);
-                return seeds;
-            }
-        }
-        // here we need to use while loop to add arrays , need more research 
-        seeds.push(bytes_of(&self.bump));
+        seeds.as_mut()[T::SEED_COUNT] = bytes_of(&self.bump);
         seeds
     }
+
+    /// Returns the seeds with the bump as [`SignerSeeds`].
+    pub fn signer_seeds(&self) -> SignerSeeds<'_> {
+        const {
+            assert!(
+                T::SEED_COUNT < MAX_SEEDS,
+                "Seeds can have at most 15 seeds, plus the bump"
+            );
+        }
+        SignerSeeds::copy_from(self.seeds_with_bump().as_ref())
+    }
+}
+
+/// Seeds including the bump, used to sign for a PDA. Stored inline with room for [`MAX_SEEDS`] seeds.
+#[derive(Clone, Copy)]
+pub struct SignerSeeds<'a> {
+    seeds: [&'a [u8]; MAX_SEEDS],
+    len: usize,
+}
+
+impl<'a> SignerSeeds<'a> {
+    /// Copies the seeds, erroring if there are more than [`MAX_SEEDS`] or any are longer than [`MAX_SEED_LEN`].
+    pub fn new(seeds: &[&'a [u8]]) -> Result<Self> {
+        check_seeds(seeds)?;
+        Ok(Self::copy_from(seeds))
+    }
+
+    /// Panics if there are more than [`MAX_SEEDS`] seeds.
+    fn copy_from(seeds: &[&'a [u8]]) -> Self {
+        let mut out = Self {
+            seeds: [&[]; MAX_SEEDS],
+            len: seeds.len(),
+        };
+        out.seeds[..seeds.len()].copy_from_slice(seeds);
+        out
+    }
+
+    /// Calls `f` with the seeds as a pinocchio signer, to pass to `invoke_signed`.
+    pub fn with_signer<R>(
+        &self,
+        f: impl FnOnce(&[pinocchio::instruction::Signer<'_, '_>]) -> R,
+    ) -> R {
+        let seeds: [pinocchio::instruction::Seed<'a>; MAX_SEEDS] =
+            std::array::from_fn(|index| self.seeds[index].into());
+        f(&[pinocchio::instruction::Signer::from(&seeds[..self.len])])
+    }
+}
+
+impl<'a> std::ops::Deref for SignerSeeds<'a> {
+    type Target = [&'a [u8]];
+
+    fn deref(&self) -> &Self::Target {
+        &self.seeds[..self.len]
+    }
+}
+
+impl Debug for SignerSeeds<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&**self).finish()
+    }
 }
 
 /// Wrapper type for seed validation arguments.
@@ -104,16 +212,158 @@ where
 #[repr(transparent)]
 pub struct Seeds<T>(pub T);
 
+/// Wrapper around [`Seeds`] or [`SeedsWithBump`] validation arguments that derives the address with
+/// [`derive_program_address`] instead of `create_program_address`, skipping the off-curve check.
+///
+/// This is only sound for accounts that already exist, since an existing account at the derived address proves it
+/// was created as a PDA. Validation fails with [`ErrorCode::AccountNotInitialized`] if the account has no lamports
+/// and isn't owned by the seed program. It can't be used with `Init`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct SkipCurveCheck<T>(pub T);
+
+pub(crate) const PDA_MARKER: &[u8; 21] = b"ProgramDerivedAddress";
+
+/// Hashes the concatenation of `values` with sha256, using the `sol_sha256` syscall on-chain.
+pub(crate) fn hashv(values: &[&[u8]]) -> [u8; 32] {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = core::mem::MaybeUninit::<[u8; 32]>::uninit();
+        // SAFETY: `&[u8]` has the same layout as the syscall's byte slices, and the output is 32 bytes.
+        unsafe {
+            pinocchio::syscalls::sol_sha256(
+                values.as_ptr().cast(),
+                values.len() as u64,
+                hash.as_mut_ptr().cast(),
+            );
+            hash.assume_init()
+        }
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        for value in values {
+            hasher.update(value);
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// Creates a PDA from seeds that already include the bump, erroring if it's on the ed25519 curve.
+///
+/// Uses the `sol_create_program_address` syscall on-chain, and `solana_pubkey` off-chain where the syscall isn't
+/// available.
+pub(crate) fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Result<Pubkey> {
+    #[cfg(target_os = "solana")]
+    {
+        Ok(pinocchio::pubkey::create_program_address(
+            seeds, program_id,
+        )?)
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        let program_id = solana_pubkey::Pubkey::new_from_array(*program_id);
+        let address = solana_pubkey::Pubkey::create_program_address(seeds, &program_id)
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+        Ok(address.to_bytes())
+    }
+}
+
+/// Finds a PDA and its canonical bump.
+///
+/// Uses the `sol_try_find_program_address` syscall on-chain, and `solana_pubkey` off-chain where the syscall isn't
+/// available.
+pub(crate) fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+    #[cfg(target_os = "solana")]
+    {
+        pinocchio::pubkey::find_program_address(seeds, program_id)
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        let program_id = solana_pubkey::Pubkey::new_from_array(*program_id);
+        let (address, bump) = solana_pubkey::Pubkey::find_program_address(seeds, &program_id);
+        (address.to_bytes(), bump)
+    }
+}
+
+/// Derives a PDA from seeds that already include the bump by hashing them with the program id, without checking
+/// that the result is off the ed25519 curve like `create_program_address` does.
+///
+/// # Panics
+/// Panics if there are more than [`MAX_SEEDS`] seeds. Use [`check_seeds`] to check them first.
+#[must_use]
+pub fn derive_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    let mut data: [&[u8]; MAX_SEEDS + 2] = [&[]; MAX_SEEDS + 2];
+    data[..seeds.len()].copy_from_slice(seeds);
+    data[seeds.len()] = program_id.as_ref();
+    data[seeds.len() + 1] = PDA_MARKER;
+    hashv(&data[..seeds.len() + 2])
+}
+
+/// Derives a PDA from constant seeds and a known bump at compile time, without the off-curve check.
+///
+/// The bump must be the canonical bump for the seeds, which can be found once off-chain with `find_program_address`.
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// const PROGRAM_ID: Pubkey = [5; 32];
+/// // Found with `find_program_address`, which checks it below.
+/// const CONFIG_BUMP: u8 = 253;
+/// const CONFIG: Pubkey = const_program_address(&[b"config"], CONFIG_BUMP, &PROGRAM_ID);
+///
+/// let (config, bump) = solana_pubkey::Pubkey::find_program_address(
+///     &[b"config"],
+///     &solana_pubkey::Pubkey::new_from_array(PROGRAM_ID),
+/// );
+/// assert_eq!((CONFIG, CONFIG_BUMP), (config.to_bytes(), bump));
+/// ```
+#[must_use]
+pub const fn const_program_address<const N: usize>(
+    seeds: &[&[u8]; N],
+    bump: u8,
+    program_id: &Pubkey,
+) -> Pubkey {
+    pinocchio_pubkey::derive_address_const(seeds, Some(bump), program_id)
+}
+
 /// Allows generic [`crate::account_set`]s to be used in multiple programs by defaulting the [`SeedProgram`] to the current
 /// executing program. This is the default [`SeedProgram`] for [`Seeded`], and the only [`SeedProgram`] that can be used with
 /// the [`Init`] account set.
 #[derive(Debug, Clone, Copy)]
 pub struct CurrentProgram;
+/// A [`SeedProgram`] whose id is only known at runtime, such as a plugin program passed in as an account or
+/// stored in a config account. [`Seeded`] accounts using it are validated with a [`WithProgram`] argument
+/// that provides the program id, which can come from another account in the set or from instruction data.
+#[derive(Debug, Clone, Copy)]
+pub struct RuntimeProgram;
+
+/// Validation argument for [`Seeded`] accounts with a [`RuntimeProgram`], pairing [`Seeds`] or [`SeedsWithBump`]
+/// with the id of the program the PDA belongs to.
+///
+/// In the IDL, `P` is an [`IdlSeedProgram`](star_frame_idl::seeds::IdlSeedProgram) describing where the id comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash, PartialOrd, Ord)]
+pub struct WithProgram<T, P = Pubkey> {
+    pub seeds: T,
+    pub program: P,
+}
+
 /// Trait for types that can provide a program ID for PDA derivation.
 pub trait SeedProgram {
+    /// Whether the program id is only known at runtime, and has to be passed in with [`WithProgram`].
+    /// Validating with other arguments is a compile time error.
+    const RUNTIME: bool = false;
+
     fn id(ctx: &Context) -> Result<Pubkey>;
     #[cfg(all(feature = "idl", not(target_os = "solana")))]
     fn idl_program() -> Option<Pubkey>;
+    /// The program id if it's known without a [`Context`], used off-chain by
+    /// [`PdaResolver`](crate::account_set::pda_resolver::PdaResolver).
+    #[cfg(not(target_os = "solana"))]
+    #[must_use]
+    fn known_id() -> Option<Pubkey> {
+        None
+    }
 }
 
 impl SeedProgram for CurrentProgram {
@@ -126,6 +376,21 @@ impl SeedProgram for CurrentProgram {
     }
 }
 
+impl SeedProgram for RuntimeProgram {
+    const RUNTIME: bool = true;
+
+    fn id(_ctx: &Context) -> Result<Pubkey> {
+        bail!(
+            ErrorCode::SeedProgramNotSet,
+            "`RuntimeProgram` seeds must be validated with a `WithProgram` argument"
+        )
+    }
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    fn idl_program() -> Option<Pubkey> {
+        None
+    }
+}
+
 impl<P> SeedProgram for P
 where
     P: StarFrameProgram,
@@ -134,6 +399,11 @@ where
         Ok(P::ID)
     }
 
+    #[cfg(not(target_os = "solana"))]
+    fn known_id() -> Option<Pubkey> {
+        Some(P::ID)
+    }
+
     #[cfg(all(feature = "idl", not(target_os = "solana")))]
     fn idl_program() -> Option<Pubkey> {
         Some(P::ID)
@@ -145,6 +415,31 @@ where
 /// This wrapper ensures that the provided account matches the Program Derived Address (PDA) that
 /// would be generated from the given seeds and program. It supports validation with both automatic
 /// bump discovery and explicit bump values, making it suitable for both account lookup and creation scenarios.
+///
+/// When validated with [`Seeds`], the address is verified with a single `create_program_address` using the bump the
+/// inner account stores (see [`SingleAccountSet::stored_bump`] and [`ProgramAccount::BUMP_OFFSET`]). The bump is only
+/// searched for with `find_program_address` during `Init`, where the account has no data yet. Wrapping the argument
+/// in [`SkipCurveCheck`] also skips the off-curve check.
+///
+/// Accounts that don't store their bump fail validation with [`Seeds`] with [`ErrorCode::BumpNotStored`], where
+/// they used to search for it on every instruction. Either declare the bump with [`ProgramAccount::BUMP_OFFSET`],
+/// validate with [`SeedsWithBump`], or keep the search by calling [`Self::find_and_set_seeds`] before the field is
+/// validated:
+///
+/// ```ignore
+/// #[derive(AccountSet)]
+/// #[validate(before_validation = self.vault.find_and_set_seeds(&Seeds(VaultSeeds {}), ctx.current_program_id()))]
+/// pub struct WithdrawAccounts {
+///     // The seeds are already set, so they aren't checked again.
+///     #[validate(arg = Seeds(VaultSeeds {}))]
+///     pub vault: Seeded<AccountInfo, VaultSeeds>,
+/// }
+/// ```
+///
+/// [`ProgramAccount::BUMP_OFFSET`]: crate::account_set::account::ProgramAccount::BUMP_OFFSET
+///
+/// The program the PDA belongs to is set by `P`, which defaults to [`CurrentProgram`]. With [`RuntimeProgram`], the
+/// program id is passed in with a [`WithProgram`] argument instead.
 #[derive(AccountSet, Deref, DerefMut, derive_where::DeriveWhere)]
 #[derive_where(Debug, Clone; T, SeedsWithBump<S>)]
 #[account_set(skip_default_idl, skip_default_validate)]
@@ -152,23 +447,73 @@ where
     id = "seeds",
     generics = [where T: AccountSetValidate<()> + SingleAccountSet],
     arg = Seeds<S>,
-    before_validation = self.validate_and_set_seeds(&arg, ctx)
+    before_validation = self.validate_and_set_seeds::<false>(&arg, &Self::program_id(ctx)?)
 )]
 #[validate(
     id = "seeds_generic",
+    generics = [<A>],
     arg = (Seeds<S>, A),
-    before_validation = self.validate_and_set_seeds(&arg.0, ctx)
+    before_validation = self.validate_and_set_seeds::<false>(&arg.0, &Self::program_id(ctx)?)
 )]
 #[validate(
     id = "seeds_with_bump",
     generics = [where T: AccountSetValidate<()> + SingleAccountSet],
     arg = SeedsWithBump<S>,
-    before_validation = self.validate_and_set_seeds_with_bump(&arg, ctx)
+    before_validation = self.validate_and_set_seeds_with_bump::<false>(&arg, &Self::program_id(ctx)?)
 )]
 #[validate(
     id = "seeds_with_bump_generic",
+    generics = [<A>],
     arg = (SeedsWithBump<S>, A),
-    before_validation = self.validate_and_set_seeds_with_bump(&arg.0, ctx)
+    before_validation = self.validate_and_set_seeds_with_bump::<false>(&arg.0, &Self::program_id(ctx)?)
+)]
+#[validate(
+    id = "unchecked_seeds",
+    generics = [where T: AccountSetValidate<()> + SingleAccountSet],
+    arg = SkipCurveCheck<Seeds<S>>,
+    before_validation = self.validate_and_set_seeds::<true>(&arg.0, &Self::program_id(ctx)?)
+)]
+#[validate(
+    id = "unchecked_seeds_generic",
+    generics = [<A>],
+    arg = (SkipCurveCheck<Seeds<S>>, A),
+    before_validation = self.validate_and_set_seeds::<true>(&arg.0 .0, &Self::program_id(ctx)?)
+)]
+#[validate(
+    id = "unchecked_seeds_with_bump",
+    generics = [where T: AccountSetValidate<()> + SingleAccountSet],
+    arg = SkipCurveCheck<SeedsWithBump<S>>,
+    before_validation = self.validate_and_set_seeds_with_bump::<true>(&arg.0, &Self::program_id(ctx)?)
+)]
+#[validate(
+    id = "unchecked_seeds_with_bump_generic",
+    generics = [<A>],
+    arg = (SkipCurveCheck<SeedsWithBump<S>>, A),
+    before_validation = self.validate_and_set_seeds_with_bump::<true>(&arg.0 .0, &Self::program_id(ctx)?)
+)]
+#[validate(
+    id = "program_seeds",
+    generics = [where T: AccountSetValidate<()> + SingleAccountSet],
+    arg = WithProgram<Seeds<S>>,
+    before_validation = self.validate_and_set_seeds::<false>(&arg.seeds, &arg.program)
+)]
+#[validate(
+    id = "program_seeds_generic",
+    generics = [<A>],
+    arg = (WithProgram<Seeds<S>>, A),
+    before_validation = self.validate_and_set_seeds::<false>(&arg.0.seeds, &arg.0.program)
+)]
+#[validate(
+    id = "program_seeds_with_bump",
+    generics = [where T: AccountSetValidate<()> + SingleAccountSet],
+    arg = WithProgram<SeedsWithBump<S>>,
+    before_validation = self.validate_and_set_seeds_with_bump::<false>(&arg.seeds, &arg.program)
+)]
+#[validate(
+    id = "program_seeds_with_bump_generic",
+    generics = [<A>],
+    arg = (WithProgram<SeedsWithBump<S>>, A),
+    before_validation = self.validate_and_set_seeds_with_bump::<false>(&arg.0.seeds, &arg.0.program)
 )]
 pub struct Seeded<T, S = <T as HasSeeds>::Seeds, P = CurrentProgram>
 where
@@ -183,6 +528,10 @@ where
     )]
     #[validate(id = "seeds_generic", arg = arg.1)]
     #[validate(id = "seeds_with_bump_generic", arg = arg.1)]
+    #[validate(id = "unchecked_seeds_generic", arg = arg.1)]
+    #[validate(id = "unchecked_seeds_with_bump_generic", arg = arg.1)]
+    #[validate(id = "program_seeds_generic", arg = arg.1)]
+    #[validate(id = "program_seeds_with_bump_generic", arg = arg.1)]
     #[deref]
     #[deref_mut]
     pub(crate) account: T,
@@ -200,7 +549,7 @@ where
     P: SeedProgram,
 {
     fn init_seeds(&mut self, arg: &(Seeds<S>, A), ctx: &Context) -> Result<()> {
-        self.validate_and_set_seeds(&arg.0, ctx)
+        self.find_and_set_seeds(&arg.0, &Self::program_id(ctx)?)
     }
 }
 
@@ -211,7 +560,7 @@ where
     P: SeedProgram,
 {
     fn init_seeds(&mut self, arg: &Seeds<S>, ctx: &Context) -> Result<()> {
-        self.validate_and_set_seeds(arg, ctx)
+        self.find_and_set_seeds(arg, &Self::program_id(ctx)?)
     }
 }
 
@@ -222,7 +571,7 @@ where
     P: SeedProgram,
 {
     fn init_seeds(&mut self, arg: &(SeedsWithBump<S>, A), ctx: &Context) -> Result<()> {
-        self.validate_and_set_seeds_with_bump(&arg.0, ctx)
+        self.validate_and_set_seeds_with_bump::<false>(&arg.0, &Self::program_id(ctx)?)
     }
 }
 
@@ -233,7 +582,7 @@ where
     P: SeedProgram,
 {
     fn init_seeds(&mut self, arg: &SeedsWithBump<S>, ctx: &Context) -> Result<()> {
-        self.validate_and_set_seeds_with_bump(arg, ctx)
+        self.validate_and_set_seeds_with_bump::<false>(arg, &Self::program_id(ctx)?)
     }
 }
 
@@ -243,37 +592,101 @@ where
     S: GetSeeds + Clone,
     P: SeedProgram,
 {
-    fn validate_and_set_seeds(&mut self, seeds: &Seeds<S>, ctx: &Context) -> Result<()> {
+    /// The program id of a [`SeedProgram`] known from its type.
+    fn program_id(ctx: &Context) -> Result<Pubkey> {
+        const {
+            assert!(
+                !P::RUNTIME,
+                "`RuntimeProgram` seeds must be validated with a `WithProgram` argument"
+            );
+        }
+        P::id(ctx)
+    }
+
+    /// Validates the seeds using the bump stored in the account, failing with [`ErrorCode::BumpNotStored`] if it
+    /// doesn't store one.
+    fn validate_and_set_seeds<const SKIP_CURVE_CHECK: bool>(
+        &mut self,
+        seeds: &Seeds<S>,
+        program_id: &Pubkey,
+    ) -> Result<()> {
+        if self.seeds.is_some() {
+            return Ok(());
+        }
+        let Some(bump) = self.account.stored_bump()? else {
+            bail!(
+                ErrorCode::BumpNotStored,
+                "Account `{}` doesn't store its bump, validate it with `SeedsWithBump` or search for it with \
+                `Seeded::find_and_set_seeds` instead",
+                self.account.account_info().pubkey().display()
+            );
+        };
+        self.validate_and_set_seeds_with_bump::<SKIP_CURVE_CHECK>(
+            &SeedsWithBump {
+                seeds: seeds.0.clone(),
+                bump,
+            },
+            program_id,
+        )
+    }
+
+    /// Validates the seeds by searching for the canonical bump with `find_program_address`, which is what `Init` does.
+    ///
+    /// Outside of `Init`, this is for accounts that don't store their bump, which fail validation with [`Seeds`]
+    /// with [`ErrorCode::BumpNotStored`]. Call it before the account is validated, since validation skips seeds that
+    /// are already set.
+    pub fn find_and_set_seeds(&mut self, seeds: &Seeds<S>, program_id: &Pubkey) -> Result<()> {
         if self.seeds.is_some() {
             return Ok(());
         }
         let seeds = seeds.clone().0;
-        let (address, bump) = Pubkey::find_program_address(&seeds.seeds(), &P::id(ctx)?);
+        let (address, bump) = {
+            let raw_seeds = seeds.seeds();
+            let raw_seeds = &raw_seeds.as_ref()[..S::SEED_COUNT];
+            check_seeds(raw_seeds)?;
+            find_program_address(raw_seeds, program_id)
+        };
         let expected = self.account.account_info().pubkey();
         ensure!(
             address.fast_eq(expected),
             ErrorCode::AddressMismatch,
-            "Seeds: {seeds:?} result in address `{address}` and bump `{bump}`, expected `{expected}`"
+            "Seeds: {seeds:?} result in address `{}` and bump `{bump}`, expected `{}`",
+            address.display(),
+            expected.display()
         );
         self.seeds = Some(SeedsWithBump { seeds, bump });
         Ok(())
     }
 
-    fn validate_and_set_seeds_with_bump(
+    fn validate_and_set_seeds_with_bump<const SKIP_CURVE_CHECK: bool>(
         &mut self,
         seeds: &SeedsWithBump<S>,
-        ctx: &Context,
+        program_id: &Pubkey,
     ) -> Result<()> {
         if self.seeds.is_some() {
             return Ok(());
         }
         let arg_seeds = seeds.seeds_with_bump();
-        let address = Pubkey::create_program_address(&arg_seeds, &P::id(ctx)?)?;
+        check_seeds(arg_seeds.as_ref())?;
+        let address = if SKIP_CURVE_CHECK {
+            let account = self.account.account_info();
+            ensure!(
+                account.lamports() > 0 || account.is_owned_by(program_id),
+                ErrorCode::AccountNotInitialized,
+                "Account `{}` doesn't exist, so its address can't skip the off-curve check",
+                account.pubkey().display()
+            );
+            derive_program_address(arg_seeds.as_ref(), program_id)
+        } else {
+            create_program_address(arg_seeds.as_ref(), program_id)?
+        };
         let expected = self.account.account_info().pubkey();
         ensure!(
             address.fast_eq(expected),
             ErrorCode::AddressMismatch,
-            "Seeds `{seeds:?}` result in address `{address}`, expected `{expected}`"
+            "Seeds `{seeds:?}` result in address `{}`, expected `{}`",
+            address.display(),
+            expected.display()
         );
         self.seeds = Some(seeds.clone());
         Ok(())
@@ -296,8 +709,8 @@ where
     T: SingleAccountSet,
     S: GetSeeds + Clone,
 {
-    fn signer_seeds(&self) -> Option<Vec<&[u8]>> {
-        Some(self.access_seeds().seeds_with_bump())
+    fn signer_seeds(&self) -> Option<SignerSeeds<'_>> {
+        Some(self.access_seeds().signer_seeds())
     }
 }
 
@@ -316,10 +729,14 @@ where
     T: CanInitAccount<A>,
     S: GetSeeds + Clone,
 {
+    fn init_space(arg: &A) -> Option<usize> {
+        T::init_space(arg)
+    }
+
     fn init_account<const IF_NEEDED: bool>(
         &mut self,
         arg: A,
-        account_seeds: Option<Vec<&[u8]>>,
+        account_seeds: Option<&[&[u8]]>,
         ctx: &Context,
     ) -> Result<()> {
         // override seeds. Init should be called after seeds are set
@@ -332,7 +749,7 @@ where
         let seeds = self
             .seeds
             .as_ref()
-            .map(|s| s.seeds_with_bump())
+            .map(SeedsWithBump::seeds_with_bump)
             .ok_or_else(|| {
                 error!(
                     ErrorCode::SeedsNotSet,
@@ -340,7 +757,7 @@ where
                 )
             })?;
         self.account
-            .init_account::<IF_NEEDED>(arg, Some(seeds), ctx)
+            .init_account::<IF_NEEDED>(arg, Some(seeds.as_ref()), ctx)
     }
 }
 
@@ -349,7 +766,11 @@ mod idl_impl {
     use crate::idl::FindIdlSeeds;
 
     use super::*;
-    use star_frame_idl::{account_set::IdlAccountSetDef, seeds::IdlFindSeeds, IdlDefinition};
+    use star_frame_idl::{
+        account_set::IdlAccountSetDef,
+        seeds::{IdlFindSeeds, IdlSeedProgram},
+        IdlDefinition,
+    };
 
     impl<T, A, S, P, F> AccountSetToIdl<(Seeds<F>, A)> for Seeded<T, S, P>
     where
@@ -376,7 +797,7 @@ mod idl_impl {
             }
             let seeds = IdlFindSeeds {
                 seeds: F::find_seeds(&arg.0 .0)?,
-                program: P::idl_program(),
+                program: P::idl_program().map(IdlSeedProgram::Address),
             };
             single.seeds = Some(seeds);
 
@@ -399,6 +820,73 @@ mod idl_impl {
         }
     }
 
+    impl<T, A, S, P, F> AccountSetToIdl<(SkipCurveCheck<Seeds<F>>, A)> for Seeded<T, S, P>
+    where
+        T: AccountSetToIdl<A> + SingleAccountSet,
+        S: GetSeeds + Clone,
+        P: SeedProgram,
+        F: FindIdlSeeds,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: (SkipCurveCheck<Seeds<F>>, A),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            Self::account_set_to_idl(idl_definition, (arg.0 .0, arg.1))
+        }
+    }
+
+    impl<T, S, P, F> AccountSetToIdl<SkipCurveCheck<Seeds<F>>> for Seeded<T, S, P>
+    where
+        T: AccountSetToIdl<()> + SingleAccountSet,
+        S: GetSeeds + Clone,
+        P: SeedProgram,
+        F: FindIdlSeeds,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: SkipCurveCheck<Seeds<F>>,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            Self::account_set_to_idl(idl_definition, (arg.0, ()))
+        }
+    }
+
+    impl<T, A, S, F> AccountSetToIdl<(WithProgram<Seeds<F>, IdlSeedProgram>, A)>
+        for Seeded<T, S, RuntimeProgram>
+    where
+        T: AccountSetToIdl<A> + SingleAccountSet,
+        S: GetSeeds + Clone,
+        F: FindIdlSeeds,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: (WithProgram<Seeds<F>, IdlSeedProgram>, A),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            let (WithProgram { seeds, program }, arg) = arg;
+            let mut set = Self::account_set_to_idl(idl_definition, (seeds, arg))?;
+            set.single()?
+                .seeds
+                .as_mut()
+                .expect("Seeds are set by the `Seeds` impl")
+                .program = Some(program);
+            Ok(set)
+        }
+    }
+
+    impl<T, S, F> AccountSetToIdl<WithProgram<Seeds<F>, IdlSeedProgram>>
+        for Seeded<T, S, RuntimeProgram>
+    where
+        T: AccountSetToIdl<()> + SingleAccountSet,
+        S: GetSeeds + Clone,
+        F: FindIdlSeeds,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: WithProgram<Seeds<F>, IdlSeedProgram>,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            Self::account_set_to_idl(idl_definition, (arg, ()))
+        }
+    }
+
     impl<T, S, P> AccountSetToIdl<()> for Seeded<T, S, P>
     where
         T: AccountSetToIdl<()> + SingleAccountSet,
@@ -414,17 +902,12 @@ mod idl_impl {
     }
 }
 
-///
-///```compile_fail
-/// use star_frame_proc::GetSeeds;
-/// #[derive(GetSeeds)]
-/// struct Banana(i32, i32);
-/// ```
-fn _unnamed_seed_structs_fail() {}
-
 #[cfg(test)]
 mod tests {
-    use crate::prelude::*;
+    use super::{
+        check_seeds, const_program_address, derive_program_address, MAX_SEEDS, MAX_SEED_LEN,
+    };
+    use crate::{data_types::PodStr, prelude::*, test_helpers::TestAccount, ErrorCode};
 
     use solana_pubkey::Pubkey;
 
@@ -434,7 +917,8 @@ mod tests {
     fn test_unit_struct() {
         let unit_seeds = UnitSeeds {};
         let seeds = <UnitSeeds as crate::prelude::GetSeeds>::seeds(&unit_seeds);
-        assert_eq!(seeds, &[&[] as &[u8]]);
+        assert_eq!(UnitSeeds::SEED_COUNT, 0);
+        assert_eq!(seeds, [&[] as &[u8]]);
     }
 
     #[derive(Debug, GetSeeds, Clone)]
@@ -446,8 +930,9 @@ mod tests {
         let single_key = SingleKey {
             key: Pubkey::new_unique(),
         };
-        let intended_seeds = vec![single_key.key.seed(), &[]];
+        let intended_seeds = [single_key.key.seed(), &[]];
         let seeds = single_key.seeds();
+        assert_eq!(SingleKey::SEED_COUNT, 1);
         assert_eq!(seeds, intended_seeds);
     }
 
@@ -462,8 +947,9 @@ mod tests {
             key1: Pubkey::new_unique(),
             key2: Pubkey::new_unique(),
         };
-        let intended_seeds = vec![two_keys.key1.seed(), two_keys.key2.seed(), &[]];
+        let intended_seeds = [two_keys.key1.seed(), two_keys.key2.seed(), &[]];
         let seeds = two_keys.seeds();
+        assert_eq!(TwoKeys::SEED_COUNT, 2);
         assert_eq!(seeds, intended_seeds);
     }
 
@@ -478,11 +964,146 @@ mod tests {
             key: Pubkey::new_unique(),
             number: 42,
         };
-        let intended_seeds = vec![key_and_number.key.seed(), key_and_number.number.seed(), &[]];
+        let intended_seeds = [key_and_number.key.seed(), key_and_number.number.seed(), &[]];
         let seeds = key_and_number.seeds();
         assert_eq!(seeds, intended_seeds);
     }
 
+    #[test]
+    fn test_seeds_with_bump() {
+        let seeds = SeedsWithBump {
+            seeds: KeyAndNumber {
+                key: Pubkey::new_unique(),
+                number: 42,
+            },
+            bump: 254,
+        };
+        let intended_seeds = [
+            seeds.seeds.key.seed(),
+            seeds.seeds.number.seed(),
+            &[254][..],
+        ];
+        assert_eq!(seeds.seeds_with_bump(), intended_seeds);
+        assert_eq!(&*seeds.signer_seeds(), &intended_seeds[..]);
+
+        let single = SeedsWithBump {
+            seeds: 7u64,
+            bump: 1,
+        };
+        assert_eq!(single.seeds_with_bump(), [7u64.seed(), &[1][..]]);
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    #[get_seeds(seed_const = b"handle")]
+    pub struct HandleSeeds<'a> {
+        owner: Pubkey,
+        handle: String,
+        nickname: &'a str,
+        data: Vec<u8>,
+        padded: PodStr<8>,
+    }
+    #[test]
+    fn test_variable_length_seeds() {
+        let seeds = HandleSeeds {
+            owner: Pubkey::new_unique(),
+            handle: "star_frame".to_string(),
+            nickname: "sf",
+            data: vec![1, 2, 3],
+            padded: PodStr::new("sf").unwrap(),
+        };
+        let intended_seeds = [
+            b"handle".as_ref(),
+            seeds.owner.seed(),
+            b"star_frame",
+            b"sf",
+            &[1, 2, 3],
+            b"sf\0\0\0\0\0\0",
+            &[],
+        ];
+        assert_eq!(HandleSeeds::SEED_COUNT, 6);
+        assert_eq!(seeds.seeds(), intended_seeds);
+        assert!(check_seeds(&seeds.seeds()).is_ok());
+
+        let long = HandleSeeds {
+            handle: "a".repeat(MAX_SEED_LEN + 1),
+            ..seeds
+        };
+        let error = check_seeds(&long.seeds()).unwrap_err();
+        assert!(error.is(ErrorCode::SeedTooLong));
+        let error = check_seeds(&[&[] as &[u8]; MAX_SEEDS + 1]).unwrap_err();
+        assert!(error.is(ErrorCode::TooManySeeds));
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    pub struct TupleSeeds(Pubkey, u64);
+    #[test]
+    fn test_tuple_struct() {
+        let seeds = TupleSeeds(Pubkey::new_unique(), 42);
+        let intended_seeds = [seeds.0.seed(), seeds.1.seed(), &[]];
+        assert_eq!(TupleSeeds::SEED_COUNT, 2);
+        assert_eq!(seeds.seeds(), intended_seeds);
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    #[get_seeds(seed_const = b"vault")]
+    pub enum VaultSeeds {
+        User { owner: Pubkey, index: u16 },
+        Protocol(u64),
+        Global,
+    }
+    #[test]
+    fn test_enum() {
+        assert_eq!(VaultSeeds::SEED_COUNT, 4);
+
+        let owner = Pubkey::new_unique();
+        let user = VaultSeeds::User { owner, index: 3 };
+        let intended_seeds = [b"vault".as_ref(), &[0], owner.seed(), 3u16.seed(), &[]];
+        assert_eq!(user.seeds(), intended_seeds);
+
+        let protocol = VaultSeeds::Protocol(7);
+        let intended_seeds = [b"vault".as_ref(), &[1], 7u64.seed(), &[], &[]];
+        assert_eq!(protocol.seeds(), intended_seeds);
+
+        let global = VaultSeeds::Global;
+        assert_eq!(global.seeds(), [b"vault".as_ref(), &[2], &[], &[], &[]]);
+
+        // The padding doesn't change the address, so the bump can go in the last slot
+        let program_id = Pubkey::new_unique();
+        let (address, bump) =
+            Pubkey::find_program_address(&[b"vault", &[1], 7u64.seed()], &program_id);
+        let seeds = SeedsWithBump {
+            seeds: protocol,
+            bump,
+        };
+        assert_eq!(
+            derive_program_address(seeds.seeds_with_bump().as_ref(), &program_id.to_bytes()),
+            address.to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_derive_program_address() {
+        let program_id = Pubkey::new_unique();
+        let seeds = KeyAndNumber {
+            key: Pubkey::new_unique(),
+            number: 42,
+        };
+        let raw_seeds = seeds.seeds();
+        let (address, bump) =
+            Pubkey::find_program_address(&raw_seeds[..KeyAndNumber::SEED_COUNT], &program_id);
+        let seeds = SeedsWithBump { seeds, bump };
+        assert_eq!(
+            derive_program_address(seeds.seeds_with_bump().as_ref(), &program_id.to_bytes()),
+            address.to_bytes()
+        );
+
+        let (config, bump) = Pubkey::find_program_address(&[b"config"], &program_id);
+        assert_eq!(
+            const_program_address(&[b"config"], bump, &program_id.to_bytes()),
+            config.to_bytes()
+        );
+    }
+
     #[derive(Debug, GetSeeds, Clone)]
     #[get_seeds(seed_const = b"TEST_CONST")]
     pub struct OnlyConstSeed {}
@@ -490,7 +1111,8 @@ mod tests {
     fn test_unit_with_const_seed() {
         let only_const_seed = OnlyConstSeed {};
         let seeds = only_const_seed.seeds();
-        let intended_seeds = vec![b"TEST_CONST".as_ref(), &[]];
+        let intended_seeds = [b"TEST_CONST".as_ref(), &[]];
+        assert_eq!(OnlyConstSeed::SEED_COUNT, 1);
         assert_eq!(seeds, intended_seeds);
     }
 
@@ -504,8 +1126,9 @@ mod tests {
         let account = OneKeyConstSeed {
             key: Pubkey::new_unique(),
         };
-        let intended_seeds = vec![b"TEST_CONST".as_ref(), account.key.seed(), &[]];
+        let intended_seeds = [b"TEST_CONST".as_ref(), account.key.seed(), &[]];
         let seeds = account.seeds();
+        assert_eq!(OneKeyConstSeed::SEED_COUNT, 2);
         assert_eq!(seeds, intended_seeds);
     }
 
@@ -521,7 +1144,150 @@ mod tests {
     fn test_path_seed() {
         let account = SeedPath {};
         let seeds = account.seeds();
-        let intended_seeds = vec![b"TEST_CONST".as_ref(), &[]];
+        let intended_seeds = [b"TEST_CONST".as_ref(), &[]];
         assert_eq!(seeds, intended_seeds);
     }
+
+    static COUNTER_PROGRAM_ID: crate::prelude::Pubkey = [7; 32];
+
+    #[derive(Debug)]
+    struct CounterProgram;
+
+    impl StarFrameProgram for CounterProgram {
+        type InstructionSet = ();
+        type AccountDiscriminant = [u8; 8];
+        type Errors = ();
+        const ID: crate::prelude::Pubkey = COUNTER_PROGRAM_ID;
+    }
+
+    #[derive(Debug, Clone, Copy, Pod, Zeroable)]
+    #[repr(C)]
+    struct Counter {
+        count: u64,
+        bump: u8,
+        padding: [u8; 7],
+    }
+
+    impl ProgramAccount for Counter {
+        type OwnerProgram = CounterProgram;
+        const DISCRIMINANT: [u8; 8] = *b"counter\0";
+        const BUMP_OFFSET: Option<usize> = Some(core::mem::offset_of!(Counter, bump));
+    }
+
+    fn counter_account(address: Pubkey, bump: u8) -> TestAccount {
+        let counter = Counter {
+            count: 0,
+            bump,
+            padding: [0; 7],
+        };
+        let mut data = Counter::DISCRIMINANT.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&counter));
+        TestAccount::new(address.to_bytes(), COUNTER_PROGRAM_ID, 1_000, data.len()).with_data(&data)
+    }
+
+    fn validate_seeded<T>(account: &mut TestAccount) -> Result<SeedsWithBump<OnlyConstSeed>>
+    where
+        T: for<'a> AccountSetDecode<'a, ()> + AccountSetValidate<()> + SingleAccountSet,
+    {
+        let infos = [account.account_info()];
+        let mut ctx = Context::new(&COUNTER_PROGRAM_ID);
+        let mut set = Seeded::<T, OnlyConstSeed>::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.validate_accounts(Seeds(OnlyConstSeed {}), &mut ctx)?;
+        Ok(set.access_seeds().clone())
+    }
+
+    #[test]
+    fn test_stored_bump() -> Result<()> {
+        let program_id = Pubkey::new_from_array(COUNTER_PROGRAM_ID);
+        let (address, bump) = Pubkey::find_program_address(&[b"TEST_CONST"], &program_id);
+
+        let mut account = counter_account(address, bump);
+        assert_eq!(
+            validate_seeded::<Account<Counter>>(&mut account)?.bump,
+            bump
+        );
+
+        // The stored bump is trusted instead of searching for the canonical one, so a wrong bump is rejected even
+        // though the seeds are right.
+        let wrong_bump = (0..bump)
+            .rev()
+            .find(|bump| {
+                Pubkey::create_program_address(&[b"TEST_CONST", &[*bump]], &program_id).is_ok()
+            })
+            .unwrap();
+        let mut account = counter_account(address, wrong_bump);
+        let error = validate_seeded::<Account<Counter>>(&mut account).unwrap_err();
+        assert!(error.is(ErrorCode::AddressMismatch));
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_curve_check_requires_existing_account() -> Result<()> {
+        let program_id = Pubkey::new_from_array(COUNTER_PROGRAM_ID);
+        let (address, bump) = Pubkey::find_program_address(&[b"TEST_CONST"], &program_id);
+        let validate = |account: &mut TestAccount| {
+            let infos = [account.account_info()];
+            let mut ctx = Context::new(&COUNTER_PROGRAM_ID);
+            let mut set = Seeded::<AccountInfo, OnlyConstSeed>::decode_accounts(
+                &mut &infos[..],
+                (),
+                &mut ctx,
+            )?;
+            set.validate_accounts(
+                SkipCurveCheck(SeedsWithBump {
+                    seeds: OnlyConstSeed {},
+                    bump,
+                }),
+                &mut ctx,
+            )
+        };
+
+        validate(&mut TestAccount::new(
+            address.to_bytes(),
+            COUNTER_PROGRAM_ID,
+            0,
+            0,
+        ))?;
+        validate(&mut TestAccount::new(
+            address.to_bytes(),
+            pinocchio_system::ID,
+            1_000,
+            0,
+        ))?;
+        let error = validate(&mut TestAccount::new(
+            address.to_bytes(),
+            pinocchio_system::ID,
+            0,
+            0,
+        ))
+        .unwrap_err();
+        assert!(error.is(ErrorCode::AccountNotInitialized));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bump_only_searched_during_init() -> Result<()> {
+        let program_id = Pubkey::new_from_array(COUNTER_PROGRAM_ID);
+        let (address, bump) = Pubkey::find_program_address(&[b"TEST_CONST"], &program_id);
+
+        let mut account = TestAccount::new(address.to_bytes(), COUNTER_PROGRAM_ID, 1_000, 0);
+        let error = validate_seeded::<AccountInfo>(&mut account).unwrap_err();
+        assert!(error.is(ErrorCode::BumpNotStored));
+
+        // Searching for the bump before validation is the way to keep validating accounts that don't store it.
+        let infos = [account.account_info()];
+        let mut ctx = Context::new(&COUNTER_PROGRAM_ID);
+        let mut set =
+            Seeded::<AccountInfo, OnlyConstSeed>::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.find_and_set_seeds(&Seeds(OnlyConstSeed {}), &COUNTER_PROGRAM_ID)?;
+        set.validate_accounts(Seeds(OnlyConstSeed {}), &mut ctx)?;
+        assert_eq!(set.access_seeds().bump, bump);
+
+        let mut ctx = Context::new(&COUNTER_PROGRAM_ID);
+        let mut set =
+            Seeded::<AccountInfo, OnlyConstSeed>::decode_accounts(&mut &infos[..], (), &mut ctx)?;
+        set.init_seeds(&Seeds(OnlyConstSeed {}), &ctx)?;
+        assert_eq!(set.access_seeds().bump, bump);
+        Ok(())
+    }
 }
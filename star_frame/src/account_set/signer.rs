@@ -0,0 +1,32 @@
+//! Account set modifier that checks the account signed the transaction.
+
+use crate::{
+    account_set::{modifiers::SignedAccount, seeded::SignerSeeds},
+    prelude::*,
+};
+use derive_more::{Deref, DerefMut};
+
+/// A modifier that checks the account is a signer of the transaction.
+///
+/// Decoding, validation, and cleanup arguments are passed through to `T`.
+#[derive(AccountSet, Debug, Deref, DerefMut, Clone, Copy)]
+#[decode(generics = [<A>], arg = A)]
+#[validate(generics = [<A>], arg = A, extra_validation = self.check_signer())]
+#[cleanup(generics = [<A>], arg = A)]
+#[repr(transparent)]
+pub struct Signer<T = AccountInfo>(
+    #[single_account_set(signer, skip_signed_account)]
+    #[decode(arg = arg)]
+    #[validate(arg = arg)]
+    #[cleanup(arg = arg)]
+    T,
+);
+
+impl<T> SignedAccount for Signer<T>
+where
+    T: SingleAccountSet,
+{
+    fn signer_seeds(&self) -> Option<SignerSeeds<'_>> {
+        None
+    }
+}
@@ -0,0 +1,108 @@
+//! Account sets made of a single account.
+
+use crate::{prelude::*, ErrorCode};
+
+/// Static information about a [`SingleAccountSet`], used to build its account meta.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SingleSetMeta {
+    /// The account must sign the transaction.
+    pub signer: bool,
+    /// The account must be writable.
+    pub writable: bool,
+}
+
+/// An account set that contains exactly one account.
+///
+/// Derivable with [`AccountSet`](derive@crate::account_set::AccountSet) by marking a field with
+/// `#[single_account_set]`.
+pub trait SingleAccountSet {
+    /// Whether the account is required to be a signer or writable.
+    fn meta() -> SingleSetMeta
+    where
+        Self: Sized;
+
+    /// The underlying account.
+    fn account_info(&self) -> &AccountInfo;
+
+    /// The bump stored in the account's data, for PDAs that store their own canonical bump.
+    ///
+    /// [`Seeded`](crate::account_set::seeded::Seeded) uses it to check the address without searching for the bump.
+    fn stored_bump(&self) -> Result<Option<u8>> {
+        Ok(None)
+    }
+
+    /// The account meta to pass the account to an instruction with.
+    fn account_meta(&self) -> PinocchioAccountMeta<'_>
+    where
+        Self: Sized,
+    {
+        let meta = Self::meta();
+        PinocchioAccountMeta::new(self.pubkey(), meta.writable, meta.signer)
+    }
+
+    /// The account's address.
+    fn pubkey(&self) -> &Pubkey {
+        self.account_info().key()
+    }
+
+    /// The program that owns the account.
+    fn owner_pubkey(&self) -> Pubkey {
+        // SAFETY: The owner is copied out, so it can't be changed by an `assign` while it's borrowed.
+        unsafe { *self.account_info().owner() }
+    }
+
+    fn is_signer(&self) -> bool {
+        self.account_info().is_signer()
+    }
+
+    fn is_writable(&self) -> bool {
+        self.account_info().is_writable()
+    }
+
+    fn is_executable(&self) -> bool {
+        self.account_info().executable()
+    }
+
+    /// Errors with [`ErrorCode::ExpectedSigner`] if the account isn't a signer.
+    fn check_signer(&self) -> Result<()> {
+        ensure!(
+            self.is_signer(),
+            ErrorCode::ExpectedSigner,
+            "Account `{}` is not a signer",
+            self.pubkey().display()
+        );
+        Ok(())
+    }
+
+    /// Errors with [`ErrorCode::ExpectedWritable`] if the account isn't writable.
+    fn check_writable(&self) -> Result<()> {
+        ensure!(
+            self.is_writable(),
+            ErrorCode::ExpectedWritable,
+            "Account `{}` is not writable",
+            self.pubkey().display()
+        );
+        Ok(())
+    }
+
+    /// Errors with [`ErrorCode::ExpectedExecutable`] if the account isn't an executable program.
+    fn check_executable(&self) -> Result<()> {
+        ensure!(
+            self.is_executable(),
+            ErrorCode::ExpectedExecutable,
+            "Account `{}` is not executable",
+            self.pubkey().display()
+        );
+        Ok(())
+    }
+}
+
+impl SingleAccountSet for AccountInfo {
+    fn meta() -> SingleSetMeta {
+        SingleSetMeta::default()
+    }
+
+    fn account_info(&self) -> &AccountInfo {
+        self
+    }
+}
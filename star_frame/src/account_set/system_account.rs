@@ -0,0 +1,22 @@
+//! Account set for accounts owned by the system program.
+
+use crate::prelude::*;
+use derive_more::{Deref, DerefMut};
+
+/// An account owned by the system program, like a wallet or an account that hasn't been created yet.
+#[derive(AccountSet, Debug, Clone, Copy, Deref, DerefMut)]
+#[validate(extra_validation = self.check_owner())]
+#[repr(transparent)]
+pub struct SystemAccount(#[single_account_set] AccountInfo);
+
+impl SystemAccount {
+    fn check_owner(self) -> Result<()> {
+        ensure!(
+            self.0.is_owned_by(&pinocchio_system::ID),
+            ProgramError::IllegalOwner,
+            "Account `{}` is not owned by the system program",
+            self.pubkey().display()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+//! Account modifier for addresses derived with `create_with_seed`.
+//!
+//! The `WithSeed<T>` modifier wraps accounts whose address is `sha256(base || seed || owner)`, the scheme used by the
+//! system program's `*WithSeed` instructions. Unlike PDAs, these addresses are derived from a base signer rather than a
+//! program, and can be initialized through that signer.
+
+use crate::{
+    account_set::{
+        modifiers::{CanInitAccount, SignedAccount},
+        seeded::{hashv, CurrentProgram, SeedProgram, MAX_SEED_LEN, PDA_MARKER},
+        AccountSetValidate,
+    },
+    data_types::PodStr,
+    prelude::*,
+    ErrorCode,
+};
+use derive_more::{Deref, DerefMut};
+use std::marker::PhantomData;
+
+/// Derives the address for `base`, `seed`, and `owner` like `Pubkey::create_with_seed`.
+///
+/// Fails if the seed is longer than [`MAX_SEED_LEN`], or if `owner` ends with the PDA marker.
+pub fn create_with_seed(base: &Pubkey, seed: &[u8], owner: &Pubkey) -> Result<Pubkey> {
+    ensure!(
+        seed.len() <= MAX_SEED_LEN,
+        ErrorCode::SeedTooLong,
+        "Seed is {} bytes long, expected at most {MAX_SEED_LEN}",
+        seed.len()
+    );
+    ensure!(
+        !owner.ends_with(PDA_MARKER),
+        ProgramError::IllegalOwner,
+        "Owner of a seeded address cannot be a PDA marker"
+    );
+    Ok(hashv(&[base, seed, owner]))
+}
+
+/// Validation argument for [`WithSeed`], the base account and the seed used to derive the address.
+///
+/// The base should come from the account set, since it signs for the account during `Init`.
+#[derive(Debug, Clone, Copy)]
+pub struct AddressSeed {
+    pub base: AccountInfo,
+    pub seed: PodStr<MAX_SEED_LEN>,
+}
+
+impl AddressSeed {
+    /// Creates an [`AddressSeed`], erroring if `seed` is longer than [`MAX_SEED_LEN`].
+    pub fn new(base: &impl SingleAccountSet, seed: &str) -> Result<Self> {
+        Ok(Self {
+            base: *base.account_info(),
+            seed: PodStr::new(seed)?,
+        })
+    }
+}
+
+/// A modifier that validates accounts are derived from a base, seed, and owner with `create_with_seed`.
+///
+/// The owner is set by `P`, which defaults to [`CurrentProgram`]. During `Init`, the account is created with the
+/// system program's `CreateAccountWithSeed` signed by the base, and then its data is initialized by `T`.
+#[derive(AccountSet, Deref, DerefMut, derive_where::DeriveWhere)]
+#[derive_where(Debug, Clone; T)]
+#[account_set(skip_default_idl, skip_default_validate)]
+#[validate(
+    id = "seed",
+    generics = [where T: AccountSetValidate<()> + SingleAccountSet],
+    arg = AddressSeed,
+    before_validation = self.validate_and_set_address_seed(arg, ctx)
+)]
+#[validate(
+    id = "seed_generic",
+    generics = [<A>],
+    arg = (AddressSeed, A),
+    before_validation = self.validate_and_set_address_seed(arg.0, ctx)
+)]
+pub struct WithSeed<T, P = CurrentProgram>
+where
+    P: SeedProgram,
+{
+    #[single_account_set(skip_signed_account, skip_can_init_account)]
+    #[validate(id = "seed_generic", arg = arg.1)]
+    #[deref]
+    #[deref_mut]
+    pub(crate) account: T,
+    /// The base and seed of the account. Starts as `None`, and is set to `Some` during `AccountSetValidate`.
+    #[account_set(skip = None)]
+    pub(crate) address_seed: Option<AddressSeed>,
+    #[account_set(skip = PhantomData)]
+    phantom_p: PhantomData<P>,
+}
+
+impl<T, P> WithSeed<T, P>
+where
+    T: SingleAccountSet,
+    P: SeedProgram,
+{
+    fn validate_and_set_address_seed(
+        &mut self,
+        address_seed: AddressSeed,
+        ctx: &Context,
+    ) -> Result<()> {
+        if self.address_seed.is_some() {
+            return Ok(());
+        }
+        let address = create_with_seed(
+            address_seed.base.pubkey(),
+            address_seed.seed.as_bytes(),
+            &P::id(ctx)?,
+        )?;
+        let expected = self.account.account_info().pubkey();
+        ensure!(
+            address.fast_eq(expected),
+            ErrorCode::AddressMismatch,
+            "Base `{}` and seed `{}` result in address `{}`, expected `{}`",
+            address_seed.base.pubkey().display(),
+            address_seed.seed,
+            address.display(),
+            expected.display()
+        );
+        self.address_seed = Some(address_seed);
+        Ok(())
+    }
+
+    /// The base and seed the account was validated with.
+    pub fn access_address_seed(&self) -> &AddressSeed {
+        self.address_seed.as_ref().expect("Address seed not set!")
+    }
+}
+
+/// Accounts derived with a seed can't sign for themselves, their base signs instead.
+impl<T, P> SignedAccount for WithSeed<T, P>
+where
+    T: SingleAccountSet,
+    P: SeedProgram,
+{
+    fn signer_seeds(&self) -> Option<SignerSeeds<'_>> {
+        None
+    }
+}
+
+/// [`WithSeed`] can only be initialized with [`CurrentProgram`] as the owner.
+///
+/// An account still owned by the system program is created with a single `CreateAccountWithSeed`, signed by the
+/// base and funded by the [`Context`] funder, using `T`'s [`CanInitAccount::init_space`]. `T` then only initializes
+/// the data. Like `CreateAccount`, this fails if the address has already been funded.
+impl<T, A> CanInitAccount<A> for WithSeed<T, CurrentProgram>
+where
+    T: CanInitAccount<A> + SingleAccountSet,
+{
+    fn init_space(arg: &A) -> Option<usize> {
+        T::init_space(arg)
+    }
+
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: A,
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<()> {
+        if account_seeds.is_some() {
+            bail!(
+                ErrorCode::ConflictingAccountSeeds,
+                "Conflicting account seeds during init."
+            );
+        }
+        let AddressSeed { base, seed } = *self.address_seed.as_ref().ok_or_else(|| {
+            error!(
+                ErrorCode::SeedsNotSet,
+                "Address seed not set for `WithSeed` during init."
+            )
+        })?;
+        let account = self.account.account_info();
+        if account.is_owned_by(&pinocchio_system::ID) {
+            let space = T::init_space(&arg).ok_or_else(|| {
+                error!(
+                    ProgramError::InvalidArgument,
+                    "`WithSeed` needs the size of `{}` to create it",
+                    account.pubkey().display()
+                )
+            })?;
+            let funder = ctx.get_funder().ok_or_else(|| {
+                error!(
+                    ErrorCode::EmptyFunderCache,
+                    "Creating `{}` needs a funder",
+                    account.pubkey().display()
+                )
+            })?;
+            ensure!(
+                funder.can_create_account(),
+                ProgramError::InvalidArgument,
+                "Funder must be owned by the system program to create `{}`",
+                account.pubkey().display()
+            );
+            let from = funder.account_to_modify();
+            let create = pinocchio_system::instructions::CreateAccountWithSeed {
+                from: &from,
+                to: account,
+                base: Some(&base),
+                seed: seed.as_str()?,
+                lamports: ctx.get_rent()?.minimum_balance(space),
+                space: space as u64,
+                owner: ctx.current_program_id(),
+            };
+            match funder.signer_seeds() {
+                Some(seeds) => seeds.with_signer(|signers| create.invoke_signed(signers)),
+                None => create.invoke(),
+            }
+            .cpi_err::<System>()?;
+        }
+        self.account.init_account::<IF_NEEDED>(arg, None, ctx)
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<T, A, P> AccountSetToIdl<(AddressSeed, A)> for WithSeed<T, P>
+    where
+        T: AccountSetToIdl<A> + SingleAccountSet,
+        P: SeedProgram,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: (AddressSeed, A),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            T::account_set_to_idl(idl_definition, arg.1)?.assert_single()
+        }
+    }
+
+    impl<T, P> AccountSetToIdl<AddressSeed> for WithSeed<T, P>
+    where
+        T: AccountSetToIdl<()> + SingleAccountSet,
+        P: SeedProgram,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            _arg: AddressSeed,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            T::account_set_to_idl(idl_definition, ())?.assert_single()
+        }
+    }
+
+    impl<T, P> AccountSetToIdl<()> for WithSeed<T, P>
+    where
+        T: AccountSetToIdl<()> + SingleAccountSet,
+        P: SeedProgram,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: (),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            T::account_set_to_idl(idl_definition, arg)?.assert_single()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        program::system::SystemError,
+        test_helpers::{TestAccount, TEST_RENT},
+    };
+
+    static PROGRAM_ID: Pubkey = [7; 32];
+    const FUNDER_LAMPORTS: u64 = 1_000_000_000;
+
+    /// Writes its argument to an account created by [`WithSeed`].
+    #[derive(AccountSet, Debug)]
+    struct Vault(#[single_account_set(skip_can_init_account)] Mut<SystemAccount>);
+
+    impl CanInitAccount<u64> for Vault {
+        fn init_space(_arg: &u64) -> Option<usize> {
+            Some(size_of::<u64>())
+        }
+
+        fn init_account<const IF_NEEDED: bool>(
+            &mut self,
+            arg: u64,
+            account_seeds: Option<&[&[u8]]>,
+            _ctx: &Context,
+        ) -> Result<()> {
+            assert!(account_seeds.is_none());
+            self.account_info()
+                .try_borrow_mut_data()?
+                .copy_from_slice(&arg.to_le_bytes());
+            Ok(())
+        }
+    }
+
+    struct Accounts {
+        funder: TestAccount,
+        base: TestAccount,
+        vault: TestAccount,
+    }
+
+    impl Accounts {
+        fn new() -> Self {
+            Self::with_vault_lamports(0)
+        }
+
+        fn with_vault_lamports(vault_lamports: u64) -> Self {
+            let base = [2; 32];
+            let vault = create_with_seed(&base, b"vault", &PROGRAM_ID).unwrap();
+            Self {
+                funder: TestAccount::new([1; 32], pinocchio_system::ID, FUNDER_LAMPORTS, 0)
+                    .signer()
+                    .writable(),
+                base: TestAccount::new(base, pinocchio_system::ID, 0, 0).signer(),
+                vault: TestAccount::new(vault, pinocchio_system::ID, vault_lamports, 0).writable(),
+            }
+        }
+
+        fn decode(&mut self) -> Result<(Mut<Signer>, Signer, WithSeed<Vault>, Context)> {
+            let infos = [
+                self.funder.account_info(),
+                self.base.account_info(),
+                self.vault.account_info(),
+            ];
+            let mut ctx = Context::new(&PROGRAM_ID).with_rent(TEST_RENT);
+            let accounts = &mut &infos[..];
+            let funder = Mut::<Signer>::decode_accounts(accounts, (), &mut ctx)?;
+            let base = Signer::decode_accounts(accounts, (), &mut ctx)?;
+            let vault = WithSeed::<Vault>::decode_accounts(accounts, (), &mut ctx)?;
+            Ok((funder, base, vault, ctx))
+        }
+    }
+
+    #[test]
+    fn test_validate_address_seed() -> Result<()> {
+        let mut accounts = Accounts::new();
+        let (_, base, mut vault, mut ctx) = accounts.decode()?;
+
+        let error = vault
+            .validate_accounts(AddressSeed::new(&base, "other")?, &mut ctx)
+            .unwrap_err();
+        assert!(error.is(ErrorCode::AddressMismatch));
+        assert!(vault.address_seed.is_none());
+
+        vault.validate_accounts(AddressSeed::new(&base, "vault")?, &mut ctx)?;
+        let address_seed = vault.access_address_seed();
+        assert_eq!(address_seed.base.pubkey(), base.pubkey());
+        assert_eq!(address_seed.seed.as_str()?, "vault");
+        Ok(())
+    }
+
+    #[test]
+    fn test_init_creates_account_with_seed() -> Result<()> {
+        let mut accounts = Accounts::new();
+        let (funder, base, mut vault, mut ctx) = accounts.decode()?;
+
+        let error = vault.init_account::<false>(5, None, &ctx).unwrap_err();
+        assert!(error.is(ErrorCode::SeedsNotSet));
+
+        vault.validate_accounts(AddressSeed::new(&base, "vault")?, &mut ctx)?;
+        let error = vault.init_account::<false>(5, None, &ctx).unwrap_err();
+        assert!(error.is(ErrorCode::EmptyFunderCache));
+
+        ctx.set_funder(funder);
+        vault.init_account::<false>(5, None, &ctx)?;
+        let rent = TEST_RENT.minimum_balance(size_of::<u64>());
+        assert_eq!(accounts.vault.owner(), PROGRAM_ID);
+        assert_eq!(accounts.vault.data(), 5u64.to_le_bytes());
+        assert_eq!(accounts.vault.lamports(), rent);
+        assert_eq!(accounts.funder.lamports(), FUNDER_LAMPORTS - rent);
+        Ok(())
+    }
+
+    #[test]
+    fn test_init_tags_system_errors() -> Result<()> {
+        // Funding the address first makes the system program reject the creation.
+        let mut accounts = Accounts::with_vault_lamports(1);
+        let (funder, base, mut vault, mut ctx) = accounts.decode()?;
+        vault.validate_accounts(AddressSeed::new(&base, "vault")?, &mut ctx)?;
+        ctx.set_funder(funder);
+
+        let error = vault.init_account::<false>(5, None, &ctx).unwrap_err();
+        assert!(error.is(SystemError::AccountAlreadyInUse));
+        #[cfg(not(feature = "compact_errors"))]
+        assert_eq!(error.cpi_program(), Some(&System::ID));
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_with_seed() {
+        let base = solana_pubkey::Pubkey::new_unique();
+        let owner = solana_pubkey::Pubkey::new_unique();
+        let expected = solana_pubkey::Pubkey::create_with_seed(&base, "vault", &owner).unwrap();
+        assert_eq!(
+            create_with_seed(&base.to_bytes(), b"vault", &owner.to_bytes()).unwrap(),
+            expected.to_bytes()
+        );
+
+        let long_seed = [b'a'; MAX_SEED_LEN + 1];
+        let error = create_with_seed(&base.to_bytes(), &long_seed, &owner.to_bytes()).unwrap_err();
+        assert!(error.is(ErrorCode::SeedTooLong));
+
+        let mut pda_owner = [0; 32];
+        pda_owner[32 - PDA_MARKER.len()..].copy_from_slice(PDA_MARKER);
+        assert!(create_with_seed(&base.to_bytes(), b"vault", &pda_owner).is_err());
+    }
+}
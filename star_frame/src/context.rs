@@ -8,7 +8,21 @@ use crate::{
 use pinocchio::sysvars::{clock::Clock, rent::Rent, Sysvar};
 use std::cell::Cell;
 
+#[cfg(not(feature = "no_alloc"))]
+type FunderStorage = Box<dyn CanFundRent>;
+#[cfg(feature = "no_alloc")]
+type FunderStorage = inline::InlineFunder;
+
+#[cfg(not(feature = "no_alloc"))]
+type RecipientStorage = Box<dyn CanAddLamports>;
+#[cfg(feature = "no_alloc")]
+type RecipientStorage = inline::InlineRecipient;
+
 /// Additional context given to [`crate::instruction::StarFrameInstruction`]s, enabling programs to cache and retrieve helpful information during instruction execution.
+///
+/// With the `no_alloc` feature, the funder and recipient are stored inline instead of boxed, so
+/// setting them never touches the heap. The feature also enables `compact_errors`, so errors don't
+/// allocate either.
 #[derive(Debug)]
 pub struct Context {
     /// The program id of the currently executing program.
@@ -18,9 +32,9 @@ pub struct Context {
     // Clock cache to avoid repeated `Clock::get()` calls
     clock_cache: Cell<Option<Clock>>,
     // Cached recipient for rent. Usually set during `AccountSetValidate`
-    recipient: Option<Box<dyn CanAddLamports>>,
+    recipient: Option<RecipientStorage>,
     // Cached funder for rent. Usually set during `AccountSetValidate`
-    funder: Option<Box<dyn CanFundRent>>,
+    funder: Option<FunderStorage>,
 }
 
 impl Default for Context {
@@ -43,6 +57,15 @@ impl Context {
         }
     }
 
+    /// Fills the rent cache, so [`Self::get_rent`] returns `rent` without calling `Rent::get()`.
+    ///
+    /// Useful off-chain, like in tests, where the rent sysvar isn't available.
+    #[must_use]
+    pub fn with_rent(self, rent: Rent) -> Self {
+        self.rent_cache.set(Some(rent));
+        self
+    }
+
     /// Get the program id of the currently executing program.
     pub fn current_program_id(&self) -> &Pubkey {
         self.program_id
@@ -78,8 +101,14 @@ impl Context {
     }
 
     /// Sets the funder for rent.
-    pub fn set_funder(&mut self, funder: Box<dyn CanFundRent>) {
-        self.funder.replace(funder);
+    ///
+    /// With the `no_alloc` feature, the funder must fit in [`inline::INLINE_WORDS`] words, which is
+    /// checked at compile time.
+    pub fn set_funder(&mut self, funder: impl CanFundRent + 'static) {
+        #[cfg(not(feature = "no_alloc"))]
+        self.funder.replace(Box::new(funder));
+        #[cfg(feature = "no_alloc")]
+        self.funder.replace(inline::InlineFunder::new(funder));
     }
 
     /// Gets the cached recipient for rent if it has been set.
@@ -88,7 +117,105 @@ impl Context {
     }
 
     /// Sets the recipient for rent.
-    pub fn set_recipient(&mut self, recipient: Box<dyn CanAddLamports>) {
-        self.recipient.replace(recipient);
+    ///
+    /// With the `no_alloc` feature, the recipient must fit in [`inline::INLINE_WORDS`] words, which is
+    /// checked at compile time.
+    pub fn set_recipient(&mut self, recipient: impl CanAddLamports + 'static) {
+        #[cfg(not(feature = "no_alloc"))]
+        self.recipient.replace(Box::new(recipient));
+        #[cfg(feature = "no_alloc")]
+        self.recipient
+            .replace(inline::InlineRecipient::new(recipient));
+    }
+}
+
+/// Fixed size inline storage for the [`Context`] funder and recipient, used with the `no_alloc` feature.
+#[cfg(feature = "no_alloc")]
+pub mod inline {
+    use crate::account_set::{CanAddLamports, CanFundRent};
+    use std::{
+        fmt::{Debug, Formatter},
+        mem::{align_of, size_of, MaybeUninit},
+    };
+
+    /// The number of machine words available to store a funder or recipient inline.
+    pub const INLINE_WORDS: usize = 16;
+
+    type Words = [MaybeUninit<usize>; INLINE_WORDS];
+
+    macro_rules! inline_dyn {
+        ($(#[$meta:meta])* $name:ident, $trait:ident) => {
+            $(#[$meta])*
+            pub struct $name {
+                words: Words,
+                as_dyn: unsafe fn(*const Words) -> *const dyn $trait,
+                drop: unsafe fn(*mut Words),
+            }
+
+            impl $name {
+                /// Moves `value` into inline storage. Fails to compile if `T` is too large or overaligned.
+                pub fn new<T: $trait + 'static>(value: T) -> Self {
+                    unsafe fn as_dyn<T: $trait + 'static>(words: *const Words) -> *const dyn $trait {
+                        words.cast::<T>()
+                    }
+
+                    unsafe fn drop<T>(words: *mut Words) {
+                        // SAFETY: `words` holds an initialized `T`, written in `new`
+                        unsafe { words.cast::<T>().drop_in_place() }
+                    }
+
+                    const {
+                        assert!(
+                            size_of::<T>() <= size_of::<Words>(),
+                            concat!("Value is too large to store inline in `", stringify!($name), "`")
+                        );
+                        assert!(
+                            align_of::<T>() <= align_of::<Words>(),
+                            concat!("Value is overaligned for `", stringify!($name), "`")
+                        );
+                    }
+
+                    let mut words: Words = [MaybeUninit::uninit(); INLINE_WORDS];
+                    // SAFETY: size and alignment are checked above
+                    unsafe { words.as_mut_ptr().cast::<T>().write(value) };
+                    Self {
+                        words,
+                        as_dyn: as_dyn::<T>,
+                        drop: drop::<T>,
+                    }
+                }
+            }
+
+            impl AsRef<dyn $trait> for $name {
+                fn as_ref(&self) -> &(dyn $trait + 'static) {
+                    // SAFETY: `as_dyn` was created for the type written to `words`
+                    unsafe { &*(self.as_dyn)(&self.words) }
+                }
+            }
+
+            impl Drop for $name {
+                fn drop(&mut self) {
+                    // SAFETY: `drop` was created for the type written to `words`, and is only called once
+                    unsafe { (self.drop)(&mut self.words) }
+                }
+            }
+
+            impl Debug for $name {
+                fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                    f.debug_tuple(stringify!($name)).field(&self.as_ref()).finish()
+                }
+            }
+        };
     }
+
+    inline_dyn!(
+        /// Inline storage for a [`CanFundRent`] trait object.
+        InlineFunder,
+        CanFundRent
+    );
+    inline_dyn!(
+        /// Inline storage for a [`CanAddLamports`] trait object.
+        InlineRecipient,
+        CanAddLamports
+    );
 }
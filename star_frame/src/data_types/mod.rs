@@ -0,0 +1,5 @@
+//! Data types for use in account and instruction data.
+
+mod pod_str;
+
+pub use pod_str::*;
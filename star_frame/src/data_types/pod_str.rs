@@ -0,0 +1,93 @@
+//! A fixed capacity utf8 string that can be stored in zero-copy data.
+
+use crate::{prelude::*, ErrorCode};
+use std::fmt::{Debug, Display, Formatter};
+
+/// A utf8 string of up to `N` bytes, zero padded to `N` bytes.
+///
+/// As a [`Seed`](crate::account_set::seeded::Seed), a `PodStr` is always its full `N` padded bytes, which keeps
+/// the seed length fixed and lets the `GetSeeds` derive check it against the maximum seed length at compile time.
+/// Use a `&str` seed from [`PodStr::as_str`] to seed with just the string.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct PodStr<const N: usize>([u8; N]);
+
+// SAFETY: `PodStr` is a transparent wrapper around a byte array.
+unsafe impl<const N: usize> Zeroable for PodStr<N> {}
+// SAFETY: `PodStr` is a transparent wrapper around a byte array.
+unsafe impl<const N: usize> Pod for PodStr<N> {}
+
+impl<const N: usize> Default for PodStr<N> {
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl<const N: usize> PodStr<N> {
+    /// Creates a `PodStr` from a string, erroring if it's longer than `N` bytes or contains a nul byte.
+    pub fn new(string: &str) -> Result<Self> {
+        ensure!(
+            string.len() <= N,
+            ErrorCode::IndexOutOfBounds,
+            "String is {} bytes long, expected at most {N}",
+            string.len()
+        );
+        ensure!(
+            !string.as_bytes().contains(&0),
+            ErrorCode::InvalidRange,
+            "`PodStr` strings cannot contain nul bytes"
+        );
+        let mut bytes = [0; N];
+        bytes[..string.len()].copy_from_slice(string.as_bytes());
+        Ok(Self(bytes))
+    }
+
+    /// The bytes of the string, without the zero padding.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = self.0.iter().position(|byte| *byte == 0).unwrap_or(N);
+        &self.0[..len]
+    }
+
+    /// The string, without the zero padding.
+    pub fn as_str(&self) -> Result<&str> {
+        Ok(std::str::from_utf8(self.as_bytes())?)
+    }
+
+    /// The full `N` bytes, including the zero padding.
+    #[must_use]
+    pub fn padded_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> Debug for PodStr<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&String::from_utf8_lossy(self.as_bytes()), f)
+    }
+}
+
+impl<const N: usize> Display for PodStr<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&String::from_utf8_lossy(self.as_bytes()), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pod_str() {
+        let string = PodStr::<8>::new("handle").unwrap();
+        assert_eq!(string.as_str().unwrap(), "handle");
+        assert_eq!(string.padded_bytes(), b"handle\0\0");
+        assert_eq!(format!("{string}"), "handle");
+        assert_eq!(
+            PodStr::<8>::new("12345678").unwrap().as_bytes(),
+            b"12345678"
+        );
+        assert!(PodStr::<8>::new("123456789").is_err());
+        assert!(PodStr::<8>::new("a\0b").is_err());
+    }
+}
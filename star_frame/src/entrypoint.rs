@@ -0,0 +1,73 @@
+//! Program entrypoint and heap configuration.
+
+/// Declares the program entrypoint for a [`StarFrameProgram`](crate::program::StarFrameProgram),
+/// along with its global allocator and panic handler.
+///
+/// The heap can be configured with a second argument:
+/// - `star_frame_entrypoint!(MyProgram)` uses pinocchio's default bump allocator over the 32KiB heap.
+/// - `star_frame_entrypoint!(MyProgram, heap_size = 64 * 1024)` uses a bump allocator over a larger heap.
+///   Transactions calling the program must request the matching heap frame through the compute budget program.
+///   The size must be a multiple of 1024 and at most 256KiB.
+/// - `star_frame_entrypoint!(MyProgram, no_allocator)` uses pinocchio's `no_allocator!`, which aborts
+///   on any heap allocation. Use this together with the `no_alloc` feature.
+///
+/// ```ignore
+/// # use star_frame::prelude::*;
+/// # #[derive(StarFrameProgram)]
+/// # #[program(instruction_set = (), id = System::ID, no_entrypoint)]
+/// # pub struct MyProgram;
+/// star_frame::star_frame_entrypoint!(MyProgram, no_allocator);
+/// ```
+#[macro_export]
+macro_rules! star_frame_entrypoint {
+    ($program:ty $(,)?) => {
+        $crate::star_frame_entrypoint!(@entrypoint $program);
+        $crate::pinocchio::default_allocator!();
+        $crate::pinocchio::default_panic_handler!();
+    };
+    ($program:ty, no_allocator $(,)?) => {
+        $crate::star_frame_entrypoint!(@entrypoint $program);
+        $crate::pinocchio::no_allocator!();
+        $crate::pinocchio::default_panic_handler!();
+    };
+    ($program:ty, heap_size = $heap_size:expr $(,)?) => {
+        $crate::star_frame_entrypoint!(@entrypoint $program);
+
+        const _: () = {
+            assert!(
+                $heap_size % 1024 == 0,
+                "Heap size must be a multiple of 1024"
+            );
+            assert!(
+                $heap_size <= 256 * 1024,
+                "Heap size must be at most 256KiB"
+            );
+        };
+
+        #[cfg(target_os = "solana")]
+        #[global_allocator]
+        static A: $crate::pinocchio::entrypoint::BumpAllocator =
+            $crate::pinocchio::entrypoint::BumpAllocator {
+                start: $crate::pinocchio::entrypoint::HEAP_START_ADDRESS as usize,
+                len: $heap_size,
+            };
+
+        $crate::pinocchio::default_panic_handler!();
+    };
+    (@entrypoint $program:ty) => {
+        $crate::pinocchio::program_entrypoint!(__star_frame_process_instruction);
+
+        #[inline(always)]
+        fn __star_frame_process_instruction(
+            program_id: &'static $crate::prelude::Pubkey,
+            accounts: &[$crate::prelude::AccountInfo],
+            instruction_data: &[u8],
+        ) -> $crate::prelude::ProgramResult {
+            <$program as $crate::program::StarFrameProgram>::entrypoint(
+                program_id,
+                accounts,
+                instruction_data,
+            )
+        }
+    };
+}
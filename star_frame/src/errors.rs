@@ -4,12 +4,22 @@ use std::{
     panic::Location,
 };
 
+use crate::{account_set::PathSegment, program::StarFrameProgram};
 use derive_more::{Deref, DerefMut, Display, Error as DeriveError};
-use itertools::Itertools;
-use pinocchio::program_error::ProgramError;
-use pinocchio_log::{log, logger::Logger};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use pinocchio_log::log;
+#[cfg(not(feature = "compact_errors"))]
+use pinocchio_log::logger::Logger;
 pub use star_frame_proc::star_frame_error;
 
+pub mod record;
+#[cfg(not(target_os = "solana"))]
+mod registry;
+#[cfg(not(target_os = "solana"))]
+pub use record::ErrorRecord;
+#[cfg(not(target_os = "solana"))]
+pub use registry::{DecodedError, ErrorEntry, ErrorRegistry};
+
 /// Error codes for errors emitted by `star_frame`
 #[star_frame_error(offset = 0)]
 pub enum ErrorCode {
@@ -32,10 +42,34 @@ pub enum ErrorCode {
     ConflictingAccountSeeds,
     #[msg("Seeds not set during init")]
     SeedsNotSet,
+    #[msg("A seed is longer than the maximum seed length of 32 bytes")]
+    SeedTooLong,
+    #[msg("More seeds than the maximum of 16, including the bump")]
+    TooManySeeds,
+    #[msg("Seed program not passed in for a runtime seed program")]
+    SeedProgramNotSet,
+    #[msg("The same writable account was passed in for more than one account set")]
+    DuplicateWritableAccount,
+    #[msg("Account's owner does not match the expected owner")]
+    OwnerMismatch,
+    #[msg("Key stored in an account does not match the related account")]
+    HasOneMismatch,
+    #[msg("An account constraint was violated")]
+    ConstraintViolated,
+    #[msg("Account is not executable")]
+    ExpectedExecutable,
+    #[msg("Signer is not a member of the multisig")]
+    NotMultisigMember,
+    #[msg("Not enough multisig members signed")]
+    NotEnoughMultisigSigners,
+    #[msg("Account does not store its bump, so its seeds can only be found during init")]
+    BumpNotStored,
+    #[msg("Account is not initialized")]
+    AccountNotInitialized,
 
     // Unsized Type errors
     #[msg("An unexpected unsized type error occurred. This is a bug in star_frame")]
-    UnsizedUnexpected = 2_000,
+    UnsizedUnexpected = UNSIZED_ERROR_CODE_START,
     #[msg("Pointer out of bounds in unsized type operation")]
     PointerOutOfBounds,
     #[msg("RawSliceAdvance out of bounds")]
@@ -43,7 +77,7 @@ pub enum ErrorCode {
 
     // Invalid input errors
     #[msg("Index out of bounds")]
-    IndexOutOfBounds = 3_000,
+    IndexOutOfBounds = INVALID_INPUT_ERROR_CODE_START,
     #[msg("Invalid range")]
     InvalidRange,
 
@@ -150,6 +184,7 @@ macro_rules! bail {
 }
 
 /// Constructs an [`Error`]
+#[cfg(not(feature = "compact_errors"))]
 #[macro_export]
 macro_rules! error {
     ($err:expr $(,)?) => {
@@ -160,6 +195,84 @@ macro_rules! error {
     };
 }
 
+/// Constructs an [`Error`]
+///
+/// With the `compact_errors` feature, the context is still type checked, but is never formatted.
+#[cfg(feature = "compact_errors")]
+#[macro_export]
+macro_rules! error {
+    ($err:expr $(,)?) => {
+        $crate::errors::Error::new($err)
+    };
+    ($err:expr, $($ctx:tt)*) => {{
+        if false {
+            let _ = ::core::format_args!($($ctx)*);
+        }
+        $crate::errors::Error::new($err)
+    }};
+}
+
+/// The first [`ErrorCode`] for unsized type errors.
+///
+/// Anchor uses 2000-2999 for its constraint errors, which the `anchor_errors` feature reports, so the unsized type
+/// errors move to 8000-8999 to keep every code unique.
+#[cfg(not(feature = "anchor_errors"))]
+const UNSIZED_ERROR_CODE_START: u32 = 2_000;
+#[cfg(feature = "anchor_errors")]
+const UNSIZED_ERROR_CODE_START: u32 = 8_000;
+
+/// The first [`ErrorCode`] for invalid input errors.
+///
+/// Anchor uses 3000-3999 for its account errors, so with the `anchor_errors` feature the invalid input errors move to
+/// 7000-7999.
+#[cfg(not(feature = "anchor_errors"))]
+const INVALID_INPUT_ERROR_CODE_START: u32 = 3_000;
+#[cfg(feature = "anchor_errors")]
+const INVALID_INPUT_ERROR_CODE_START: u32 = 7_000;
+
+/// The ranges of error codes used by [`ErrorCode`], as `(start, end)` with `end` exclusive.
+pub const BUILTIN_ERROR_CODE_RANGES: &[(u32, u32)] = &[
+    // Account set errors
+    (1_000, 2_000),
+    // Unsized type errors
+    (UNSIZED_ERROR_CODE_START, UNSIZED_ERROR_CODE_START + 1_000),
+    // Invalid input errors
+    (
+        INVALID_INPUT_ERROR_CODE_START,
+        INVALID_INPUT_ERROR_CODE_START + 1_000,
+    ),
+    // Conversion errors
+    (9_000, 10_000),
+];
+
+/// The ranges of error codes that [`macro@star_frame_error`] enums are checked at compile time to not use, as
+/// `(start, end)` with `end` exclusive.
+///
+/// These are the [`BUILTIN_ERROR_CODE_RANGES`]. With the `anchor_errors` feature, they also include the codes below
+/// [`ANCHOR_ERROR_CODE_OFFSET`], which Anchor uses for its own errors.
+#[cfg(not(feature = "anchor_errors"))]
+pub const RESERVED_ERROR_CODE_RANGES: &[(u32, u32)] = BUILTIN_ERROR_CODE_RANGES;
+#[cfg(feature = "anchor_errors")]
+pub const RESERVED_ERROR_CODE_RANGES: &[(u32, u32)] = &[
+    // Anchor's errors, which include the account set errors
+    (100, ANCHOR_ERROR_CODE_OFFSET),
+    // Invalid input errors
+    (
+        INVALID_INPUT_ERROR_CODE_START,
+        INVALID_INPUT_ERROR_CODE_START + 1_000,
+    ),
+    // Unsized type errors
+    (UNSIZED_ERROR_CODE_START, UNSIZED_ERROR_CODE_START + 1_000),
+    // Conversion errors
+    (9_000, 10_000),
+];
+
+/// The first error code Anchor uses for program defined errors.
+///
+/// Declaring an error enum with `#[star_frame_error(offset = 6000)]` gives it the same numbering as an
+/// Anchor `#[error_code]` enum.
+pub const ANCHOR_ERROR_CODE_OFFSET: u32 = 6_000;
+
 /// Represents something that can be used as a error.
 ///
 /// Can be converted into an [`Error`] via `From`.
@@ -168,15 +281,97 @@ macro_rules! error {
 pub trait StarFrameError: 'static + Debug {
     fn code(&self) -> u32;
     fn name(&self) -> Cow<'static, str>;
+    fn message(&self) -> Cow<'static, str>;
+}
+
+/// A [`StarFrameError`] enum whose variants can be listed and looked up by code.
+///
+/// Derived alongside [`StarFrameError`] by [`macro@star_frame_error`].
+pub trait ErrorVariants: StarFrameError + Copy {
+    /// Every variant of the enum, in declaration order.
+    const VARIANTS: &'static [Self];
+
+    /// Finds the variant with the given error code.
+    #[must_use]
+    fn from_code(code: u32) -> Option<Self> {
+        Self::VARIANTS
+            .iter()
+            .copied()
+            .find(|variant| variant.code() == code)
+    }
+}
+
+/// The custom errors a [`StarFrameProgram`] can return, used to decode errors from CPIs into the program.
+///
+/// Implemented for every [`ErrorVariants`] enum, and for `()` for programs without custom errors.
+pub trait ProgramErrors {
+    /// Decodes a custom error code returned by the program.
+    fn decode_code(code: u32) -> Option<ErrorKind>;
+}
+
+impl<T> ProgramErrors for T
+where
+    T: ErrorVariants,
+{
+    fn decode_code(code: u32) -> Option<ErrorKind> {
+        T::from_code(code).map(ErrorKind::from)
+    }
+}
+
+impl ProgramErrors for () {
+    fn decode_code(_code: u32) -> Option<ErrorKind> {
+        None
+    }
 }
 
 /// The kind of error. Either a [`ProgramError`] or a custom error implementing [`StarFrameError`].
 #[derive(Debug, Display)]
+#[cfg_attr(feature = "compact_errors", derive(Clone, Copy))]
 pub enum ErrorKind {
     #[display("ProgramError: {_0}")]
     ProgramError(ProgramError),
+    #[cfg(not(feature = "compact_errors"))]
     #[display("StarFrameError: {}", _0.name())]
     Custom(Box<dyn StarFrameError + 'static>),
+    #[cfg(feature = "compact_errors")]
+    #[display("StarFrameError: {}", _0.code())]
+    Custom(CompactError),
+}
+
+impl ErrorKind {
+    #[cfg(not(feature = "compact_errors"))]
+    fn custom(error: impl StarFrameError) -> Self {
+        ErrorKind::Custom(Box::new(error))
+    }
+
+    #[cfg(feature = "compact_errors")]
+    #[allow(clippy::needless_pass_by_value)] // Same signature as the boxing version
+    fn custom(error: impl StarFrameError) -> Self {
+        ErrorKind::Custom(CompactError { code: error.code() })
+    }
+}
+
+/// A [`StarFrameError`] reduced to its code, which is all [`ErrorKind::Custom`] keeps with the
+/// `compact_errors` feature.
+#[cfg(feature = "compact_errors")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactError {
+    code: u32,
+}
+
+#[cfg(feature = "compact_errors")]
+impl StarFrameError for CompactError {
+    fn code(&self) -> u32 {
+        self.code
+    }
+
+    fn name(&self) -> Cow<'static, str> {
+        "Custom".into()
+    }
+
+    fn message(&self) -> Cow<'static, str> {
+        "".into()
+    }
 }
 
 impl PartialEq for ErrorKind {
@@ -190,20 +385,48 @@ impl PartialEq for ErrorKind {
 }
 
 /// The main body of the error struct, which is boxed to form [`Error`].
+///
+/// With the `compact_errors` feature, only the [`ErrorKind`] is kept, and [`Error`] stores it inline. Errors are also
+/// created without `#[track_caller]`, so call sites don't pass a [`Location`] and file paths stay out of the program.
 #[derive(Debug, DeriveError)]
+#[cfg_attr(feature = "compact_errors", derive(Clone, Copy))]
 pub struct ErrorInner {
     kind: ErrorKind,
-    account_path: Vec<&'static str>,
+    #[cfg(not(feature = "compact_errors"))]
+    account_path: Vec<PathSegment>,
+    #[cfg(not(feature = "compact_errors"))]
     initial_ctx: Option<Cow<'static, str>>,
+    #[cfg(not(feature = "compact_errors"))]
     initial_source: ErrorSource,
+    #[cfg(not(feature = "compact_errors"))]
     context: Vec<(ErrorSource, Cow<'static, str>)>,
+    #[cfg(not(feature = "compact_errors"))]
+    cpi: Option<(Pubkey, CpiFailure)>,
+}
+
+/// Where an error converted with [`Error::from_cpi`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpiFailure {
+    /// The callee program returned a custom error code.
+    Callee,
+    /// Invoking the callee failed before it ran, like when an account was already borrowed or a
+    /// signer seed was invalid.
+    Invoke,
 }
 
 /// The error type returned from `star_frame` traits and functions.
+#[cfg(not(feature = "compact_errors"))]
 #[derive(Debug, DeriveError, Display, Deref, DerefMut)]
 pub struct Error(#[error(source)] Box<ErrorInner>);
 
+/// The error type returned from `star_frame` traits and functions.
+#[cfg(feature = "compact_errors")]
+#[derive(Debug, DeriveError, Display, Deref, DerefMut)]
+#[allow(missing_copy_implementations)] // Not `Copy` without `compact_errors`, so code can't rely on it
+pub struct Error(#[error(source)] ErrorInner);
+
 impl std::fmt::Display for ErrorInner {
+    #[cfg(not(feature = "compact_errors"))]
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.kind)?;
         if let Some(initial_ctx) = &self.initial_ctx {
@@ -217,9 +440,16 @@ impl std::fmt::Display for ErrorInner {
             writeln!(
                 f,
                 "For account: {}",
-                self.account_path.iter().rev().join(".")
+                crate::account_set::format_path(self.account_path.iter().rev())
             )?;
         }
+        if let Some((cpi_program, failure)) = &self.cpi {
+            let cpi_program = solana_pubkey::Pubkey::new_from_array(*cpi_program);
+            match failure {
+                CpiFailure::Callee => writeln!(f, "Returned by CPI into: {cpi_program}")?,
+                CpiFailure::Invoke => writeln!(f, "Failed to invoke CPI into: {cpi_program}")?,
+            }
+        }
         if !self.context.is_empty() {
             for (source, ctx) in &self.context {
                 writeln!(f, "{source}: {ctx}")?;
@@ -227,6 +457,11 @@ impl std::fmt::Display for ErrorInner {
         }
         Ok(())
     }
+
+    #[cfg(feature = "compact_errors")]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.kind)
+    }
 }
 
 impl From<Error> for ProgramError {
@@ -271,28 +506,48 @@ mod private {
     impl<T, E> Sealed for Result<T, E> where E: Into<super::Error> {}
 }
 
+/// Maps errors returned by a CPI into typed errors of the callee program. See [`Error::from_cpi`].
+pub trait CpiErrorInfo<T>: private::Sealed {
+    /// Converts the error with [`Error::from_cpi`].
+    fn cpi_err<P: StarFrameProgram + ?Sized>(self) -> Result<T, Error>;
+}
+
+impl<T, E> CpiErrorInfo<T> for Result<T, E>
+where
+    E: Into<Error>,
+{
+    #[inline]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
+    fn cpi_err<P: StarFrameProgram + ?Sized>(self) -> Result<T, Error> {
+        match self {
+            Ok(ok) => Ok(ok),
+            Err(error) => Err(Error::from_cpi::<P>(error)),
+        }
+    }
+}
+
 /// Adds additional context to an error, while automatically converting to the [`Error`] type.
 pub trait ErrorInfo<T>: private::Sealed {
     /// Adds a ctx to the error
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn ctx(self, ctx: &'static str) -> Result<T, Error>;
 
     /// Add a ctx to the error with a closure. The ctx is evaluated lazily, and should be used when
     /// the ctx is not static.
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn with_ctx<C>(self, with_ctx: impl FnOnce() -> C) -> Result<T, Error>
     where
         C: Into<Cow<'static, str>>;
 
-    /// Add an account path to the error, from the inner account name to outermost
-    fn account_path(self, account_path: &'static str) -> Result<T, Error>;
+    /// Add a segment to the error's account path, from the inner account to outermost
+    fn account_path(self, segment: PathSegment) -> Result<T, Error>;
 }
 
 impl<T, E> ErrorInfo<T> for Result<T, E>
 where
     E: Into<Error>,
 {
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn ctx(self, ctx: &'static str) -> Result<T, Error> {
         match self {
             Ok(ok) => Ok(ok),
@@ -300,21 +555,27 @@ where
         }
     }
 
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn with_ctx<C>(self, with_ctx: impl FnOnce() -> C) -> Result<T, Error>
     where
         C: Into<Cow<'static, str>>,
     {
         match self {
             Ok(ok) => Ok(ok),
+            #[cfg(not(feature = "compact_errors"))]
             Err(error) => Err(error.into().push_ctx(with_ctx(), Location::caller())),
+            #[cfg(feature = "compact_errors")]
+            Err(error) => {
+                let _ = with_ctx;
+                Err(error.into())
+            }
         }
     }
 
-    fn account_path(self, account_path: &'static str) -> Result<T, Error> {
+    fn account_path(self, segment: PathSegment) -> Result<T, Error> {
         match self {
             Ok(ok) => Ok(ok),
-            Err(error) => Err(error.into().push_account_path(account_path)),
+            Err(error) => Err(error.into().push_account_path(segment)),
         }
     }
 }
@@ -331,7 +592,7 @@ impl Error {
     /// Creates a new error at the caller's location
     #[cold]
     #[must_use]
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     pub fn new(error: impl CanMakeError) -> Self {
         Self::new_inner(error, None, Location::caller())
     }
@@ -339,12 +600,13 @@ impl Error {
     /// Creates a new error with additional context at the caller's location
     #[cold]
     #[must_use]
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     pub fn new_with_ctx(error: impl CanMakeError, ctx: impl Into<Cow<'static, str>>) -> Self {
         Self::new_inner(error, Some(ctx.into()), Location::caller())
     }
 
     #[cold]
+    #[cfg(not(feature = "compact_errors"))]
     fn new_inner(
         error: impl CanMakeError,
         ctx: Option<Cow<'static, str>>,
@@ -360,13 +622,92 @@ impl Error {
                     line: source.line(),
                 },
                 context: vec![],
+                cpi: None,
             }
             .into(),
         )
     }
 
+    #[cold]
+    #[cfg(feature = "compact_errors")]
+    fn new_inner(
+        error: impl CanMakeError,
+        _ctx: Option<Cow<'static, str>>,
+        _source: &'static Location<'static>,
+    ) -> Self {
+        Error(ErrorInner { kind: error.into() })
+    }
+
+    /// Converts an error returned by a CPI into `P`.
+    ///
+    /// [`ProgramError::Custom`] codes were returned by `P`, and are decoded into [`StarFrameProgram::Errors`] and
+    /// tagged as [`CpiFailure::Callee`]. Anything else was produced locally by the invoke itself, and is kept as is
+    /// and tagged as [`CpiFailure::Invoke`].
+    ///
+    /// On-chain, a callee that fails aborts the whole transaction, so the caller never sees its error and only invoke
+    /// failures reach here. Callee errors are only observable off-chain, like in transaction simulation or a test
+    /// harness that returns them from the CPI.
+    #[cold]
+    #[must_use]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
+    pub fn from_cpi<P: StarFrameProgram + ?Sized>(error: impl Into<Error>) -> Self {
+        let mut error = error.into();
+        let failure = match error.kind {
+            ErrorKind::ProgramError(ProgramError::Custom(code)) => {
+                if let Some(kind) = P::Errors::decode_code(code) {
+                    error.kind = kind;
+                }
+                CpiFailure::Callee
+            }
+            _ => CpiFailure::Invoke,
+        };
+        #[cfg(not(feature = "compact_errors"))]
+        {
+            error.cpi = Some((P::ID, failure));
+        }
+        #[cfg(feature = "compact_errors")]
+        let _ = failure;
+        error
+    }
+
+    /// The program a CPI was made into, if this error came from [`Self::from_cpi`].
+    ///
+    /// Always `None` with the `compact_errors` feature.
+    #[must_use]
+    pub fn cpi_program(&self) -> Option<&Pubkey> {
+        #[cfg(feature = "compact_errors")]
+        {
+            None
+        }
+        #[cfg(not(feature = "compact_errors"))]
+        self.cpi.as_ref().map(|(program, _)| program)
+    }
+
+    /// Whether the CPI this error came from failed in the callee or while invoking it, if it came from
+    /// [`Self::from_cpi`].
+    ///
+    /// Always `None` with the `compact_errors` feature.
+    #[must_use]
+    pub fn cpi_failure(&self) -> Option<CpiFailure> {
+        #[cfg(feature = "compact_errors")]
+        {
+            None
+        }
+        #[cfg(not(feature = "compact_errors"))]
+        self.cpi.map(|(_, failure)| failure)
+    }
+
+    /// Checks if this is the given error, using the code the error is reported with on-chain.
+    pub fn is(&self, error: impl StarFrameError) -> bool {
+        match (&self.kind, ErrorKind::from(error)) {
+            (ErrorKind::Custom(custom), ErrorKind::Custom(other)) => custom.code() == other.code(),
+            _ => false,
+        }
+    }
+
     #[cold]
     #[must_use]
+    #[cfg(not(feature = "compact_errors"))]
     fn push_ctx(
         mut self,
         ctx: impl Into<Cow<'static, str>>,
@@ -384,13 +725,53 @@ impl Error {
 
     #[cold]
     #[must_use]
-    fn push_account_path(mut self, account_path: &'static str) -> Self {
-        self.account_path.push(account_path);
+    #[cfg(feature = "compact_errors")]
+    fn push_ctx(
+        self,
+        _ctx: impl Into<Cow<'static, str>>,
+        _location: &'static Location<'static>,
+    ) -> Self {
+        self
+    }
+
+    #[cold]
+    #[must_use]
+    #[cfg(not(feature = "compact_errors"))]
+    fn push_account_path(mut self, segment: PathSegment) -> Self {
+        self.account_path.push(segment);
+        self
+    }
+
+    #[cold]
+    #[must_use]
+    #[cfg(feature = "compact_errors")]
+    fn push_account_path(self, _segment: PathSegment) -> Self {
         self
     }
 
     /// Logs the error using [`pinocchio_log`]
+    ///
+    /// With the `compact_errors` feature, only the error code is logged.
     pub fn log(&self) {
+        #[cfg(not(feature = "compact_errors"))]
+        self.log_full();
+        #[cfg(feature = "compact_errors")]
+        match &self.kind {
+            ErrorKind::ProgramError(program_error) => {
+                log!("ProgramError: {}", u64::from(*program_error));
+            }
+            ErrorKind::Custom(custom) => log!("StarFrameError: {}", custom.code()),
+        }
+    }
+
+    /// Logs the error along with its context, source location, and account path.
+    ///
+    /// With the `anchor_errors` feature, the first line matches the `AnchorError` log format.
+    #[cfg(not(feature = "compact_errors"))]
+    fn log_full(&self) {
+        #[cfg(feature = "anchor_errors")]
+        self.log_anchor_line();
+        #[cfg(not(feature = "anchor_errors"))]
         {
             let mut logger = Logger::<1000>::default();
             match &self.kind {
@@ -410,27 +791,93 @@ impl Error {
             logger.log();
         }
 
+        #[cfg(feature = "anchor_errors")]
+        if let Some(initial_ctx) = &self.initial_ctx {
+            log!(1000, "Context: {}", initial_ctx.as_ref());
+        }
+
         log!(
             "Occurred at: {}:{}",
             self.initial_source.file,
             self.initial_source.line
         );
 
-        if let Some((last, rest)) = self.account_path.split_last() {
+        if !self.account_path.is_empty() {
             let mut logger = Logger::<200>::default();
             logger.append("For account: ");
-            logger.append(*last);
-            for account in rest.iter().rev() {
-                logger.append(".");
-                logger.append(*account);
-            }
+            append_account_path(&mut logger, &self.account_path);
             logger.log();
         }
 
+        if let Some((cpi_program, failure)) = &self.cpi {
+            match failure {
+                CpiFailure::Callee => log!("Returned by CPI into:"),
+                CpiFailure::Invoke => log!("Failed to invoke CPI into:"),
+            }
+            pinocchio::pubkey::log(cpi_program);
+        }
+
         for (source, ctx) in &self.context {
             log!(1000, "{}:{}: {}", source.file, source.line, ctx.as_ref(),);
         }
     }
+
+    /// Logs the `AnchorError occurred` (or `ProgramError occurred`) line Anchor clients parse.
+    #[cfg(all(feature = "anchor_errors", not(feature = "compact_errors")))]
+    fn log_anchor_line(&self) {
+        let mut logger = Logger::<1000>::default();
+        let (name, number, message) = match &self.kind {
+            ErrorKind::ProgramError(program_error) => {
+                logger.append("ProgramError");
+                (
+                    Cow::Owned(format!("{program_error:?}")),
+                    u64::from(*program_error),
+                    Cow::Owned(program_error.to_string()),
+                )
+            }
+            ErrorKind::Custom(custom) => {
+                logger.append("AnchorError");
+                (custom.name(), u64::from(custom.code()), custom.message())
+            }
+        };
+        if self.account_path.is_empty() {
+            logger.append(" thrown in ");
+            logger.append(self.initial_source.file);
+            logger.append(":");
+            logger.append(self.initial_source.line);
+        } else {
+            logger.append(" caused by account: ");
+            append_account_path(&mut logger, &self.account_path);
+        }
+        logger.append(". Error Code: ");
+        logger.append(name.as_ref());
+        logger.append(". Error Number: ");
+        logger.append(number);
+        logger.append(". Error Message: ");
+        logger.append(message.as_ref());
+        logger.append(".");
+        logger.log();
+    }
+}
+
+/// Appends an account path, stored innermost first, to `logger` from outermost to innermost.
+#[cfg(not(feature = "compact_errors"))]
+fn append_account_path<const N: usize>(logger: &mut Logger<N>, account_path: &[PathSegment]) {
+    for (index, segment) in account_path.iter().rev().enumerate() {
+        match segment {
+            PathSegment::Field(name) => {
+                if index != 0 {
+                    logger.append(".");
+                }
+                logger.append(*name);
+            }
+            PathSegment::Index(element) => {
+                logger.append("[");
+                logger.append(*element);
+                logger.append("]");
+            }
+        }
+    }
 }
 
 // CONVERSIONS
@@ -440,7 +887,14 @@ where
     T: StarFrameError + 'static,
 {
     fn from(error: T) -> Self {
-        ErrorKind::Custom(Box::new(error))
+        #[cfg(feature = "anchor_errors")]
+        if let Some(anchor_error) = (&error as &dyn std::any::Any)
+            .downcast_ref::<ErrorCode>()
+            .and_then(ErrorCode::anchor_equivalent)
+        {
+            return ErrorKind::custom(anchor_error);
+        }
+        ErrorKind::custom(error)
     }
 }
 
@@ -448,78 +902,86 @@ impl<T> From<T> for Error
 where
     T: Into<ErrorKind>,
 {
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn from(value: T) -> Self {
         Error::new_inner(value, None, Location::caller())
     }
 }
 
 impl From<std::io::Error> for Error {
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn from(error: std::io::Error) -> Self {
-        Error::new_inner(
-            ErrorCode::IoError,
-            Some(error.to_string().into()),
-            Location::caller(),
-        )
+        Error::new_inner(ErrorCode::IoError, foreign_ctx(&error), Location::caller())
     }
 }
 
 impl From<bytemuck::PodCastError> for Error {
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn from(error: bytemuck::PodCastError) -> Self {
         Error::new_inner(
             ErrorCode::PodCastError,
-            Some(error.to_string().into()),
+            foreign_ctx(&error),
             Location::caller(),
         )
     }
 }
 
 impl From<bytemuck::checked::CheckedCastError> for Error {
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn from(error: bytemuck::checked::CheckedCastError) -> Self {
         Error::new_inner(
             ErrorCode::CheckedCastError,
-            Some(error.to_string().into()),
+            foreign_ctx(&error),
             Location::caller(),
         )
     }
 }
 
 impl From<advancer::AdvanceError> for Error {
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn from(error: advancer::AdvanceError) -> Self {
         Error::new_inner(
             ErrorCode::AdvanceError,
-            Some(error.to_string().into()),
+            foreign_ctx(&error),
             Location::caller(),
         )
     }
 }
 
 impl From<std::str::Utf8Error> for Error {
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn from(error: std::str::Utf8Error) -> Self {
         Error::new_inner(
             ErrorCode::Utf8Error,
-            Some(error.to_string().into()),
+            foreign_ctx(&error),
             Location::caller(),
         )
     }
 }
 
 impl From<core::array::TryFromSliceError> for Error {
-    #[track_caller]
+    #[cfg_attr(not(feature = "compact_errors"), track_caller)]
     fn from(error: core::array::TryFromSliceError) -> Self {
         Error::new_inner(
             ErrorCode::TryFromSliceError,
-            Some(error.to_string().into()),
+            foreign_ctx(&error),
             Location::caller(),
         )
     }
 }
 
+/// Formats a foreign error as the initial context of an [`Error`], unless `compact_errors` is enabled.
+#[inline]
+fn foreign_ctx(error: &impl ToString) -> Option<Cow<'static, str>> {
+    #[cfg(feature = "compact_errors")]
+    {
+        let _ = error;
+        None
+    }
+    #[cfg(not(feature = "compact_errors"))]
+    Some(error.to_string().into())
+}
+
 // Static error messages with no useful extra information to log.
 
 impl From<ProgramError> for ErrorKind {
@@ -559,26 +1021,131 @@ impl From<solana_pubkey::PubkeyError> for ErrorKind {
     }
 }
 
+#[cfg(feature = "anchor_errors")]
+pub use anchor::AnchorLangError;
+
+#[cfg(feature = "anchor_errors")]
+mod anchor {
+    use super::*;
+
+    /// One of Anchor's built-in `ErrorCode`s, reported in place of an equivalent [`ErrorCode`] when the
+    /// `anchor_errors` feature is enabled.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AnchorLangError {
+        pub code: u32,
+        pub name: &'static str,
+        pub message: &'static str,
+    }
+
+    impl StarFrameError for AnchorLangError {
+        fn code(&self) -> u32 {
+            self.code
+        }
+
+        fn name(&self) -> Cow<'static, str> {
+            self.name.into()
+        }
+
+        fn message(&self) -> Cow<'static, str> {
+            self.message.into()
+        }
+    }
+
+    macro_rules! anchor_lang_error {
+        ($name:ident = $code:literal, $message:literal) => {
+            AnchorLangError {
+                code: $code,
+                name: stringify!($name),
+                message: $message,
+            }
+        };
+    }
+
+    impl ErrorCode {
+        /// Returns the Anchor `ErrorCode` with the same meaning as this error, if there is one.
+        #[must_use]
+        pub fn anchor_equivalent(&self) -> Option<AnchorLangError> {
+            let error = match self {
+                ErrorCode::ReadDiscriminantFailed => {
+                    anchor_lang_error!(
+                        InstructionMissing = 100,
+                        "8 byte instruction identifier not provided"
+                    )
+                }
+                ErrorCode::InvalidDiscriminant => {
+                    anchor_lang_error!(
+                        InstructionFallbackNotFound = 101,
+                        "Fallback functions are not supported"
+                    )
+                }
+                ErrorCode::DiscriminantCastFailed => anchor_lang_error!(
+                    InstructionDidNotDeserialize = 102,
+                    "The program could not deserialize the given instruction"
+                ),
+                ErrorCode::ConflictingAccountSeeds
+                | ErrorCode::SeedsNotSet
+                | ErrorCode::SeedTooLong
+                | ErrorCode::TooManySeeds
+                | ErrorCode::SeedProgramNotSet
+                | ErrorCode::BumpNotStored => {
+                    anchor_lang_error!(ConstraintSeeds = 2006, "A seeds constraint was violated")
+                }
+                ErrorCode::HasOneMismatch => {
+                    anchor_lang_error!(ConstraintHasOne = 2001, "A has one constraint was violated")
+                }
+                ErrorCode::ConstraintViolated => {
+                    anchor_lang_error!(ConstraintRaw = 2003, "A raw constraint was violated")
+                }
+                ErrorCode::OwnerMismatch => {
+                    anchor_lang_error!(ConstraintOwner = 2004, "An owner constraint was violated")
+                }
+                ErrorCode::AddressMismatch => {
+                    anchor_lang_error!(
+                        ConstraintAddress = 2012,
+                        "An address constraint was violated"
+                    )
+                }
+                ErrorCode::DiscriminantMismatch => anchor_lang_error!(
+                    AccountDiscriminatorMismatch = 3002,
+                    "8 byte discriminator did not match what was expected"
+                ),
+                ErrorCode::ExpectedWritable => {
+                    anchor_lang_error!(AccountNotMutable = 3006, "The given account is not mutable")
+                }
+                ErrorCode::ExpectedExecutable => anchor_lang_error!(
+                    AccountNotExecutable = 3009,
+                    "The given account is not executable"
+                ),
+                ErrorCode::ExpectedSigner => {
+                    anchor_lang_error!(AccountNotSigner = 3010, "The given account did not sign")
+                }
+                ErrorCode::AccountNotInitialized => anchor_lang_error!(
+                    AccountNotInitialized = 3012,
+                    "The program expected this account to be already initialized"
+                ),
+                _ => return None,
+            };
+            Some(error)
+        }
+    }
+}
+
 #[cfg(all(feature = "idl", not(target_os = "solana")))]
 mod idl_impls {
     use super::*;
     impl From<star_frame_idl::Error> for Error {
-        #[track_caller]
+        #[cfg_attr(not(feature = "compact_errors"), track_caller)]
         fn from(error: star_frame_idl::Error) -> Self {
-            Error::new_inner(
-                ErrorCode::IdlError,
-                Some(error.to_string().into()),
-                Location::caller(),
-            )
+            Error::new_inner(ErrorCode::IdlError, foreign_ctx(&error), Location::caller())
         }
     }
 
     impl From<serde_json::Error> for Error {
-        #[track_caller]
+        #[cfg_attr(not(feature = "compact_errors"), track_caller)]
         fn from(error: serde_json::Error) -> Self {
             Error::new_inner(
                 ErrorCode::SerdeJsonError,
-                Some(error.to_string().into()),
+                foreign_ctx(&error),
                 Location::caller(),
             )
         }
@@ -609,6 +1176,85 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "anchor_errors")]
+    #[test]
+    fn test_anchor_error_numbers() {
+        let error = Error::from(ErrorCode::ExpectedSigner);
+        error.log();
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(3010));
+        assert_eq!(ErrorCode::IndexOutOfBounds.code(), 7_000);
+        assert_eq!(ErrorCode::InvalidRange.code(), 7_001);
+
+        // The codes Anchor uses for its instruction, IDL, event, constraint, account, state, and misc errors.
+        let anchor_codes = [(100, 1_003), (1_500, 1_501), (2_000, 6_000)];
+        for variant in ErrorCode::VARIANTS {
+            let code = match ProgramError::from(Error::from(*variant)) {
+                ProgramError::Custom(code) => code,
+                error => panic!("{variant:?} was reported as {error:?}"),
+            };
+            if variant.anchor_equivalent().is_none() {
+                assert_eq!(code, variant.code());
+                assert!(
+                    !anchor_codes
+                        .iter()
+                        .any(|(start, end)| (*start..*end).contains(&code)),
+                    "{variant:?} collides with an Anchor error"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_error_code_variants() {
+        for variant in ErrorCode::VARIANTS {
+            assert_eq!(ErrorCode::from_code(variant.code()), Some(*variant));
+            assert!(BUILTIN_ERROR_CODE_RANGES
+                .iter()
+                .any(|(start, end)| (*start..*end).contains(&variant.code())));
+            assert!(RESERVED_ERROR_CODE_RANGES
+                .iter()
+                .any(|(start, end)| (*start..*end).contains(&variant.code())));
+        }
+        assert_eq!(ErrorCode::AddressMismatch.code(), 1_002);
+        assert_eq!(ErrorCode::AddressMismatch.name(), "AddressMismatch");
+        assert_eq!(
+            ErrorCode::AddressMismatch.to_string(),
+            "Account's address does not match expected address"
+        );
+        assert_eq!(ErrorCode::from_code(0), None);
+    }
+
+    #[cfg(feature = "compact_errors")]
+    #[test]
+    fn test_compact_error_size() {
+        // Only the kind is kept, without a boxed context or source location.
+        assert_eq!(size_of::<Error>(), size_of::<ErrorKind>());
+        assert!(size_of::<Result<(), Error>>() <= size_of::<u64>());
+    }
+
+    #[cfg(not(feature = "compact_errors"))]
+    #[test]
+    fn test_from_cpi() {
+        struct Callee;
+        impl StarFrameProgram for Callee {
+            type InstructionSet = ();
+            type AccountDiscriminant = [u8; 8];
+            type Errors = ();
+            const ID: Pubkey = [9; 32];
+        }
+
+        let error = Error::from_cpi::<Callee>(ProgramError::Custom(6000));
+        assert_eq!(error.cpi_program(), Some(&[9; 32]));
+        assert_eq!(error.cpi_failure(), Some(CpiFailure::Callee));
+        assert!(error.to_string().contains("Returned by CPI into"));
+
+        let result: Result<(), _> = Err(ErrorCode::BorrowError);
+        let error = result.cpi_err::<Callee>().unwrap_err();
+        assert!(error.is(ErrorCode::BorrowError));
+        assert_eq!(error.cpi_failure(), Some(CpiFailure::Invoke));
+        assert!(error.to_string().contains("Failed to invoke CPI into"));
+    }
+
     #[test]
     fn test_bail() {
         let _: fn() -> Result<(), Error> = || bail!(ProgramError::IllegalOwner, "Static str");
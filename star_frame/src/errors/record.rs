@@ -0,0 +1,329 @@
+//! Binary error records written through `sol_log_data`, for indexers that aggregate failures without parsing
+//! the text written by [`Error::log`].
+//!
+//! A record is a single `Program data:` entry with the layout (all integers little endian):
+//!
+//! | Bytes            | Content                                                               |
+//! |------------------|-----------------------------------------------------------------------|
+//! | 8                | [`ERROR_RECORD_DISCRIMINANT`]                                         |
+//! | 32               | Program id                                                            |
+//! | 8                | The error as a `u64`, using [`ProgramError`]'s conversion             |
+//! | 4                | [`error_string_hash`] of the initial context, `0` if there is none    |
+//! | 4 + 4            | Hash of the source file and the source line, `0` if unknown           |
+//! | 1 + 5 * n        | Number of account path segments, then each segment, outermost first   |
+//! | 1 + 12 * n       | Number of trace entries, then their file hash, line, and context hash |
+//!
+//! An account path segment is a tag byte, `0` for a struct field and `1` for an element of a collection, followed
+//! by a `u32` of the [`error_string_hash`] of the field's name or the element's index. Indices past `u32::MAX` are
+//! written as `u32::MAX`.
+//!
+//! Strings are hashed rather than written out to keep records small and fixed size. The
+//! [`ErrorRegistry`](super::ErrorRegistry) maps hashes back to strings it knows about, such as static contexts.
+use super::*;
+#[cfg(not(feature = "compact_errors"))]
+use crate::account_set::PathSegment;
+use crate::prelude::Pubkey;
+
+/// The first 8 bytes of every error record.
+pub const ERROR_RECORD_DISCRIMINANT: [u8; 8] = *b"sf_error";
+/// The maximum number of account path segments written to a record. Deeper paths keep the outermost segments.
+pub const MAX_RECORD_ACCOUNT_PATH: usize = 8;
+/// The maximum number of trace entries written to a record. Longer traces keep the earliest entries.
+pub const MAX_RECORD_TRACE: usize = 8;
+
+const HEADER_LEN: usize = 8 + 32 + 8 + 4 + 4 + 4;
+const MAX_RECORD_LEN: usize =
+    HEADER_LEN + 1 + 5 * MAX_RECORD_ACCOUNT_PATH + 1 + 12 * MAX_RECORD_TRACE;
+const FIELD_TAG: u8 = 0;
+const INDEX_TAG: u8 = 1;
+
+/// Hashes a string written to an error record using 32 bit FNV-1a.
+///
+/// `0` is reserved for missing strings, so a string hashing to `0` is written as `1`.
+#[must_use]
+pub const fn error_string_hash(string: &str) -> u32 {
+    let bytes = string.as_bytes();
+    let mut hash: u32 = 0x811c_9dc5;
+    let mut index = 0;
+    while index < bytes.len() {
+        hash ^= bytes[index] as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+        index += 1;
+    }
+    if hash == 0 {
+        1
+    } else {
+        hash
+    }
+}
+
+struct RecordWriter {
+    buffer: [u8; MAX_RECORD_LEN],
+    len: usize,
+}
+
+impl RecordWriter {
+    fn write(&mut self, bytes: &[u8]) {
+        self.buffer[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    #[cfg(not(feature = "compact_errors"))]
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Writes a count of at most [`MAX_RECORD_ACCOUNT_PATH`] or [`MAX_RECORD_TRACE`].
+    #[cfg(not(feature = "compact_errors"))]
+    fn write_len(&mut self, len: usize) {
+        self.write(&[u8::try_from(len).unwrap_or(u8::MAX)]);
+    }
+
+    #[cfg(not(feature = "compact_errors"))]
+    fn write_path_segment(&mut self, segment: &PathSegment) {
+        let (tag, value) = match segment {
+            PathSegment::Field(name) => (FIELD_TAG, error_string_hash(name)),
+            PathSegment::Index(index) => (INDEX_TAG, u32::try_from(*index).unwrap_or(u32::MAX)),
+        };
+        self.write(&[tag]);
+        self.write_u32(value);
+    }
+}
+
+impl Error {
+    /// Logs the error as a binary record through `sol_log_data`. See the [module docs](self) for the layout.
+    ///
+    /// With the `compact_errors` feature, the record only contains the program id and error.
+    pub fn log_data(&self, program_id: &Pubkey) {
+        let mut writer = RecordWriter {
+            buffer: [0; MAX_RECORD_LEN],
+            len: 0,
+        };
+        self.write_record(program_id, &mut writer);
+        pinocchio::log::sol_log_data(&[&writer.buffer[..writer.len]]);
+    }
+
+    /// Emits the error the way the program is configured to, through [`Self::log_data`] with the
+    /// `binary_errors` feature, or [`Self::log`] otherwise.
+    pub fn emit(&self, program_id: &Pubkey) {
+        #[cfg(feature = "binary_errors")]
+        self.log_data(program_id);
+        #[cfg(not(feature = "binary_errors"))]
+        {
+            let _ = program_id;
+            self.log();
+        }
+    }
+
+    fn write_record(&self, program_id: &Pubkey, writer: &mut RecordWriter) {
+        let error = match &self.kind {
+            ErrorKind::ProgramError(program_error) => u64::from(*program_error),
+            ErrorKind::Custom(custom) => u64::from(ProgramError::Custom(custom.code())),
+        };
+        writer.write(&ERROR_RECORD_DISCRIMINANT);
+        writer.write(program_id);
+        writer.write(&error.to_le_bytes());
+
+        #[cfg(feature = "compact_errors")]
+        writer.write(&[0; 4 + 4 + 4 + 1 + 1]);
+        #[cfg(not(feature = "compact_errors"))]
+        {
+            writer.write_u32(self.initial_ctx.as_deref().map_or(0, error_string_hash));
+            writer.write_u32(error_string_hash(self.initial_source.file));
+            writer.write_u32(self.initial_source.line);
+
+            let path_len = self.account_path.len().min(MAX_RECORD_ACCOUNT_PATH);
+            writer.write_len(path_len);
+            for segment in self.account_path.iter().rev().take(path_len) {
+                writer.write_path_segment(segment);
+            }
+
+            let trace_len = self.context.len().min(MAX_RECORD_TRACE);
+            writer.write_len(trace_len);
+            for (source, ctx) in self.context.iter().take(trace_len) {
+                writer.write_u32(error_string_hash(source.file));
+                writer.write_u32(source.line);
+                writer.write_u32(error_string_hash(ctx));
+            }
+        }
+    }
+}
+
+/// A source location in an [`ErrorRecord`].
+#[cfg(not(target_os = "solana"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecordSource {
+    /// [`error_string_hash`] of the file.
+    pub file: u32,
+    pub line: u32,
+}
+
+/// A segment of the account path in an [`ErrorRecord`].
+#[cfg(not(target_os = "solana"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordPathSegment {
+    /// A struct field, by the [`error_string_hash`] of its name.
+    Field(u32),
+    /// An element of a collection, by its index, saturating at `u32::MAX`.
+    Index(u32),
+}
+
+/// A binary error record parsed off-chain. See the [module docs](self) for the layout.
+///
+/// A record is a partial [`ErrorInner`]. The program id, error, source line, account path indices, and trace lines
+/// are restored as they were, but strings, including the account path's field names, are only restored as their
+/// [`error_string_hash`].
+#[cfg(not(target_os = "solana"))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErrorRecord {
+    /// The program that returned the error.
+    pub program_id: Pubkey,
+    /// The error as a `u64`, which converts back to a [`ProgramError`].
+    pub error: u64,
+    /// [`error_string_hash`] of the initial context.
+    pub context: Option<u32>,
+    /// Where the error was created.
+    pub source: Option<RecordSource>,
+    /// The account path to the failing account, from outermost to innermost.
+    pub account_path: Vec<RecordPathSegment>,
+    /// Context added while the error propagated, as hashes.
+    pub trace: Vec<(RecordSource, u32)>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl ErrorRecord {
+    /// Parses a record from the bytes of a single `sol_log_data` entry into the parts of the [`ErrorInner`] it
+    /// can restore. Returns `None` if the bytes aren't an error record.
+    #[must_use]
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+            let (taken, rest) = bytes.split_at_checked(len)?;
+            *bytes = rest;
+            Some(taken)
+        }
+        fn take_u32(bytes: &mut &[u8]) -> Option<u32> {
+            Some(u32::from_le_bytes(take(bytes, 4)?.try_into().ok()?))
+        }
+        fn non_zero(hash: u32) -> Option<u32> {
+            (hash != 0).then_some(hash)
+        }
+
+        let bytes = &mut bytes.strip_prefix(&ERROR_RECORD_DISCRIMINANT)?;
+        let program_id = take(bytes, 32)?.try_into().ok()?;
+        let error = u64::from_le_bytes(take(bytes, 8)?.try_into().ok()?);
+        let context = non_zero(take_u32(bytes)?);
+        let file = take_u32(bytes)?;
+        let line = take_u32(bytes)?;
+        let source = (file != 0).then_some(RecordSource { file, line });
+
+        let path_len = take(bytes, 1)?[0];
+        let account_path = (0..path_len)
+            .map(|_| {
+                let tag = take(bytes, 1)?[0];
+                let value = take_u32(bytes)?;
+                match tag {
+                    FIELD_TAG => Some(RecordPathSegment::Field(value)),
+                    INDEX_TAG => Some(RecordPathSegment::Index(value)),
+                    _ => None,
+                }
+            })
+            .collect::<Option<_>>()?;
+
+        let trace_len = take(bytes, 1)?[0];
+        let trace = (0..trace_len)
+            .map(|_| {
+                let source = RecordSource {
+                    file: take_u32(bytes)?,
+                    line: take_u32(bytes)?,
+                };
+                Some((source, take_u32(bytes)?))
+            })
+            .collect::<Option<_>>()?;
+
+        bytes.is_empty().then_some(Self {
+            program_id,
+            error,
+            context,
+            source,
+            account_path,
+            trace,
+        })
+    }
+
+    /// Parses every error record in the `Program data:` lines of a transaction's logs.
+    #[must_use]
+    pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<Self> {
+        use base64::Engine;
+
+        logs.iter()
+            .filter_map(|line| line.as_ref().strip_prefix("Program data: "))
+            .flat_map(str::split_whitespace)
+            .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+            .filter_map(|bytes| Self::parse(&bytes))
+            .collect()
+    }
+}
+
+#[cfg(all(test, not(feature = "compact_errors")))]
+impl Error {
+    /// Writes the error's record and parses it back, as an indexer would see it.
+    pub(super) fn to_record(&self, program_id: &Pubkey) -> ErrorRecord {
+        let mut writer = RecordWriter {
+            buffer: [0; MAX_RECORD_LEN],
+            len: 0,
+        };
+        self.write_record(program_id, &mut writer);
+        ErrorRecord::parse(&writer.buffer[..writer.len]).unwrap()
+    }
+}
+
+#[cfg(all(test, not(feature = "compact_errors")))]
+mod tests {
+    use super::*;
+
+    const PROGRAM_ID: Pubkey = [3; 32];
+
+    #[test]
+    fn test_record_round_trip() {
+        let error = Error::new_with_ctx(ErrorCode::AddressMismatch, "bad counter")
+            .push_account_path(PathSegment::Field("counter"))
+            .push_account_path(PathSegment::Index(5_000_000_000))
+            .push_account_path(PathSegment::Field("hops"))
+            .push_ctx("Failed to validate accounts", Location::caller());
+        let mut writer = RecordWriter {
+            buffer: [0; MAX_RECORD_LEN],
+            len: 0,
+        };
+        error.write_record(&PROGRAM_ID, &mut writer);
+
+        let trace_source = error.context[0].0;
+        let expected = ErrorRecord {
+            program_id: PROGRAM_ID,
+            error: u64::from(ProgramError::from(Error::from(ErrorCode::AddressMismatch))),
+            context: Some(error_string_hash("bad counter")),
+            source: Some(RecordSource {
+                file: error_string_hash(file!()),
+                line: error.initial_source.line,
+            }),
+            account_path: vec![
+                RecordPathSegment::Field(error_string_hash("hops")),
+                RecordPathSegment::Index(u32::MAX),
+                RecordPathSegment::Field(error_string_hash("counter")),
+            ],
+            trace: vec![(
+                RecordSource {
+                    file: error_string_hash(file!()),
+                    line: trace_source.line,
+                },
+                error_string_hash("Failed to validate accounts"),
+            )],
+        };
+        assert_eq!(
+            ErrorRecord::parse(&writer.buffer[..writer.len]),
+            Some(expected)
+        );
+
+        assert_eq!(ErrorRecord::parse(&writer.buffer[..writer.len - 1]), None);
+        assert_eq!(ErrorRecord::parse(&writer.buffer[1..writer.len]), None);
+    }
+}
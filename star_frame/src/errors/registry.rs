@@ -0,0 +1,613 @@
+//! Off-chain decoding of [`StarFrameError`] codes and [`Error::log`] output back into typed errors.
+//!
+//! The [`ErrorRegistry`] always knows the built-in [`ErrorCode`]s, and program specific error enums are
+//! registered under their program id. Failed transactions (or mollusk results) can then be decoded from
+//! just the custom error code, or from the code plus the transaction logs for full context.
+use super::{
+    record::{error_string_hash, ErrorRecord, RecordPathSegment, RecordSource},
+    *,
+};
+use crate::prelude::Pubkey;
+use std::collections::BTreeMap;
+
+/// A single registered error variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorEntry {
+    pub code: u32,
+    pub name: Cow<'static, str>,
+    pub message: Cow<'static, str>,
+}
+
+impl ErrorEntry {
+    fn new(error: &impl StarFrameError) -> Self {
+        Self {
+            code: error.code(),
+            name: error.name(),
+            message: error.message(),
+        }
+    }
+}
+
+/// Collects [`StarFrameError`] enums keyed by program id, and decodes failed transactions back into [`DecodedError`]s.
+///
+/// ```ignore
+/// # use star_frame::{errors::ErrorRegistry, prelude::*};
+/// let registry = ErrorRegistry::new().with_errors::<MyError>(MyProgram::ID);
+///
+/// // With mollusk, the custom code comes from `ProgramResult::Failure(ProgramError::Custom(code))`
+/// let error = registry.decode(&MyProgram::ID, code);
+/// assert!(error.is(MyError::IncorrectAuthority));
+///
+/// // With the transaction logs, the context, source location, and account path are recovered too
+/// let error = registry.decode_logs(&MyProgram::ID, code, &logs);
+/// println!("{error}");
+///
+/// // With the `binary_errors` feature, strings are hashed and need to be registered to be recovered
+/// let registry = registry.with_strings(["Failed to validate accounts", "src/lib.rs", "counter"]);
+/// let error = registry.decode_data_logs(&MyProgram::ID, &logs).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ErrorRegistry {
+    builtin: BTreeMap<u32, ErrorEntry>,
+    programs: BTreeMap<Pubkey, BTreeMap<u32, ErrorEntry>>,
+    strings: BTreeMap<u32, Cow<'static, str>>,
+}
+
+/// The entry an [`ErrorCode`] is reported as, which is its Anchor equivalent with the `anchor_errors` feature.
+fn builtin_entry(code: ErrorCode) -> ErrorEntry {
+    #[cfg(feature = "anchor_errors")]
+    if let Some(anchor_error) = code.anchor_equivalent() {
+        return ErrorEntry::new(&anchor_error);
+    }
+    ErrorEntry::new(&code)
+}
+
+impl Default for ErrorRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorRegistry {
+    /// Creates a registry that knows the built-in [`ErrorCode`]s, which are shared by every program.
+    #[must_use]
+    pub fn new() -> Self {
+        let builtin = ErrorCode::VARIANTS
+            .iter()
+            .copied()
+            .map(builtin_entry)
+            .map(|entry| (entry.code, entry))
+            .collect();
+        Self {
+            builtin,
+            programs: BTreeMap::new(),
+            strings: BTreeMap::new(),
+        }
+    }
+
+    /// Registers every variant of `E` as an error of `program_id`.
+    pub fn register<E: ErrorVariants>(&mut self, program_id: Pubkey) -> &mut Self {
+        let program = self.programs.entry(program_id).or_default();
+        for variant in E::VARIANTS {
+            program.insert(variant.code(), ErrorEntry::new(variant));
+        }
+        self
+    }
+
+    /// Builder version of [`Self::register`].
+    #[must_use]
+    pub fn with_errors<E: ErrorVariants>(mut self, program_id: Pubkey) -> Self {
+        self.register::<E>(program_id);
+        self
+    }
+
+    /// Registers strings that may appear hashed in an [`ErrorRecord`], such as contexts, source files, and the field
+    /// names in account paths.
+    pub fn register_strings(
+        &mut self,
+        strings: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> &mut Self {
+        for string in strings {
+            let string = string.into();
+            self.strings.insert(error_string_hash(&string), string);
+        }
+        self
+    }
+
+    /// Builder version of [`Self::register_strings`].
+    #[must_use]
+    pub fn with_strings(
+        mut self,
+        strings: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> Self {
+        self.register_strings(strings);
+        self
+    }
+
+    /// Looks up an error code, preferring errors registered for `program_id` over the built-in errors.
+    #[must_use]
+    pub fn lookup(&self, program_id: &Pubkey, code: u32) -> Option<&ErrorEntry> {
+        self.programs
+            .get(program_id)
+            .and_then(|program| program.get(&code))
+            .or_else(|| self.builtin.get(&code))
+    }
+
+    /// Decodes a custom error code returned by `program_id`.
+    #[must_use]
+    pub fn decode(&self, program_id: &Pubkey, code: u32) -> DecodedError {
+        DecodedError {
+            program_id: *program_id,
+            code,
+            entry: self.lookup(program_id, code).cloned(),
+            context: None,
+            source: None,
+            account_path: vec![],
+            trace: vec![],
+        }
+    }
+
+    /// Decodes a [`ProgramError`] returned by `program_id`. Returns `None` for non custom errors.
+    #[must_use]
+    pub fn decode_program_error(
+        &self,
+        program_id: &Pubkey,
+        error: &ProgramError,
+    ) -> Option<DecodedError> {
+        match error {
+            ProgramError::Custom(code) => Some(self.decode(program_id, *code)),
+            _ => None,
+        }
+    }
+
+    /// Decodes a custom error code along with the logs of the failed transaction, recovering the context,
+    /// source location, and account path written by [`Error::log`].
+    ///
+    /// Only log lines emitted by the last invocation of `program_id` are considered. Logs without any
+    /// `Program <id> invoke` lines are assumed to all come from `program_id`.
+    #[must_use]
+    pub fn decode_logs<S: AsRef<str>>(
+        &self,
+        program_id: &Pubkey,
+        code: u32,
+        logs: &[S],
+    ) -> DecodedError {
+        let mut decoded = self.decode(program_id, code);
+        let program = solana_pubkey::Pubkey::new_from_array(*program_id).to_string();
+        let mut lines = program_lines(&program, logs).into_iter();
+
+        for line in lines.by_ref() {
+            if parse_header(line, &mut decoded) {
+                break;
+            }
+        }
+        for line in lines {
+            if let Some(ctx) = line.strip_prefix("Context: ") {
+                decoded.context = Some(ctx.to_string());
+            } else if let Some(source) = line.strip_prefix("Occurred at: ") {
+                decoded.source = DecodedSource::parse(source);
+            } else if let Some(path) = line.strip_prefix("For account: ") {
+                decoded.account_path = parse_account_path(path);
+            } else if let Some((source, ctx)) = line
+                .split_once(": ")
+                .and_then(|(source, ctx)| Some((DecodedSource::parse(source)?, ctx)))
+            {
+                decoded.trace.push((source, ctx.to_string()));
+            }
+        }
+        decoded
+    }
+}
+
+impl ErrorRegistry {
+    /// Decodes a binary [`ErrorRecord`], resolving hashes through the registered strings.
+    /// Unknown hashes are rendered as `#<hash>`. Account path fields are resolved like any other string, and
+    /// collection elements are rendered as `[<index>]`. Returns `None` for non custom errors.
+    #[must_use]
+    pub fn decode_record(&self, record: &ErrorRecord) -> Option<DecodedError> {
+        let mut decoded =
+            self.decode_program_error(&record.program_id, &ProgramError::from(record.error))?;
+        decoded.context = record.context.map(|hash| self.resolve(hash));
+        decoded.source = record.source.map(|source| self.resolve_source(source));
+        decoded.account_path = record
+            .account_path
+            .iter()
+            .map(|segment| match segment {
+                RecordPathSegment::Field(name) => self.resolve(*name),
+                RecordPathSegment::Index(index) => format!("[{index}]"),
+            })
+            .collect();
+        decoded.trace = record
+            .trace
+            .iter()
+            .map(|(source, ctx)| (self.resolve_source(*source), self.resolve(*ctx)))
+            .collect();
+        Some(decoded)
+    }
+
+    /// Decodes the last [`ErrorRecord`] written by `program_id` in a transaction's logs.
+    #[must_use]
+    pub fn decode_data_logs<S: AsRef<str>>(
+        &self,
+        program_id: &Pubkey,
+        logs: &[S],
+    ) -> Option<DecodedError> {
+        ErrorRecord::parse_logs(logs)
+            .iter()
+            .rev()
+            .find(|record| &record.program_id == program_id)
+            .and_then(|record| self.decode_record(record))
+    }
+
+    fn resolve(&self, hash: u32) -> String {
+        self.strings
+            .get(&hash)
+            .map_or_else(|| format!("#{hash:08x}"), ToString::to_string)
+    }
+
+    fn resolve_source(&self, source: RecordSource) -> DecodedSource {
+        DecodedSource {
+            file: self.resolve(source.file),
+            line: source.line,
+        }
+    }
+}
+
+/// Collects the log messages emitted by the last invocation of `program`, with the `Program log: ` prefix removed.
+fn program_lines<'a, S: AsRef<str>>(program: &str, logs: &'a [S]) -> Vec<&'a str> {
+    let mut invoke_stack: Vec<&str> = vec![];
+    let mut saw_invoke = false;
+    let mut lines = vec![];
+    for line in logs {
+        let line = line.as_ref();
+        if let Some((id, status)) = line
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(' '))
+            .filter(|(id, _)| !matches!(*id, "log:" | "data:" | "return:"))
+        {
+            if status.starts_with("invoke [") {
+                saw_invoke = true;
+                if id == program {
+                    lines.clear();
+                }
+                invoke_stack.push(id);
+                continue;
+            }
+            if status == "success" || status.starts_with("failed") {
+                invoke_stack.pop();
+                continue;
+            }
+        }
+        let message = match line.strip_prefix("Program log: ") {
+            Some(message) => message,
+            None if line.starts_with("Program ") => continue,
+            None => line,
+        };
+        if !saw_invoke || invoke_stack.last() == Some(&program) {
+            lines.push(message);
+        }
+    }
+    lines
+}
+
+/// Parses the first line written by [`Error::log`], in the default, `compact_errors`, or `anchor_errors` format.
+/// Returns `false` if the line isn't an error header.
+fn parse_header(line: &str, decoded: &mut DecodedError) -> bool {
+    if let Some(rest) = line
+        .strip_prefix("StarFrameError: ")
+        .or_else(|| line.strip_prefix("ProgramError: "))
+    {
+        if let Some((_name, ctx)) = rest.split_once(" - ") {
+            decoded.context = Some(ctx.to_string());
+        }
+        return true;
+    }
+
+    let Some(rest) = line
+        .strip_prefix("AnchorError ")
+        .or_else(|| line.strip_prefix("ProgramError "))
+    else {
+        return false;
+    };
+    let Some((origin, _)) = rest.split_once(". Error Code: ") else {
+        return false;
+    };
+    if let Some(source) = origin.strip_prefix("thrown in ") {
+        decoded.source = DecodedSource::parse(source);
+    } else if let Some(path) = origin.strip_prefix("caused by account: ") {
+        decoded.account_path = parse_account_path(path);
+    }
+    true
+}
+
+/// Splits an account path written by [`Error::log`], such as `hops[2].pool`, into its segments, with collection
+/// elements as `[<index>]` segments.
+fn parse_account_path(path: &str) -> Vec<String> {
+    path.split('.')
+        .flat_map(|field| field.split_inclusive(']'))
+        .flat_map(|segment| match segment.split_once('[') {
+            Some((name, index)) => [
+                (!name.is_empty()).then(|| name.to_string()),
+                Some(format!("[{index}")),
+            ],
+            None => [Some(segment.to_string()), None],
+        })
+        .flatten()
+        .collect()
+}
+
+/// A source location recovered from the logs.
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[display("{}:{}", file, line)]
+pub struct DecodedSource {
+    pub file: String,
+    pub line: u32,
+}
+
+impl DecodedSource {
+    fn parse(source: &str) -> Option<Self> {
+        let (file, line) = source.rsplit_once(':')?;
+        Some(Self {
+            file: file.to_string(),
+            line: line.parse().ok()?,
+        })
+    }
+}
+
+/// An error decoded off-chain by an [`ErrorRegistry`]. Mirrors the information in [`ErrorInner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedError {
+    /// The program that returned the error.
+    pub program_id: Pubkey,
+    /// The custom error code.
+    pub code: u32,
+    /// The registered error for the code, if any.
+    pub entry: Option<ErrorEntry>,
+    /// The initial context passed to the error.
+    pub context: Option<String>,
+    /// Where the error was created.
+    pub source: Option<DecodedSource>,
+    /// The account path to the failing account, from outermost to innermost. Collection elements are `[<index>]`.
+    pub account_path: Vec<String>,
+    /// Context added while the error propagated.
+    pub trace: Vec<(DecodedSource, String)>,
+}
+
+impl DecodedError {
+    /// Returns the name of the error, if the code is registered.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.entry.as_ref().map(|entry| entry.name.as_ref())
+    }
+
+    /// Returns the message of the error, if the code is registered.
+    #[must_use]
+    pub fn message(&self) -> Option<&str> {
+        self.entry.as_ref().map(|entry| entry.message.as_ref())
+    }
+
+    /// Checks if this is the given error, using the code the error is reported with on-chain.
+    pub fn is(&self, error: impl StarFrameError) -> bool {
+        match ErrorKind::from(error) {
+            ErrorKind::Custom(custom) => custom.code() == self.code,
+            ErrorKind::ProgramError(_) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for DecodedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.entry {
+            Some(entry) => write!(f, "{}: {} ({})", entry.name, entry.message, self.code)?,
+            None => write!(f, "Unknown error ({})", self.code)?,
+        }
+        if let Some(context) = &self.context {
+            write!(f, " - {context}")?;
+        }
+        writeln!(f)?;
+        if let Some(source) = &self.source {
+            writeln!(f, "Occurred at: {source}")?;
+        }
+        if let Some((first, rest)) = self.account_path.split_first() {
+            write!(f, "For account: {first}")?;
+            for segment in rest {
+                if segment.starts_with('[') {
+                    write!(f, "{segment}")?;
+                } else {
+                    write!(f, ".{segment}")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        for (source, ctx) in &self.trace {
+            writeln!(f, "{source}: {ctx}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM_ID: Pubkey = [1; 32];
+
+    fn program() -> String {
+        solana_pubkey::Pubkey::new_from_array(PROGRAM_ID).to_string()
+    }
+
+    #[test]
+    fn test_builtin_codes_unique() {
+        let mut entries = BTreeMap::new();
+        for code in ErrorCode::VARIANTS {
+            let entry = builtin_entry(*code);
+            // Several errors can be reported as the same Anchor error, but different errors can't share a code.
+            if let Some(existing) = entries.insert(entry.code, entry.clone()) {
+                assert_eq!(
+                    existing, entry,
+                    "`{code:?}` shares its code with another error"
+                );
+            }
+        }
+        assert_eq!(ErrorRegistry::new().builtin, entries);
+    }
+
+    #[cfg(not(feature = "anchor_errors"))]
+    #[test]
+    fn test_decode_builtin() {
+        let registry = ErrorRegistry::new();
+        let code = ErrorCode::AddressMismatch.code();
+        let decoded = registry.decode(&PROGRAM_ID, code);
+        assert!(decoded.is(ErrorCode::AddressMismatch));
+        assert_eq!(decoded.name(), Some("AddressMismatch"));
+        assert_eq!(
+            registry.decode_program_error(&PROGRAM_ID, &ProgramError::Custom(code)),
+            Some(decoded)
+        );
+        assert_eq!(
+            registry.decode_program_error(&PROGRAM_ID, &ProgramError::InvalidSeeds),
+            None
+        );
+        assert_eq!(registry.decode(&PROGRAM_ID, u32::MAX).entry, None);
+    }
+
+    #[cfg(not(feature = "anchor_errors"))]
+    #[test]
+    fn test_decode_logs() {
+        let registry = ErrorRegistry::new();
+        let program = program();
+        let logs = vec![
+            format!("Program {program} invoke [1]"),
+            "Program log: Instruction: Increment".to_string(),
+            "Program 11111111111111111111111111111111 invoke [2]".to_string(),
+            "Program log: StarFrameError: ExpectedSigner - not from us".to_string(),
+            "Program 11111111111111111111111111111111 success".to_string(),
+            "Program log: StarFrameError: AddressMismatch - expected `a`, found `b`".to_string(),
+            "Program log: Occurred at: src/account_set/seeded.rs:240".to_string(),
+            "Program log: For account: accounts.counter".to_string(),
+            "Program log: src/instruction/mod.rs:230: Failed to validate accounts".to_string(),
+            format!("Program {program} consumed 5000 of 200000 compute units"),
+            format!("Program {program} failed: custom program error: 0x3ea"),
+        ];
+        let decoded = registry.decode_logs(&PROGRAM_ID, ErrorCode::AddressMismatch.code(), &logs);
+        assert_eq!(decoded.name(), Some("AddressMismatch"));
+        assert_eq!(decoded.context.as_deref(), Some("expected `a`, found `b`"));
+        assert_eq!(
+            decoded.source,
+            Some(DecodedSource {
+                file: "src/account_set/seeded.rs".to_string(),
+                line: 240,
+            })
+        );
+        assert_eq!(decoded.account_path, vec!["accounts", "counter"]);
+        assert_eq!(
+            decoded.trace,
+            vec![(
+                DecodedSource {
+                    file: "src/instruction/mod.rs".to_string(),
+                    line: 230,
+                },
+                "Failed to validate accounts".to_string()
+            )]
+        );
+        println!("{decoded}");
+    }
+
+    #[test]
+    fn test_decode_data_logs() {
+        use crate::errors::record::ERROR_RECORD_DISCRIMINANT;
+        use base64::Engine;
+
+        let registry = ErrorRegistry::new();
+        let record = ErrorRecord {
+            program_id: PROGRAM_ID,
+            error: u64::from(ProgramError::Custom(1234)),
+            context: Some(error_string_hash("bad counter")),
+            source: None,
+            account_path: vec![
+                RecordPathSegment::Field(error_string_hash("hops")),
+                RecordPathSegment::Index(3),
+            ],
+            trace: vec![],
+        };
+        let mut bytes = ERROR_RECORD_DISCRIMINANT.to_vec();
+        bytes.extend_from_slice(&PROGRAM_ID);
+        bytes.extend_from_slice(&record.error.to_le_bytes());
+        bytes.extend_from_slice(&record.context.unwrap().to_le_bytes());
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(&[2, 0]);
+        bytes.extend_from_slice(&error_string_hash("hops").to_le_bytes());
+        bytes.extend_from_slice(&[1, 3, 0, 0, 0, 0]);
+        let logs = [
+            format!("Program {} invoke [1]", program()),
+            format!(
+                "Program data: {}",
+                base64::engine::general_purpose::STANDARD.encode(&bytes)
+            ),
+        ];
+        assert_eq!(ErrorRecord::parse_logs(&logs), vec![record]);
+
+        let decoded = registry.decode_data_logs(&PROGRAM_ID, &logs).unwrap();
+        assert_eq!(decoded.code, 1234);
+        assert_eq!(
+            decoded.account_path,
+            vec![
+                format!("#{:08x}", error_string_hash("hops")),
+                "[3]".to_string()
+            ]
+        );
+        assert_eq!(
+            decoded.context,
+            Some(format!("#{:08x}", error_string_hash("bad counter")))
+        );
+        assert_eq!(registry.decode_data_logs(&[9; 32], &logs), None);
+
+        let decoded = registry
+            .with_strings(["hops"])
+            .decode_data_logs(&PROGRAM_ID, &logs)
+            .unwrap();
+        assert_eq!(decoded.account_path, vec!["hops", "[3]"]);
+    }
+
+    #[cfg(not(feature = "compact_errors"))]
+    #[test]
+    fn test_decode_logs_account_path() {
+        let error = Error::new(ErrorCode::AddressMismatch)
+            .push_account_path(crate::account_set::PathSegment::Field("pool"))
+            .push_account_path(crate::account_set::PathSegment::Index(2))
+            .push_account_path(crate::account_set::PathSegment::Field("hops"));
+        let message = error.to_string();
+        let logs = [
+            "StarFrameError: AddressMismatch",
+            message
+                .lines()
+                .find(|line| line.starts_with("For account: "))
+                .unwrap(),
+        ];
+        let registry = ErrorRegistry::new().with_strings(["hops", "pool"]);
+        let from_logs = registry.decode_logs(&PROGRAM_ID, ErrorCode::AddressMismatch.code(), &logs);
+        assert_eq!(from_logs.account_path, vec!["hops", "[2]", "pool"]);
+        let from_record = registry
+            .decode_record(&error.to_record(&PROGRAM_ID))
+            .unwrap();
+        assert_eq!(from_record.account_path, from_logs.account_path);
+        assert_eq!(
+            from_logs.to_string().lines().nth(1),
+            Some("For account: hops[2].pool")
+        );
+    }
+
+    #[test]
+    fn test_decode_anchor_logs() {
+        let registry = ErrorRegistry::new();
+        let logs = [
+            "AnchorError caused by account: counter. Error Code: ConstraintAddress. Error Number: 2012. Error Message: An address constraint was violated.",
+            "Context: expected `a`, found `b`",
+            "Occurred at: src/account_set/seeded.rs:240",
+        ];
+        let decoded = registry.decode_logs(&PROGRAM_ID, 2012, &logs);
+        assert_eq!(decoded.account_path, vec!["counter"]);
+        assert_eq!(decoded.context.as_deref(), Some("expected `a`, found `b`"));
+        assert_eq!(decoded.source.map(|source| source.line), Some(240));
+    }
+}
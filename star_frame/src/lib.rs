@@ -21,14 +21,14 @@
 // pub extern crate advancer;
 // pub extern crate borsh;
 // pub extern crate bytemuck;
-// pub extern crate derive_more;
+pub extern crate derive_more;
 // pub extern crate derive_where;
 // pub extern crate fixed;
 // pub extern crate itertools;
 // pub extern crate num_traits;
 // pub extern crate paste;
-// pub extern crate pinocchio;
-// pub extern crate self as star_frame;
+pub extern crate pinocchio;
+pub extern crate self as star_frame;
 // pub extern crate serde;
 // #[cfg(all(feature = "idl", not(target_os = "solana")))]
 // pub extern crate serde_json;
@@ -39,22 +39,24 @@
 // pub extern crate static_assertions;
 // pub extern crate typenum;
 
-// pub mod account_set;
+pub mod account_set;
 // pub mod align1;
 // pub mod client;
 // pub mod cpi;
-// pub mod data_types;
-// mod entrypoint;
-// pub mod errors;
+pub mod data_types;
+mod entrypoint;
+pub mod errors;
 
 pub mod context;
 // #[cfg(all(feature = "idl", not(target_os = "solana")))]
 // pub mod idl;
 pub mod instruction;
 pub mod prelude;
-// pub mod program;
+pub mod program;
 // pub mod unsize;
-// pub mod util;
+#[cfg(feature = "test_helpers")]
+pub mod test_helpers;
+pub mod util;
 
 // /// Internal paths mainly for use in macros. DO NOT USE MANUALLY. NOT PART OF THE PUBLIC API.
 // #[doc(hidden)]
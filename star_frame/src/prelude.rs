@@ -1,6 +1,37 @@
 // TODO: create a prelude module for star_frame
 
-pub use crate::{context::Context, ensure, Result};
+pub use crate::{
+    account_set::{
+        modifiers::{
+            CanInitAccount, CanInitSeeds, HasInnerType, HasOwnerProgram, HasSeeds, ReadAccount,
+            SignedAccount, WritableAccount,
+        },
+        seeded::{
+            check_seeds, const_program_address, derive_program_address, CurrentProgram, GetSeeds,
+            RuntimeProgram, Seed, SeedProgram, Seeded, Seeds, SeedsWithBump, SignerSeeds,
+            SkipCurveCheck, WithProgram, MAX_SEED_LEN,
+        },
+        with_seed::{AddressSeed, WithSeed},
+        Account, AccountSet, AccountSetAccounts, AccountSetCleanup, AccountSetDecode,
+        AccountSetValidate, CanAddLamports, CanFundRent, Close, Executable, Lazy, Many,
+        MultisigSigners, Mut, Program, ProgramAccount, ProgramData, Resize, Signer,
+        SingleAccountSet, SystemAccount, UpgradeAuthority,
+    },
+    bail,
+    context::Context,
+    data_types::PodStr,
+    ensure, error,
+    errors::{star_frame_error, CpiErrorInfo, ErrorInfo},
+    program::{system::System, StarFrameProgram},
+    util::{DisplayPubkey, FastPubkeyEq},
+    Result,
+};
+
+#[cfg(not(target_os = "solana"))]
+pub use crate::account_set::{
+    client::{ClientAccountMeta, ClientAccountSet},
+    pda_resolver::PdaResolver,
+};
 
 pub use pinocchio::{
     account_info::AccountInfo, instruction::AccountMeta as PinocchioAccountMeta, msg,
@@ -10,6 +41,8 @@ pub use pinocchio::{
 // bytemuck
 pub use bytemuck::{CheckedBitPattern, NoUninit, Pod, Zeroable};
 
+pub use std::fmt::Debug;
+
 // pub use borsh::{BorshDeserialize, BorshSerialize};
 
 // ensure derive macros are in scope
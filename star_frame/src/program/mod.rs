@@ -0,0 +1,32 @@
+//! The [`StarFrameProgram`] trait, which ties a program's id, instructions, and errors together.
+
+use crate::{errors::ProgramErrors, instruction::InstructionSet, prelude::*};
+
+pub mod system;
+
+/// A program built with (or a client for a program compatible with) `star_frame`.
+pub trait StarFrameProgram {
+    /// The instructions the program can process.
+    type InstructionSet: InstructionSet;
+    /// The discriminant at the start of the program's accounts.
+    type AccountDiscriminant: Pod + Eq;
+    /// The program's custom error enum, used to decode errors returned by CPIs into the program
+    /// (see [`Error::from_cpi`](crate::errors::Error::from_cpi)). Use `()` if the program has no custom errors.
+    type Errors: ProgramErrors;
+    /// The program id.
+    const ID: Pubkey;
+
+    /// The entrypoint of the program, called by [`star_frame_entrypoint`](crate::star_frame_entrypoint).
+    ///
+    /// Errors are emitted with [`Error::emit`](crate::errors::Error::emit) before being returned to the runtime.
+    fn entrypoint(
+        program_id: &'static Pubkey,
+        accounts: &[AccountInfo],
+        instruction_data: &[u8],
+    ) -> ProgramResult {
+        Self::InstructionSet::dispatch(program_id, accounts, instruction_data).map_err(|error| {
+            error.emit(program_id);
+            error.into()
+        })
+    }
+}
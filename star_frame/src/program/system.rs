@@ -0,0 +1,40 @@
+//! The system program, which `star_frame` calls into to create and fund accounts.
+
+use crate::prelude::*;
+
+/// The system program.
+///
+/// Errors from the CPIs `star_frame` makes into it are tagged with [`CpiErrorInfo::cpi_err`], so they're reported
+/// as coming from the system program.
+#[derive(Debug, Clone, Copy)]
+pub struct System;
+
+impl StarFrameProgram for System {
+    type InstructionSet = ();
+    type AccountDiscriminant = ();
+    type Errors = SystemError;
+    const ID: Pubkey = pinocchio_system::ID;
+}
+
+/// The custom errors of the system program, decoded from CPIs into [`System`].
+#[star_frame_error(offset = 0)]
+pub enum SystemError {
+    #[msg("An account with the same address already exists")]
+    AccountAlreadyInUse = 0,
+    #[msg("Account does not have enough SOL to perform the operation")]
+    ResultWithNegativeLamports,
+    #[msg("Cannot assign account to this program id")]
+    InvalidProgramId,
+    #[msg("Cannot allocate account data of this length")]
+    InvalidAccountDataLength,
+    #[msg("Length of requested seed is too long")]
+    MaxSeedLengthExceeded,
+    #[msg("Provided address does not match address derived from seed")]
+    AddressWithSeedMismatch,
+    #[msg("Advancing stored nonce requires a populated RecentBlockhashes sysvar")]
+    NonceNoRecentBlockhashes,
+    #[msg("Stored nonce is still in recent_blockhashes")]
+    NonceBlockhashNotExpired,
+    #[msg("Specified nonce does not match stored nonce")]
+    NonceUnexpectedBlockhashValue,
+}
@@ -0,0 +1,135 @@
+//! Helpers for testing account sets off-chain, without a validator.
+
+use crate::prelude::*;
+use pinocchio::{account_info::MAX_PERMITTED_DATA_INCREASE, sysvars::rent::Rent};
+
+/// The rent sysvar's values on mainnet, to pass to [`Context::with_rent`] since `Rent::get()` isn't available
+/// off-chain.
+pub const TEST_RENT: Rent = Rent {
+    lamports_per_byte_year: 3_480,
+    exemption_threshold: 2.0,
+    burn_percent: 50,
+};
+
+/// Marks an account as not borrowed, and not a duplicate of another account.
+const NOT_BORROWED: u8 = u8::MAX;
+const IS_SIGNER: usize = 1;
+const IS_WRITABLE: usize = 2;
+const EXECUTABLE: usize = 3;
+const KEY: usize = 8;
+const OWNER: usize = 40;
+const LAMPORTS: usize = 72;
+const DATA_LEN: usize = 80;
+const DATA: usize = 88;
+
+/// A synthetic account laid out the way the runtime serializes accounts into a program's input, so
+/// [`AccountInfo`]s can point into it.
+///
+/// ```
+/// # use star_frame::{prelude::*, test_helpers::TestAccount};
+/// let mut account = TestAccount::new([1; 32], [0; 32], 1_000_000, 8).signer().writable();
+/// let info = account.account_info();
+/// assert!(info.is_signer() && info.is_writable());
+/// assert_eq!(info.data_len(), 8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestAccount {
+    /// Stored as `u64`s so the account is 8 byte aligned like in the runtime.
+    buffer: Vec<u64>,
+}
+
+impl TestAccount {
+    /// Creates a zeroed account with `data_len` bytes of data, that is neither a signer nor writable.
+    #[must_use]
+    pub fn new(key: Pubkey, owner: Pubkey, lamports: u64, data_len: usize) -> Self {
+        // The runtime leaves space after the data so it can grow within an instruction.
+        let len = DATA + data_len + MAX_PERMITTED_DATA_INCREASE;
+        let mut account = Self {
+            buffer: vec![0; len.div_ceil(8)],
+        };
+        let bytes = account.bytes_mut();
+        bytes[0] = NOT_BORROWED;
+        bytes[KEY..OWNER].copy_from_slice(&key);
+        bytes[OWNER..LAMPORTS].copy_from_slice(&owner);
+        bytes[LAMPORTS..DATA_LEN].copy_from_slice(&lamports.to_le_bytes());
+        bytes[DATA_LEN..DATA].copy_from_slice(&(data_len as u64).to_le_bytes());
+        account
+    }
+
+    /// Marks the account as a signer.
+    #[must_use]
+    pub fn signer(mut self) -> Self {
+        self.bytes_mut()[IS_SIGNER] = 1;
+        self
+    }
+
+    /// Marks the account as writable.
+    #[must_use]
+    pub fn writable(mut self) -> Self {
+        self.bytes_mut()[IS_WRITABLE] = 1;
+        self
+    }
+
+    /// Marks the account as executable.
+    #[must_use]
+    pub fn executable(mut self) -> Self {
+        self.bytes_mut()[EXECUTABLE] = 1;
+        self
+    }
+
+    /// Copies `data` into the start of the account's data.
+    ///
+    /// # Panics
+    /// Panics if `data` is longer than the account's data.
+    #[must_use]
+    pub fn with_data(mut self, data: &[u8]) -> Self {
+        self.data_mut()[..data.len()].copy_from_slice(data);
+        self
+    }
+
+    /// An [`AccountInfo`] pointing into this account.
+    ///
+    /// The [`AccountInfo`] is a raw pointer into the buffer, so it must not be used after the [`TestAccount`] is
+    /// dropped or moved.
+    pub fn account_info(&mut self) -> AccountInfo {
+        let raw = self.buffer.as_mut_ptr().cast::<u8>();
+        // SAFETY: `AccountInfo` is a `repr(C)` wrapper around a pointer to the runtime's account layout, which the
+        // buffer matches, including the space to grow the data.
+        unsafe { std::mem::transmute::<*mut u8, AccountInfo>(raw) }
+    }
+
+    #[must_use]
+    pub fn lamports(&self) -> u64 {
+        u64::from_le_bytes(self.bytes()[LAMPORTS..DATA_LEN].try_into().unwrap())
+    }
+
+    #[must_use]
+    pub fn owner(&self) -> Pubkey {
+        self.bytes()[OWNER..LAMPORTS].try_into().unwrap()
+    }
+
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        let data_len = self.data_len();
+        &self.bytes()[DATA..][..data_len]
+    }
+
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        let data_len = self.data_len();
+        &mut self.bytes_mut()[DATA..][..data_len]
+    }
+
+    #[must_use]
+    pub fn data_len(&self) -> usize {
+        let data_len = u64::from_le_bytes(self.bytes()[DATA_LEN..DATA].try_into().unwrap());
+        usize::try_from(data_len).unwrap()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(&self.buffer)
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        bytemuck::cast_slice_mut(&mut self.buffer)
+    }
+}
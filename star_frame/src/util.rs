@@ -0,0 +1,112 @@
+//! Small helpers shared across `star_frame`.
+
+use crate::prelude::*;
+use std::fmt::{Display, Formatter};
+
+/// Compares [`Pubkey`]s as four `u64`s, which takes fewer compute units than comparing 32 bytes one by one.
+pub trait FastPubkeyEq {
+    fn fast_eq(&self, other: &Self) -> bool;
+}
+
+impl FastPubkeyEq for Pubkey {
+    #[inline]
+    fn fast_eq(&self, other: &Self) -> bool {
+        let lhs: &[[u8; 8]; 4] = bytemuck::cast_ref(self);
+        let rhs: &[[u8; 8]; 4] = bytemuck::cast_ref(other);
+        lhs.iter()
+            .zip(rhs)
+            .all(|(lhs, rhs)| u64::from_ne_bytes(*lhs) == u64::from_ne_bytes(*rhs))
+    }
+}
+
+/// Displays a [`Pubkey`] in base58 through [`PubkeyDisplay`], which error messages use instead of its bytes.
+pub trait DisplayPubkey {
+    fn display(&self) -> PubkeyDisplay<'_>;
+}
+
+impl DisplayPubkey for Pubkey {
+    #[inline]
+    fn display(&self) -> PubkeyDisplay<'_> {
+        PubkeyDisplay(self)
+    }
+}
+
+/// A [`Pubkey`] formatted in base58, like `11111111111111111111111111111111`.
+///
+/// Encodes on the stack, so it can be formatted on-chain without allocating.
+#[derive(Debug, Clone, Copy)]
+pub struct PubkeyDisplay<'a>(pub &'a Pubkey);
+
+impl Display for PubkeyDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+        // 32 bytes take at most 44 base58 digits.
+        const MAX_LEN: usize = 44;
+
+        // The digits of the key, least significant first.
+        let mut digits = [0u8; MAX_LEN];
+        let mut len = 0;
+        for byte in self.0 {
+            let mut carry = u32::from(*byte);
+            for digit in &mut digits[..len] {
+                carry += u32::from(*digit) << 8;
+                *digit = (carry % 58) as u8;
+                carry /= 58;
+            }
+            while carry > 0 {
+                digits[len] = (carry % 58) as u8;
+                len += 1;
+                carry /= 58;
+            }
+        }
+
+        // Each leading zero byte is written as a `1`.
+        let zeros = self.0.iter().take_while(|byte| **byte == 0).count();
+        let mut encoded = [b'1'; MAX_LEN];
+        for (encoded, digit) in encoded[zeros..].iter_mut().zip(digits[..len].iter().rev()) {
+            *encoded = ALPHABET[usize::from(*digit)];
+        }
+        let encoded = std::str::from_utf8(&encoded[..zeros + len]).map_err(|_| std::fmt::Error)?;
+        f.pad(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_pubkey() {
+        assert_eq!(
+            [0; 32].display().to_string(),
+            "11111111111111111111111111111111"
+        );
+        for key in [
+            [255; 32],
+            [7; 32],
+            solana_pubkey::Pubkey::new_unique().to_bytes(),
+        ] {
+            assert_eq!(
+                key.display().to_string(),
+                solana_pubkey::Pubkey::new_from_array(key).to_string()
+            );
+        }
+        let mut key = [0; 32];
+        key[2] = 1;
+        assert_eq!(
+            key.display().to_string(),
+            solana_pubkey::Pubkey::new_from_array(key).to_string()
+        );
+    }
+
+    #[test]
+    fn test_fast_eq() {
+        let mut key = [7; 32];
+        assert!(key.fast_eq(&[7; 32]));
+        key[31] = 8;
+        assert!(!key.fast_eq(&[7; 32]));
+        key[31] = 7;
+        key[0] = 0;
+        assert!(!key.fast_eq(&[7; 32]));
+    }
+}
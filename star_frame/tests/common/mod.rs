@@ -0,0 +1,82 @@
+//! A counting global allocator and a test program shared by the `no_alloc` tests.
+//!
+//! Each test binary can only declare one program entrypoint, so the entrypoint forms are split across files.
+#![allow(dead_code)]
+
+use star_frame::{context::Context, instruction::InstructionSet, prelude::*};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts allocations made on the current thread, so tests running in parallel don't interfere.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+pub fn assert_no_alloc<R>(name: &str, f: impl FnOnce() -> R) -> R {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let allocations = ALLOCATIONS.with(Cell::get) - before;
+    assert_eq!(allocations, 0, "`{name}` allocated {allocations} times");
+    result
+}
+
+/// Serializes the entrypoint input for an instruction without accounts.
+pub fn entrypoint_input(program_id: &Pubkey, instruction_data: &[u8]) -> Vec<u8> {
+    let mut input = vec![];
+    input.extend_from_slice(&0u64.to_le_bytes());
+    input.extend_from_slice(&(instruction_data.len() as u64).to_le_bytes());
+    input.extend_from_slice(instruction_data);
+    input.extend_from_slice(program_id);
+    input
+}
+
+/// Succeeds without instruction data. Otherwise decodes two signers, which fails when no accounts are passed in.
+#[derive(Debug)]
+pub struct TestInstructionSet;
+
+impl InstructionSet for TestInstructionSet {
+    type Discriminant = ();
+
+    fn dispatch(
+        program_id: &'static Pubkey,
+        accounts: &[AccountInfo],
+        instruction_data: &[u8],
+    ) -> Result<()> {
+        if instruction_data.is_empty() {
+            return Ok(());
+        }
+        let mut ctx = Context::new(program_id);
+        let accounts = &mut &accounts[..];
+        <Signer>::decode_accounts(accounts, (), &mut ctx)?;
+        <Signer>::decode_accounts(accounts, (), &mut ctx)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct TestProgram;
+
+impl StarFrameProgram for TestProgram {
+    type InstructionSet = TestInstructionSet;
+    type AccountDiscriminant = [u8; 8];
+    type Errors = ();
+    const ID: Pubkey = [7; 32];
+}
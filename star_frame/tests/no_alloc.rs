@@ -0,0 +1,128 @@
+//! Ensures the instruction pipeline never touches the heap allocator with the `no_alloc` feature.
+#![cfg(feature = "no_alloc")]
+// The entrypoint macro checks for `target_os = "solana"`.
+#![allow(unexpected_cfgs)]
+
+mod common;
+
+use common::{assert_no_alloc, entrypoint_input, TestProgram};
+use star_frame::{
+    context::Context,
+    instruction::{Instruction, InstructionSet},
+    prelude::*,
+    test_helpers::TestAccount,
+};
+
+star_frame::star_frame_entrypoint!(TestProgram, no_allocator);
+
+static PROGRAM_ID: Pubkey = TestProgram::ID;
+
+#[derive(Debug, Clone, GetSeeds)]
+#[get_seeds(seed_const = b"vault")]
+struct VaultSeeds {
+    authority: Pubkey,
+}
+
+#[test]
+fn context_does_not_allocate() {
+    assert_no_alloc("Context::new", || {
+        let ctx = Context::new(&PROGRAM_ID);
+        assert_eq!(ctx.current_program_id(), &PROGRAM_ID);
+        assert!(ctx.get_funder().is_none());
+        assert!(ctx.get_recipient().is_none());
+    });
+}
+
+#[test]
+fn empty_instruction_pipeline_does_not_allocate() {
+    assert_no_alloc("<() as InstructionSet>::dispatch", || {
+        <() as InstructionSet>::dispatch(&PROGRAM_ID, &[], &[]).unwrap();
+    });
+    assert_no_alloc("<() as Instruction>::process_from_raw", || {
+        <() as Instruction>::process_from_raw(&PROGRAM_ID, &[], &[]).unwrap();
+    });
+}
+
+#[test]
+fn no_allocator_entrypoint_does_not_allocate() {
+    let mut input = entrypoint_input(&PROGRAM_ID, &[]);
+    let result = assert_no_alloc("star_frame_entrypoint!(_, no_allocator)", || unsafe {
+        entrypoint(input.as_mut_ptr())
+    });
+    assert_eq!(result, star_frame::pinocchio::SUCCESS);
+}
+
+#[test]
+fn entrypoint_error_does_not_allocate() {
+    let mut input = entrypoint_input(&PROGRAM_ID, &[1]);
+    let result = assert_no_alloc("star_frame_entrypoint!(_, no_allocator) error", || unsafe {
+        entrypoint(input.as_mut_ptr())
+    });
+    assert_eq!(result, u64::from(ProgramError::NotEnoughAccountKeys));
+}
+
+#[test]
+fn funder_and_recipient_do_not_allocate() -> Result<()> {
+    let mut funder = TestAccount::new([1; 32], PROGRAM_ID, 1_000, 0)
+        .signer()
+        .writable();
+    let mut recipient = TestAccount::new([2; 32], pinocchio_system::ID, 0, 0).writable();
+    let mut target = TestAccount::new([3; 32], PROGRAM_ID, 0, 0).writable();
+    let infos = [
+        funder.account_info(),
+        recipient.account_info(),
+        target.account_info(),
+    ];
+
+    assert_no_alloc("Context::set_funder and Context::set_recipient", || {
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let accounts = &mut &infos[..];
+        let funder = Mut::<Signer>::decode_accounts(accounts, (), &mut ctx)?;
+        let recipient = Mut::<SystemAccount>::decode_accounts(accounts, (), &mut ctx)?;
+        let target = Mut::<AccountInfo>::decode_accounts(accounts, (), &mut ctx)?;
+        ctx.set_funder(funder);
+        ctx.set_recipient(recipient);
+        let funder = ctx.get_funder().expect("Funder was set");
+        funder.fund_rent(&target, 600, &ctx)?;
+        ctx.get_recipient()
+            .expect("Recipient was set")
+            .add_lamports(100)
+    })?;
+    assert_eq!(funder.lamports(), 400);
+    assert_eq!(target.lamports(), 600);
+    assert_eq!(recipient.lamports(), 100);
+    Ok(())
+}
+
+#[test]
+fn seeded_signer_does_not_allocate() -> Result<()> {
+    let authority = [4; 32];
+    let program_id = solana_pubkey::Pubkey::new_from_array(PROGRAM_ID);
+    let (vault, bump) =
+        solana_pubkey::Pubkey::find_program_address(&[b"vault", &authority], &program_id);
+    let mut vault = TestAccount::new(vault.to_bytes(), pinocchio_system::ID, 1_000, 0).writable();
+    let mut target = TestAccount::new([5; 32], PROGRAM_ID, 0, 0).writable();
+    let infos = [vault.account_info(), target.account_info()];
+
+    assert_no_alloc("Seeded validation and SignerSeeds", || {
+        let mut ctx = Context::new(&PROGRAM_ID);
+        let accounts = &mut &infos[..];
+        let mut vault =
+            Seeded::<Mut<SystemAccount>, VaultSeeds>::decode_accounts(accounts, (), &mut ctx)?;
+        let target = Mut::<AccountInfo>::decode_accounts(accounts, (), &mut ctx)?;
+        vault.validate_accounts(
+            SeedsWithBump {
+                seeds: VaultSeeds { authority },
+                bump,
+            },
+            &mut ctx,
+        )?;
+        let seeds = SignedAccount::signer_seeds(&vault).expect("Seeded accounts sign with seeds");
+        assert_eq!(seeds.len(), 3);
+        seeds.with_signer(|signers| assert_eq!(signers.len(), 1));
+        ctx.set_funder(vault);
+        ctx.get_funder()
+            .expect("Funder was set")
+            .fund_rent(&target, 600, &ctx)
+    })
+}
@@ -0,0 +1,20 @@
+//! Ensures the `heap_size` entrypoint form doesn't touch the heap allocator with the `no_alloc` feature.
+#![cfg(feature = "no_alloc")]
+// The entrypoint macro checks for `target_os = "solana"`.
+#![allow(unexpected_cfgs)]
+
+mod common;
+
+use common::{assert_no_alloc, entrypoint_input, TestProgram};
+use star_frame::prelude::*;
+
+star_frame::star_frame_entrypoint!(TestProgram, heap_size = 64 * 1024);
+
+#[test]
+fn heap_size_entrypoint_does_not_allocate() {
+    let mut input = entrypoint_input(&TestProgram::ID, &[]);
+    let result = assert_no_alloc("star_frame_entrypoint!(_, heap_size = 64 * 1024)", || unsafe {
+        entrypoint(input.as_mut_ptr())
+    });
+    assert_eq!(result, star_frame::pinocchio::SUCCESS);
+}
@@ -0,0 +1,88 @@
+use crate::{serde_impls::serde_base58_pubkey, ty::IdlTypeDef, ItemDescription};
+use serde::{Deserialize, Serialize};
+use solana_pubkey::Pubkey;
+
+/// The seeds of a PDA, in order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct IdlSeeds(pub Vec<IdlSeed>);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IdlSeed {
+    /// A constant seed.
+    Const(Vec<u8>),
+    /// A seed of the bytes of a value of the given type.
+    Variable {
+        name: String,
+        description: ItemDescription,
+        ty: IdlTypeDef,
+    },
+    /// A utf8 string seed, without a length prefix.
+    String {
+        name: String,
+        description: ItemDescription,
+        /// Set for fixed capacity strings, which are zero padded to this length.
+        #[serde(skip_serializing_if = "crate::is_default", default)]
+        padded_len: Option<usize>,
+    },
+    /// A raw bytes seed, without a length prefix.
+    Bytes {
+        name: String,
+        description: ItemDescription,
+    },
+    /// One of several seed schemes, chosen by a tag seed. Each variant's seeds start with its tag.
+    Tagged(Vec<IdlSeedVariant>),
+}
+
+/// A variant of an [`IdlSeed::Tagged`] seed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdlSeedVariant {
+    pub name: String,
+    pub description: ItemDescription,
+    /// The tag seed that selects this variant.
+    pub tag: Vec<u8>,
+    /// The seeds following the tag.
+    pub seeds: Vec<IdlSeed>,
+}
+
+impl IdlSeed {
+    /// The name of the seed, if it's not constant or tagged.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            IdlSeed::Const(_) | IdlSeed::Tagged(_) => None,
+            IdlSeed::Variable { name, .. }
+            | IdlSeed::String { name, .. }
+            | IdlSeed::Bytes { name, .. } => Some(name),
+        }
+    }
+}
+
+/// A seed used to find a PDA in an account set.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IdlFindSeed {
+    /// A constant seed.
+    Const(Vec<u8>),
+    /// A seed taken from the address of another account in the set.
+    AccountPath(String),
+}
+
+/// The program a PDA is derived from.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IdlSeedProgram {
+    /// A program known ahead of time.
+    Address(#[serde(with = "serde_base58_pubkey")] Pubkey),
+    /// A program whose id is the address of another account in the set.
+    AccountPath(String),
+}
+
+/// How to find a PDA in an account set.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IdlFindSeeds {
+    pub seeds: Vec<IdlFindSeed>,
+    /// The program the PDA is derived from. `None` means the program the IDL is for.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub program: Option<IdlSeedProgram>,
+}
@@ -0,0 +1,83 @@
+use crate::account_set::AccountSetStruct;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse_quote;
+
+/// Generates the `AccountSetAccounts` impl, which visits each field that isn't skipped or
+/// `#[account_set(allow_duplicate)]`, and passes path prefixes on to the same fields.
+pub fn accounts_impl(account_set: &AccountSetStruct) -> TokenStream {
+    let AccountSetStruct {
+        ident,
+        generics,
+        paths,
+        ..
+    } = account_set;
+    let prelude = &paths.prelude;
+    let account_set_path = &paths.account_set;
+    let mut impl_generics = (*generics).clone();
+
+    let visited_fields: Vec<_> = account_set
+        .set_fields()
+        .filter(|field| !field.args.allow_duplicate)
+        .collect();
+    let fields = visited_fields
+        .iter()
+        .map(|field| {
+            let ty = field.ty;
+            impl_generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(#ty: #account_set_path::AccountSetAccounts));
+            // A single account set has the same path as the account it wraps, and its own writability.
+            if account_set.single.is_some() {
+                impl_generics
+                    .make_where_clause()
+                    .predicates
+                    .push(parse_quote!(#ty: #account_set_path::SingleAccountSet));
+                return quote! {
+                    visit(
+                        path,
+                        #account_set_path::SingleAccountSet::account_info(self),
+                        <Self as #account_set_path::SingleAccountSet>::meta().writable,
+                    );
+                };
+            }
+            let member = &field.member;
+            let segment = field.path_segment(account_set_path);
+            quote! {
+                <#ty as #account_set_path::AccountSetAccounts>::visit_accounts(
+                    &self.#member,
+                    &path.join(#segment),
+                    visit,
+                );
+            }
+        })
+        .collect::<Vec<_>>();
+    let prefixes = visited_fields.iter().map(|field| {
+        let member = &field.member;
+        let ty = field.ty;
+        quote! {
+            <#ty as #account_set_path::AccountSetAccounts>::prefix_path(&mut self.#member, segment);
+        }
+    });
+
+    let (impl_generics, type_generics, where_clause) = impl_generics.split_for_impl();
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics #account_set_path::AccountSetAccounts for #ident #type_generics #where_clause {
+            #[allow(unused_variables)]
+            fn visit_accounts(
+                &self,
+                path: &#account_set_path::AccountPath<'_>,
+                visit: &mut dyn ::core::ops::FnMut(&#account_set_path::AccountPath<'_>, &#prelude::AccountInfo, bool),
+            ) {
+                #(#fields)*
+            }
+
+            #[allow(unused_variables)]
+            fn prefix_path(&mut self, segment: #account_set_path::PathSegment) {
+                #(#prefixes)*
+            }
+        }
+    }
+}
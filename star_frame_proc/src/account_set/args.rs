@@ -0,0 +1,404 @@
+//! Parsing of the `AccountSet` derive's struct and field attributes.
+
+use proc_macro_error2::abort;
+use syn::{
+    bracketed, meta::ParseNestedMeta, parse::ParseStream, parse_quote, Attribute, Expr, Field,
+    Generics, Ident, LitStr, Token, Type, WhereClause,
+};
+
+/// The account set traits that can have multiple implementations, selected by an `id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Decode,
+    Validate,
+    Cleanup,
+    Idl,
+}
+
+impl Step {
+    pub const ALL: [Self; 4] = [Self::Decode, Self::Validate, Self::Cleanup, Self::Idl];
+
+    /// The name of the step's struct and field attribute.
+    pub fn attr(self) -> &'static str {
+        match self {
+            Self::Decode => "decode",
+            Self::Validate => "validate",
+            Self::Cleanup => "cleanup",
+            Self::Idl => "idl",
+        }
+    }
+
+    /// The argument that runs an expression after the fields are processed.
+    fn extra_arg(self) -> Option<&'static str> {
+        match self {
+            Self::Validate => Some("extra_validation"),
+            Self::Cleanup => Some("extra_cleanup"),
+            Self::Decode | Self::Idl => None,
+        }
+    }
+}
+
+/// `#[account_set(skip_default_decode, skip_default_validate, skip_default_cleanup, skip_default_idl,
+/// check_duplicates)]` on the struct.
+#[derive(Debug, Default)]
+pub struct StructArgs {
+    /// Indexed by [`Step`].
+    pub skip_default: [bool; 4],
+    /// Checks for writable accounts passed in more than once during validation.
+    pub check_duplicates: bool,
+}
+
+/// A struct level `#[decode]`, `#[validate]`, `#[cleanup]`, or `#[idl]`, each of which generates an impl.
+#[derive(Debug)]
+pub struct StepImpl {
+    pub id: Option<String>,
+    pub arg: Type,
+    pub generics: Generics,
+    pub before_validation: Option<Expr>,
+    pub extra: Option<Expr>,
+    pub inline_always: bool,
+    /// Generated because the struct has no impl without an id.
+    pub is_default: bool,
+}
+
+impl Default for StepImpl {
+    fn default() -> Self {
+        Self {
+            id: None,
+            arg: parse_quote!(()),
+            generics: Generics::default(),
+            before_validation: None,
+            extra: None,
+            inline_always: false,
+            is_default: false,
+        }
+    }
+}
+
+/// A field level `#[decode]`, `#[validate]`, `#[cleanup]`, or `#[idl]`, for the impl with the same `id`.
+#[derive(Debug, Default)]
+pub struct FieldStep {
+    pub id: Option<String>,
+    pub arg: Option<Expr>,
+    pub arg_ty: Option<Type>,
+    pub skip: bool,
+    pub funder: bool,
+    pub recipient: bool,
+    /// Checked to be the field's key.
+    pub address: Option<Expr>,
+    /// A program type whose id is checked to be the field's owner.
+    pub owner: Option<Type>,
+    /// Sibling fields whose keys are checked against the keys the field's account stores.
+    pub has_one: Vec<HasOne>,
+    /// A `bool` expression checked after the field is validated, and the error it fails with.
+    pub constraint: Option<Expr>,
+    pub error: Option<Expr>,
+}
+
+/// `has_one = <field>` or `has_one = <field> @ <accessor>` in a field's `#[validate(..)]`.
+#[derive(Debug)]
+pub struct HasOne {
+    /// The sibling field whose key is checked.
+    pub field: Ident,
+    /// The method that returns the stored key as a `Result<Pubkey>`. Without one, the key is read from the `field`
+    /// of the account's data through `ReadAccount`.
+    pub accessor: Option<Ident>,
+}
+
+impl HasOne {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let field = input.parse()?;
+        let accessor = if input.peek(Token![@]) {
+            input.parse::<Token![@]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { field, accessor })
+    }
+}
+
+/// `#[single_account_set(..)]` on a field.
+#[derive(Debug, Default)]
+pub struct SingleArgs {
+    pub signer: bool,
+    pub writable: bool,
+    pub skip_signed_account: bool,
+    pub skip_writable_account: bool,
+    pub skip_has_inner_type: bool,
+    pub skip_has_owner_program: bool,
+    pub skip_has_seeds: bool,
+    pub skip_read_account: bool,
+    pub skip_can_init_seeds: bool,
+    pub skip_can_init_account: bool,
+}
+
+/// A field and its attributes.
+#[derive(Debug)]
+pub struct FieldArgs {
+    /// `#[account_set(skip = <expr>)]`, the value the field is decoded as.
+    pub skip: Option<Expr>,
+    /// `#[account_set(allow_duplicate)]`, which leaves the field out of the duplicate writable account check.
+    pub allow_duplicate: bool,
+    pub single: Option<SingleArgs>,
+    /// Indexed by [`Step`].
+    pub steps: [Vec<FieldStep>; 4],
+}
+
+impl FieldArgs {
+    /// The field's attribute for the impl with `id`.
+    pub fn step(&self, step: Step, id: Option<&str>) -> Option<&FieldStep> {
+        self.steps[step as usize]
+            .iter()
+            .find(|field_step| field_step.id.as_deref() == id)
+    }
+}
+
+fn parse_flag(flag: &mut bool, meta: &ParseNestedMeta) -> syn::Result<()> {
+    if *flag {
+        return Err(meta.error("Duplicate argument"));
+    }
+    *flag = true;
+    Ok(())
+}
+
+fn parse_value<T: syn::parse::Parse>(
+    value: &mut Option<T>,
+    meta: &ParseNestedMeta,
+) -> syn::Result<()> {
+    if value.is_some() {
+        return Err(meta.error("Duplicate argument"));
+    }
+    *value = Some(meta.value()?.parse()?);
+    Ok(())
+}
+
+/// Parses `[<generics> where <predicates>]`.
+fn parse_generics(input: ParseStream) -> syn::Result<Generics> {
+    let content;
+    bracketed!(content in input);
+    let mut generics: Generics = content.parse()?;
+    generics.where_clause = content.parse::<Option<WhereClause>>()?;
+    if !content.is_empty() {
+        return Err(content.error("Expected `<generics> where <predicates>`"));
+    }
+    Ok(generics)
+}
+
+fn unwrap_or_abort<T>(result: syn::Result<T>) -> T {
+    result.unwrap_or_else(|error| abort!(error.span(), error.to_string()))
+}
+
+pub fn parse_struct_args(attrs: &[Attribute]) -> StructArgs {
+    let mut args = StructArgs::default();
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("account_set"))
+    {
+        unwrap_or_abort(attr.parse_nested_meta(|meta| {
+            let step = Step::ALL
+                .into_iter()
+                .find(|step| meta.path.is_ident(&format!("skip_default_{}", step.attr())));
+            match step {
+                Some(step) => parse_flag(&mut args.skip_default[step as usize], &meta),
+                None if meta.path.is_ident("check_duplicates") => {
+                    parse_flag(&mut args.check_duplicates, &meta)
+                }
+                None => Err(meta.error("Unknown `account_set` argument")),
+            }
+        }));
+    }
+    args
+}
+
+pub fn parse_step_impls(attrs: &[Attribute], step: Step) -> Vec<StepImpl> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(step.attr()))
+        .map(|attr| {
+            let mut step_impl = StepImpl::default();
+            let mut id: Option<LitStr> = None;
+            let mut arg = None;
+            let mut generics = None;
+            unwrap_or_abort(attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") {
+                    parse_value(&mut id, &meta)
+                } else if meta.path.is_ident("arg") {
+                    parse_value(&mut arg, &meta)
+                } else if meta.path.is_ident("generics") {
+                    if generics.is_some() {
+                        return Err(meta.error("Duplicate argument"));
+                    }
+                    generics = Some(parse_generics(meta.value()?)?);
+                    Ok(())
+                } else if meta.path.is_ident("inline_always") && step != Step::Idl {
+                    parse_flag(&mut step_impl.inline_always, &meta)
+                } else if meta.path.is_ident("before_validation") && step == Step::Validate {
+                    parse_value(&mut step_impl.before_validation, &meta)
+                } else if step
+                    .extra_arg()
+                    .is_some_and(|extra| meta.path.is_ident(extra))
+                {
+                    parse_value(&mut step_impl.extra, &meta)
+                } else {
+                    Err(meta.error(format!("Unknown `{}` argument", step.attr())))
+                }
+            }));
+            step_impl.id = id.map(|id| id.value());
+            step_impl.arg = arg.unwrap_or(step_impl.arg);
+            step_impl.generics = generics.unwrap_or_default();
+            step_impl
+        })
+        .collect()
+}
+
+fn parse_field_step(attr: &Attribute, step: Step) -> FieldStep {
+    let mut field_step = FieldStep::default();
+    let mut id: Option<LitStr> = None;
+    unwrap_or_abort(attr.parse_nested_meta(|meta| {
+        let validate = step == Step::Validate;
+        if meta.path.is_ident("id") {
+            parse_value(&mut id, &meta)
+        } else if meta.path.is_ident("arg") {
+            parse_value(&mut field_step.arg, &meta)
+        } else if meta.path.is_ident("arg_ty") && validate {
+            parse_value(&mut field_step.arg_ty, &meta)
+        } else if meta.path.is_ident("skip") && validate {
+            parse_flag(&mut field_step.skip, &meta)
+        } else if meta.path.is_ident("funder") && validate {
+            parse_flag(&mut field_step.funder, &meta)
+        } else if meta.path.is_ident("recipient") && validate {
+            parse_flag(&mut field_step.recipient, &meta)
+        } else if meta.path.is_ident("address") && matches!(step, Step::Validate | Step::Idl) {
+            parse_value(&mut field_step.address, &meta)
+        } else if meta.path.is_ident("owner") && validate {
+            parse_value(&mut field_step.owner, &meta)
+        } else if meta.path.is_ident("has_one") && validate {
+            field_step.has_one.push(HasOne::parse(meta.value()?)?);
+            Ok(())
+        } else if meta.path.is_ident("constraint") && validate {
+            parse_value(&mut field_step.constraint, &meta)
+        } else if meta.path.is_ident("error") && validate {
+            parse_value(&mut field_step.error, &meta)
+        } else {
+            Err(meta.error(format!("Unknown field `{}` argument", step.attr())))
+        }
+    }));
+    if field_step.skip && field_step.arg.is_some() {
+        abort!(attr, "`skip` and `arg` are mutually exclusive");
+    }
+    if field_step.skip
+        && (field_step.address.is_some()
+            || field_step.owner.is_some()
+            || !field_step.has_one.is_empty()
+            || field_step.constraint.is_some())
+    {
+        abort!(attr, "A skipped field can't have checks");
+    }
+    if field_step.error.is_some() && field_step.constraint.is_none() {
+        abort!(attr, "`error` can only be used with a `constraint`");
+    }
+    field_step.id = id.map(|id| id.value());
+    field_step
+}
+
+fn parse_single_args(attr: &Attribute) -> SingleArgs {
+    let mut args = SingleArgs::default();
+    if matches!(attr.meta, syn::Meta::Path(_)) {
+        return args;
+    }
+    unwrap_or_abort(attr.parse_nested_meta(|meta| {
+        let flag = [
+            ("signer", &mut args.signer),
+            ("writable", &mut args.writable),
+            ("skip_signed_account", &mut args.skip_signed_account),
+            ("skip_writable_account", &mut args.skip_writable_account),
+            ("skip_has_inner_type", &mut args.skip_has_inner_type),
+            ("skip_has_owner_program", &mut args.skip_has_owner_program),
+            ("skip_has_seeds", &mut args.skip_has_seeds),
+            ("skip_read_account", &mut args.skip_read_account),
+            ("skip_can_init_seeds", &mut args.skip_can_init_seeds),
+            ("skip_can_init_account", &mut args.skip_can_init_account),
+        ]
+        .into_iter()
+        .find(|(name, _)| meta.path.is_ident(name));
+        match flag {
+            Some((_, flag)) => parse_flag(flag, &meta),
+            None => Err(meta.error("Unknown `single_account_set` argument")),
+        }
+    }));
+    args
+}
+
+pub fn parse_field_args(field: &Field) -> FieldArgs {
+    let mut skip: Option<Expr> = None;
+    let mut allow_duplicate = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("account_set"))
+    {
+        unwrap_or_abort(attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                parse_value(&mut skip, &meta)
+            } else if meta.path.is_ident("allow_duplicate") {
+                parse_flag(&mut allow_duplicate, &meta)
+            } else {
+                Err(meta.error("Unknown field `account_set` argument"))
+            }
+        }));
+    }
+
+    let mut single = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("single_account_set"))
+    {
+        if single.is_some() {
+            abort!(attr, "Duplicate `single_account_set` attribute");
+        }
+        single = Some(parse_single_args(attr));
+    }
+
+    let steps: [Vec<FieldStep>; 4] = Step::ALL.map(|step| {
+        let field_steps: Vec<_> = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident(step.attr()))
+            .map(|attr| (attr, parse_field_step(attr, step)))
+            .collect();
+        for (index, (attr, field_step)) in field_steps.iter().enumerate() {
+            if field_steps[..index]
+                .iter()
+                .any(|(_, other)| other.id == field_step.id)
+            {
+                abort!(
+                    attr,
+                    "Duplicate field `{}` attribute for this id",
+                    step.attr()
+                );
+            }
+        }
+        field_steps
+            .into_iter()
+            .map(|(_, field_step)| field_step)
+            .collect()
+    });
+
+    if let Some(skip) = &skip {
+        if single.is_some() {
+            abort!(skip, "A skipped field can't be the `single_account_set`");
+        }
+        if steps.iter().any(|field_steps| !field_steps.is_empty()) {
+            abort!(skip, "A skipped field can't have step attributes");
+        }
+    }
+
+    FieldArgs {
+        skip,
+        allow_duplicate,
+        single,
+        steps,
+    }
+}
@@ -0,0 +1,133 @@
+use crate::account_set::{mentions_ident, AccountSetStruct};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_quote, GenericParam, Member, Visibility, WherePredicate};
+
+/// Generates the `<Name>ClientAccounts` struct and the `ClientAccountSet` impl, off-chain only.
+///
+/// Single account sets are already a `ClientAccountSet` through `SingleAccountSet`. The client accounts take the
+/// struct's generics, so structs with a generic only used by skipped fields are skipped too.
+pub fn client_impls(account_set: &AccountSetStruct, vis: &Visibility) -> TokenStream {
+    let AccountSetStruct {
+        ident,
+        generics,
+        paths,
+        ..
+    } = account_set;
+    let fields: Vec<_> = account_set.set_fields().collect();
+    let unused_generic = generics.params.iter().any(|param| {
+        let param_ident = match param {
+            GenericParam::Type(param) => &param.ident,
+            GenericParam::Lifetime(param) => &param.lifetime.ident,
+            GenericParam::Const(param) => &param.ident,
+        };
+        !fields
+            .iter()
+            .any(|field| mentions_ident(field.ty.to_token_stream(), &param_ident.to_string()))
+    });
+    if account_set.single.is_some() || unused_generic {
+        return TokenStream::new();
+    }
+    let prelude = &paths.prelude;
+    let client = {
+        let account_set_path = &paths.account_set;
+        quote!(#account_set_path::client)
+    };
+    let mut client_generics = (*generics).clone();
+    client_generics
+        .make_where_clause()
+        .predicates
+        .extend(fields.iter().map(|field| -> WherePredicate {
+            let ty = field.ty;
+            parse_quote!(#ty: #client::ClientAccountSet)
+        }));
+    let (impl_generics, type_generics, where_clause) = client_generics.split_for_impl();
+    let ident_str = ident.to_string();
+    let client_ident = format_ident!(
+        "{}ClientAccounts",
+        ident_str.strip_suffix("Accounts").unwrap_or(&ident_str)
+    );
+    let doc = format!(" The keys a client passes for [`{ident_str}`].");
+
+    let field_tys = fields.iter().map(|field| {
+        let ty = field.ty;
+        quote!(<#ty as #client::ClientAccountSet>::ClientAccounts)
+    });
+    let named = fields
+        .iter()
+        .all(|field| matches!(field.member, Member::Named(_)));
+    // Tuple structs are renumbered, since skipped fields aren't in the client accounts.
+    let members: Vec<Member> = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.member {
+            Member::Named(_) => field.member.clone(),
+            Member::Unnamed(_) => Member::Unnamed(index.into()),
+        })
+        .collect();
+    let client_ident_str = client_ident.to_string();
+    let (client_struct, debug) = if named {
+        let names = fields.iter().map(|field| &field.name);
+        (
+            quote!(#vis struct #client_ident #impl_generics #where_clause { #(pub #members: #field_tys,)* }),
+            quote! {
+                f.debug_struct(#client_ident_str)
+                    #(.field(#names, &self.#members))*
+                    .finish()
+            },
+        )
+    } else {
+        (
+            quote!(#vis struct #client_ident #impl_generics (#(pub #field_tys),*) #where_clause;),
+            quote! {
+                f.debug_tuple(#client_ident_str)
+                    #(.field(&self.#members))*
+                    .finish()
+            },
+        )
+    };
+    let extend = fields.iter().zip(&members).map(|(field, member)| {
+        let ty = field.ty;
+        quote! {
+            <#ty as #client::ClientAccountSet>::extend_account_metas(program_id, &accounts.#member, metas);
+        }
+    });
+
+    quote! {
+        #[cfg(not(target_os = "solana"))]
+        #[doc = #doc]
+        #client_struct
+
+        // Written out so the struct's generics don't need to be `Debug` or `Clone` themselves.
+        #[cfg(not(target_os = "solana"))]
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Debug for #client_ident #type_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #debug
+            }
+        }
+
+        #[cfg(not(target_os = "solana"))]
+        #[automatically_derived]
+        impl #impl_generics ::core::clone::Clone for #client_ident #type_generics #where_clause {
+            fn clone(&self) -> Self {
+                Self { #(#members: ::core::clone::Clone::clone(&self.#members),)* }
+            }
+        }
+
+        #[cfg(not(target_os = "solana"))]
+        #[automatically_derived]
+        impl #impl_generics #client::ClientAccountSet for #ident #type_generics #where_clause {
+            type ClientAccounts = #client_ident #type_generics;
+
+            #[allow(unused_variables)]
+            fn extend_account_metas(
+                program_id: &#prelude::Pubkey,
+                accounts: &Self::ClientAccounts,
+                metas: &mut ::std::vec::Vec<#client::ClientAccountMeta>,
+            ) {
+                #(#extend)*
+            }
+        }
+    }
+}
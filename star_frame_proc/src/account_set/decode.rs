@@ -0,0 +1,91 @@
+use crate::{
+    account_set::{field_arg, field_arg_ty, inline_attr, AccountSetStruct, Step},
+    util::new_lifetime,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse_quote;
+
+pub fn decode_impls(account_set: &AccountSetStruct) -> TokenStream {
+    let AccountSetStruct {
+        ident,
+        generics,
+        paths,
+        ..
+    } = account_set;
+    let prelude = &paths.prelude;
+    let account_set_path = &paths.account_set;
+    let (_, type_generics, _) = generics.split_for_impl();
+
+    let impls = account_set.impls[Step::Decode as usize].iter().map(|step_impl| {
+        let lifetime = new_lifetime(generics, None);
+        let mut impl_generics = account_set.impl_generics(step_impl, Some(&lifetime));
+        let id = step_impl.id.as_deref();
+        let arg_ty = &step_impl.arg;
+
+        let fields = account_set.fields.iter().map(|field| {
+            let member = &field.member;
+            if let Some(skip) = &field.args.skip {
+                return quote!(#member: #skip);
+            }
+            let ty = field.ty;
+            let segment = field.path_segment(account_set_path);
+            let field_step = field.args.step(Step::Decode, id);
+            let arg = field_arg(field_step);
+            let trait_arg = match field_arg_ty(step_impl, field_step) {
+                Some(field_arg_ty) => {
+                    impl_generics.make_where_clause().predicates.push(parse_quote!(
+                        #ty: #account_set_path::AccountSetDecode<#lifetime, #field_arg_ty>
+                    ));
+                    quote!(#field_arg_ty)
+                }
+                None => quote!(_),
+            };
+            let decode = quote! {
+                <#ty as #account_set_path::AccountSetDecode<#lifetime, #trait_arg>>::decode_accounts(accounts, #arg, ctx)
+            };
+            // Single account sets are transparent wrappers, so they don't add to the account path.
+            if account_set.single.is_some() {
+                quote!(#member: #decode?)
+            } else if field.args.allow_duplicate {
+                quote!(#member: #prelude::ErrorInfo::account_path(#decode, #segment)?)
+            } else {
+                // Sets like `Lazy` are validated after the derived validation returns, so they're given their path
+                // to annotate their own errors.
+                impl_generics
+                    .make_where_clause()
+                    .predicates
+                    .push(parse_quote!(#ty: #account_set_path::AccountSetAccounts));
+                quote! {
+                    #member: {
+                        let mut set = #prelude::ErrorInfo::account_path(#decode, #segment)?;
+                        #account_set_path::AccountSetAccounts::prefix_path(&mut set, #segment);
+                        set
+                    }
+                }
+            }
+        });
+        let fields: Vec<_> = fields.collect();
+
+        let inline = inline_attr(step_impl);
+        let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #account_set_path::AccountSetDecode<#lifetime, #arg_ty> for #ident #type_generics #where_clause {
+                #inline
+                #[allow(unused_variables)]
+                fn decode_accounts(
+                    accounts: &mut &#lifetime [#prelude::AccountInfo],
+                    arg: #arg_ty,
+                    ctx: &mut #prelude::Context,
+                ) -> #prelude::Result<Self> {
+                    ::core::result::Result::Ok(Self {
+                        #(#fields,)*
+                    })
+                }
+            }
+        }
+    });
+
+    quote!(#(#impls)*)
+}
@@ -0,0 +1,158 @@
+use crate::{
+    account_set::{field_arg, field_arg_ty, new_generic, AccountSetStruct, FieldStep, Step},
+    util::ignore_cfg_module,
+};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse_quote;
+
+/// Describes the `owner`, `has_one`, and `constraint` checks of a field's default validation, which are added to the
+/// field's description in the IDL.
+fn check_descriptions(field_step: Option<&FieldStep>) -> Vec<String> {
+    let Some(field_step) = field_step else {
+        return vec![];
+    };
+    let owner = field_step
+        .owner
+        .iter()
+        .map(|owner| format!("Owned by `{}`", owner.to_token_stream()));
+    let has_one = field_step
+        .has_one
+        .iter()
+        .map(|has_one| format!("Stores the key of `{}`", has_one.field));
+    let constraint = field_step
+        .constraint
+        .iter()
+        .map(|constraint| format!("Constraint: `{}`", constraint.to_token_stream()));
+    owner.chain(has_one).chain(constraint).collect()
+}
+
+/// Generates the `AccountSetToIdl` impls, behind the user crate's `idl` feature.
+///
+/// Structs are added to the IDL as a named account set of their fields. Single account sets are their field's set,
+/// with the signer and writable flags added, and the default impl passes its argument through to the field.
+pub fn idl_impls(account_set: &AccountSetStruct) -> TokenStream {
+    let AccountSetStruct {
+        ident,
+        generics,
+        docs,
+        paths,
+        ..
+    } = account_set;
+    let prelude = &paths.prelude;
+    let (_, type_generics, _) = generics.split_for_impl();
+    let ident_str = ident.to_string();
+
+    let impls = account_set.impls[Step::Idl as usize].iter().map(|step_impl| {
+        let mut impl_generics = account_set.impl_generics(step_impl, None);
+        let id = step_impl.id.as_deref();
+        let mut arg_ty = step_impl.arg.clone();
+
+        let field_sets = account_set.set_fields().map(|field| {
+            let ty = field.ty;
+            let field_step = field.args.step(Step::Idl, id);
+            let pass_through = account_set.single.is_some() && step_impl.is_default;
+            let (arg, field_arg_ty) = if pass_through {
+                let generic = new_generic(generics, "A");
+                impl_generics.params.push(parse_quote!(#generic));
+                arg_ty = parse_quote!(#generic);
+                (quote!(arg), Some(arg_ty.clone()))
+            } else {
+                (field_arg(field_step), field_arg_ty(step_impl, field_step))
+            };
+            let trait_arg = match field_arg_ty {
+                Some(field_arg_ty) => {
+                    impl_generics
+                        .make_where_clause()
+                        .predicates
+                        .push(parse_quote!(#ty: #prelude::AccountSetToIdl<#field_arg_ty>));
+                    quote!(#field_arg_ty)
+                }
+                None => quote!(_),
+            };
+            // The default validation's address is used when the `#[idl]` doesn't give one.
+            let address = field_step
+                .and_then(|field_step| field_step.address.as_ref())
+                .or_else(|| {
+                    field
+                        .args
+                        .step(Step::Validate, None)
+                        .and_then(|field_step| field_step.address.as_ref())
+                })
+                .map(|address| {
+                    quote! {
+                        let address: &#prelude::Pubkey = &#address;
+                        set.single()?.address = ::core::option::Option::Some(::core::convert::From::from(*address));
+                    }
+                });
+            quote! {{
+                let mut set = <#ty as #prelude::AccountSetToIdl<#trait_arg>>::account_set_to_idl(idl_definition, #arg)?;
+                #address
+                set
+            }}
+        });
+        let field_sets: Vec<_> = field_sets.collect();
+
+        let body = if let Some(single) = account_set.single_field().and_then(|field| field.args.single.as_ref()) {
+            let field_set = &field_sets[0];
+            let signer = single.signer.then(|| quote!(single.signer = true;));
+            let writable = single.writable.then(|| quote!(single.writable = true;));
+            quote! {
+                let mut set = #field_set;
+                let single = set.single()?;
+                #signer
+                #writable
+                ::core::result::Result::Ok(set)
+            }
+        } else {
+            let fields = account_set.set_fields().zip(&field_sets).map(|(field, field_set)| {
+                let name = &field.name;
+                let docs = &field.docs;
+                let checks = check_descriptions(field.args.step(Step::Validate, None));
+                quote! {
+                    #prelude::IdlAccountSetStructField {
+                        path: ::core::option::Option::Some(#name.to_string()),
+                        description: {
+                            let mut description: ::std::vec::Vec<::std::string::String> = #docs;
+                            description.extend([#(#checks.to_string()),*]);
+                            description
+                        },
+                        account_set_def: #field_set,
+                    }
+                }
+            });
+            quote! {
+                let account_set = #prelude::IdlAccountSet {
+                    info: #prelude::ItemInfo::new::<Self>(#ident_str, #docs),
+                    account_set_def: #prelude::IdlAccountSetDef::Struct(vec![#(#fields),*]),
+                };
+                let id = #prelude::IdlAccountSetId {
+                    source: account_set.info.source.clone(),
+                };
+                idl_definition.add_account_set(account_set);
+                ::core::result::Result::Ok(#prelude::IdlAccountSetDef::Defined(id))
+            }
+        };
+
+        let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+        quote! {
+            #[cfg(all(feature = "idl", not(target_os = "solana")))]
+            #[automatically_derived]
+            impl #impl_generics #prelude::AccountSetToIdl<#arg_ty> for #ident #type_generics #where_clause {
+                #[allow(unused_variables)]
+                fn account_set_to_idl(
+                    idl_definition: &mut #prelude::IdlDefinition,
+                    arg: #arg_ty,
+                ) -> #prelude::IdlResult<#prelude::IdlAccountSetDef> {
+                    #body
+                }
+            }
+        }
+    });
+    let impls: Vec<_> = impls.collect();
+    if impls.is_empty() {
+        return TokenStream::new();
+    }
+
+    ignore_cfg_module(ident, "_account_set_idl", quote!(#(#impls)*))
+}
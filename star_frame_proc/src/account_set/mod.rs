@@ -0,0 +1,318 @@
+mod accounts;
+mod args;
+mod client;
+mod decode;
+mod idl;
+mod single_set;
+mod validate;
+
+use crate::util::{get_docs, Paths};
+use args::{parse_field_args, parse_step_impls, parse_struct_args, FieldArgs, StepImpl};
+pub use args::{FieldStep, Step};
+use proc_macro2::{TokenStream, TokenTree};
+use proc_macro_error2::abort;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, Data, DeriveInput, Expr, Fields, GenericParam, Generics, Ident, Lifetime, Member,
+    Type,
+};
+
+/// A field of the derived struct.
+pub struct SetField<'a> {
+    pub member: Member,
+    /// The field's name in account paths, which is its index for tuple structs.
+    pub name: String,
+    pub ty: &'a Type,
+    pub docs: TokenStream,
+    pub args: FieldArgs,
+}
+
+/// The parsed struct the `AccountSet` derive is generating impls for.
+pub struct AccountSetStruct<'a> {
+    pub ident: &'a Ident,
+    pub generics: &'a Generics,
+    pub docs: TokenStream,
+    pub fields: Vec<SetField<'a>>,
+    /// The index of the `#[single_account_set]` field.
+    pub single: Option<usize>,
+    /// Indexed by [`Step`].
+    pub impls: [Vec<StepImpl>; 4],
+    /// `#[account_set(check_duplicates)]`.
+    pub check_duplicates: bool,
+    pub paths: Paths,
+}
+
+impl SetField<'_> {
+    /// The field's `PathSegment`.
+    pub fn path_segment(&self, account_set_path: &TokenStream) -> TokenStream {
+        let name = &self.name;
+        quote!(#account_set_path::PathSegment::Field(#name))
+    }
+}
+
+impl AccountSetStruct<'_> {
+    pub fn single_field(&self) -> Option<&SetField<'_>> {
+        self.single.map(|index| &self.fields[index])
+    }
+
+    /// The fields that aren't `#[account_set(skip)]`ped.
+    pub fn set_fields(&self) -> impl Iterator<Item = &SetField<'_>> {
+        self.fields.iter().filter(|field| field.args.skip.is_none())
+    }
+
+    /// The struct's generics merged with an impl's `generics`, after an optional leading lifetime. Lifetimes are
+    /// kept before the other parameters.
+    pub fn impl_generics(&self, step_impl: &StepImpl, lifetime: Option<&Lifetime>) -> Generics {
+        let params = lifetime
+            .map(|lifetime| parse_quote!(#lifetime))
+            .into_iter()
+            .chain(self.generics.params.iter().cloned())
+            .chain(step_impl.generics.params.iter().cloned());
+        let (lifetimes, others): (Vec<GenericParam>, Vec<GenericParam>) =
+            params.partition(|param| matches!(param, GenericParam::Lifetime(_)));
+        let mut generics = Generics {
+            params: lifetimes.into_iter().chain(others).collect(),
+            ..self.generics.clone()
+        };
+        if let Some(extra) = &step_impl.generics.where_clause {
+            generics
+                .make_where_clause()
+                .predicates
+                .extend(extra.predicates.iter().cloned());
+        }
+        if let Some(single) = self.single_field() {
+            let account_set = &self.paths.account_set;
+            let ty = single.ty;
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(#ty: #account_set::SingleAccountSet));
+        }
+        generics
+    }
+}
+
+/// The argument type a field's step is called with, if it can be inferred from the field's attribute.
+///
+/// Fields without an `arg` get `()`. `arg` is the impl's argument type, and `arg.0` is an element of a tuple
+/// argument. Any other expression has to be given an `arg_ty`, or the field's trait is called without a type.
+pub fn field_arg_ty(step_impl: &StepImpl, field_step: Option<&FieldStep>) -> Option<Type> {
+    fn strip_group(ty: &Type) -> &Type {
+        match ty {
+            Type::Group(group) => strip_group(&group.elem),
+            Type::Paren(paren) => strip_group(&paren.elem),
+            ty => ty,
+        }
+    }
+
+    fn infer(expr: &Expr, arg: &Type) -> Option<Type> {
+        match expr {
+            Expr::Path(path) if path.path.is_ident("arg") => Some(arg.clone()),
+            Expr::Paren(paren) => infer(&paren.expr, arg),
+            Expr::Group(group) => infer(&group.expr, arg),
+            Expr::Field(field) => {
+                let base = infer(&field.base, arg)?;
+                match (strip_group(&base), &field.member) {
+                    (Type::Tuple(tuple), Member::Unnamed(index)) => {
+                        tuple.elems.iter().nth(index.index as usize).cloned()
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    let Some(field_step) = field_step else {
+        return Some(parse_quote!(()));
+    };
+    if let Some(arg_ty) = &field_step.arg_ty {
+        return Some(arg_ty.clone());
+    }
+    match &field_step.arg {
+        None => Some(parse_quote!(())),
+        Some(arg) => infer(arg, &step_impl.arg),
+    }
+}
+
+/// The argument expression a field's step is called with.
+pub fn field_arg(field_step: Option<&FieldStep>) -> TokenStream {
+    match field_step.and_then(|field_step| field_step.arg.as_ref()) {
+        Some(arg) => arg.to_token_stream(),
+        None => quote!(()),
+    }
+}
+
+/// The attribute added to generated step functions.
+pub fn inline_attr(step_impl: &StepImpl) -> TokenStream {
+    if step_impl.inline_always {
+        quote!(#[inline(always)])
+    } else {
+        quote!(#[inline])
+    }
+}
+
+/// Whether `ty` is a path to a type named `name`, like `Many` for `Many<T>`.
+fn is_set_named(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == name),
+        Type::Group(group) => is_set_named(&group.elem, name),
+        _ => false,
+    }
+}
+
+/// Whether `tokens` mention the identifier `ident`, like `A` in `(Seeds<S>, A)`.
+fn mentions_ident(tokens: TokenStream, ident: &str) -> bool {
+    tokens.into_iter().any(|tree| match tree {
+        TokenTree::Ident(found) => found == ident,
+        TokenTree::Group(group) => mentions_ident(group.stream(), ident),
+        TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+    })
+}
+
+pub fn derive_account_set_impl(input: DeriveInput) -> TokenStream {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => abort!(data.enum_token, "AccountSet cannot be derived for enums"),
+        Data::Union(data) => abort!(data.union_token, "AccountSet cannot be derived for unions"),
+    };
+    let fields: Vec<_> = match fields {
+        Fields::Unit => vec![],
+        Fields::Named(_) | Fields::Unnamed(_) => fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let member = match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(index.into()),
+                };
+                SetField {
+                    name: match &field.ident {
+                        Some(ident) => ident.to_string(),
+                        None => index.to_string(),
+                    },
+                    member,
+                    ty: &field.ty,
+                    docs: get_docs(&field.attrs),
+                    args: parse_field_args(field),
+                }
+            })
+            .collect(),
+    };
+
+    let mut singles = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| field.args.single.is_some());
+    let single = singles.next().map(|(index, _)| index);
+    if let Some((_, field)) = singles.next() {
+        abort!(field.ty, "Only one field can be a `single_account_set`");
+    }
+    if let Some(single) = single {
+        if let Some(field) = fields
+            .iter()
+            .enumerate()
+            .find(|(index, field)| *index != single && field.args.skip.is_none())
+            .map(|(_, field)| field)
+        {
+            abort!(
+                field.ty,
+                "All fields other than the `single_account_set` must be skipped with `#[account_set(skip = <expr>)]`"
+            );
+        }
+    }
+
+    // `Many` takes all of the remaining accounts, so there would be none left to decode the fields after it.
+    let mut set_fields = fields.iter().filter(|field| field.args.skip.is_none());
+    set_fields.next_back();
+    if let Some(field) = set_fields.find(|field| is_set_named(field.ty, "Many")) {
+        abort!(
+            field.ty,
+            "All `Many` account sets must come at the end of the account set"
+        );
+    }
+
+    let struct_args = parse_struct_args(&input.attrs);
+    if struct_args.check_duplicates && single.is_some() {
+        abort!(
+            input.ident,
+            "`check_duplicates` can't be used on a `single_account_set`, which only has one account"
+        );
+    }
+    let impls = Step::ALL.map(|step| {
+        let mut impls = parse_step_impls(&input.attrs, step);
+        if !impls.iter().any(|step_impl| step_impl.id.is_none())
+            && !struct_args.skip_default[step as usize]
+        {
+            impls.push(StepImpl {
+                is_default: true,
+                ..StepImpl::default()
+            });
+        }
+        for (index, step_impl) in impls.iter().enumerate() {
+            if impls[..index].iter().any(|other| other.id == step_impl.id) {
+                abort!(
+                    input.ident,
+                    "Duplicate `#[{}]` id `{}`",
+                    step.attr(),
+                    step_impl.id.as_deref().unwrap_or("<none>")
+                );
+            }
+        }
+        for field in &fields {
+            for field_step in &field.args.steps[step as usize] {
+                if !impls.iter().any(|step_impl| step_impl.id == field_step.id) {
+                    abort!(
+                        field.ty,
+                        "No struct level `#[{}]` with id `{}`",
+                        step.attr(),
+                        field_step.id.as_deref().unwrap_or("<none>")
+                    );
+                }
+            }
+        }
+        impls
+    });
+
+    let account_set = AccountSetStruct {
+        ident: &input.ident,
+        generics: &input.generics,
+        docs: get_docs(&input.attrs),
+        fields,
+        single,
+        impls,
+        check_duplicates: struct_args.check_duplicates,
+        paths: Paths::default(),
+    };
+
+    let decode = decode::decode_impls(&account_set);
+    let validate = validate::validate_impls(&account_set, Step::Validate);
+    let cleanup = validate::validate_impls(&account_set, Step::Cleanup);
+    let accounts = accounts::accounts_impl(&account_set);
+    let single_set = single_set::single_set_impls(&account_set);
+    let idl = idl::idl_impls(&account_set);
+    let client = client::client_impls(&account_set, &input.vis);
+
+    quote! {
+        #decode
+        #validate
+        #cleanup
+        #accounts
+        #single_set
+        #idl
+        #client
+    }
+}
+
+/// An identifier that doesn't collide with the struct's generics.
+pub fn new_generic(generics: &Generics, name: &str) -> Ident {
+    let mut ident = format_ident!("__{}", name);
+    while generics.type_params().any(|param| param.ident == ident) {
+        ident = format_ident!("{}_", ident);
+    }
+    ident
+}
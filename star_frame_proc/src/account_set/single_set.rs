@@ -0,0 +1,201 @@
+use crate::account_set::{new_generic, AccountSetStruct};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Generics, WherePredicate};
+
+/// Generates `SingleAccountSet` for a struct with a `#[single_account_set]` field, and forwards the marker traits
+/// in `account_set::modifiers` from the field.
+pub fn single_set_impls(account_set: &AccountSetStruct) -> TokenStream {
+    let Some(field) = account_set.single_field() else {
+        return TokenStream::new();
+    };
+    let Some(single) = &field.args.single else {
+        return TokenStream::new();
+    };
+    let AccountSetStruct {
+        ident,
+        generics,
+        paths,
+        ..
+    } = account_set;
+    let prelude = &paths.prelude;
+    let account_set_path = &paths.account_set;
+    let modifiers = quote!(#account_set_path::modifiers);
+    let member = &field.member;
+    let ty = field.ty;
+    let (_, type_generics, _) = generics.split_for_impl();
+
+    // Bounds on the field are made higher ranked, so concrete fields that don't implement a forwarded trait don't
+    // hit trivially false bounds.
+    let with_bounds = |generics: &Generics, bounds: Vec<WherePredicate>| {
+        let mut generics = generics.clone();
+        generics.make_where_clause().predicates.extend(bounds);
+        generics
+    };
+    let forwarded = |bound: TokenStream| {
+        let generics = with_bounds(generics, vec![parse_quote!(for<'__a> #ty: #bound)]);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote!(impl #impl_generics #bound for #ident #type_generics #where_clause)
+    };
+
+    let meta = {
+        let signer = single.signer.then(|| quote!(meta.signer = true;));
+        let writable = single.writable.then(|| quote!(meta.writable = true;));
+        if signer.is_some() || writable.is_some() {
+            quote! {
+                let mut meta = <#ty as #account_set_path::SingleAccountSet>::meta();
+                #signer
+                #writable
+                meta
+            }
+        } else {
+            quote!(<#ty as #account_set_path::SingleAccountSet>::meta())
+        }
+    };
+    let single_generics = with_bounds(
+        generics,
+        vec![parse_quote!(#ty: #account_set_path::SingleAccountSet)],
+    );
+    let (impl_generics, _, where_clause) = single_generics.split_for_impl();
+    let single_account_set = quote! {
+        #[automatically_derived]
+        impl #impl_generics #account_set_path::SingleAccountSet for #ident #type_generics #where_clause {
+            #[inline]
+            fn meta() -> #account_set_path::SingleSetMeta {
+                #meta
+            }
+
+            #[inline]
+            fn account_info(&self) -> &#prelude::AccountInfo {
+                #account_set_path::SingleAccountSet::account_info(&self.#member)
+            }
+
+            #[inline]
+            fn stored_bump(&self) -> #prelude::Result<::core::option::Option<u8>> {
+                #account_set_path::SingleAccountSet::stored_bump(&self.#member)
+            }
+        }
+    };
+
+    let signed_account = (!single.skip_signed_account).then(|| {
+        let header = forwarded(quote!(#modifiers::SignedAccount));
+        quote! {
+            #[automatically_derived]
+            #header {
+                #[inline]
+                fn signer_seeds(&self) -> ::core::option::Option<#account_set_path::seeded::SignerSeeds<'_>> {
+                    #modifiers::SignedAccount::signer_seeds(&self.#member)
+                }
+            }
+        }
+    });
+    let writable_account = (!single.skip_writable_account).then(|| {
+        let header = forwarded(quote!(#modifiers::WritableAccount));
+        quote! {
+            #[automatically_derived]
+            #header {}
+        }
+    });
+    let associated_type = |skip: bool, trait_ident: TokenStream, assoc: TokenStream| {
+        (!skip).then(|| {
+            let header = forwarded(quote!(#modifiers::#trait_ident));
+            quote! {
+                #[automatically_derived]
+                #header {
+                    type #assoc = <#ty as #modifiers::#trait_ident>::#assoc;
+                }
+            }
+        })
+    };
+    let has_inner_type = associated_type(
+        single.skip_has_inner_type,
+        quote!(HasInnerType),
+        quote!(Inner),
+    );
+    let has_owner_program = associated_type(
+        single.skip_has_owner_program,
+        quote!(HasOwnerProgram),
+        quote!(OwnerProgram),
+    );
+    let has_seeds = associated_type(single.skip_has_seeds, quote!(HasSeeds), quote!(Seeds));
+    let read_account = (!single.skip_read_account).then(|| {
+        let header = forwarded(quote!(#modifiers::ReadAccount));
+        quote! {
+            #[automatically_derived]
+            #header {
+                type Data = <#ty as #modifiers::ReadAccount>::Data;
+
+                #[inline]
+                fn read_data(&self) -> #prelude::Result<Self::Data> {
+                    #modifiers::ReadAccount::read_data(&self.#member)
+                }
+            }
+        }
+    });
+
+    let arg = new_generic(generics, "A");
+    let mut arg_generics = (*generics).clone();
+    arg_generics.params.push(parse_quote!(#arg));
+    let can_init_seeds = (!single.skip_can_init_seeds).then(|| {
+        let generics = with_bounds(
+            &arg_generics,
+            vec![
+                parse_quote!(#ty: #modifiers::CanInitSeeds<#arg>),
+                parse_quote!(Self: #account_set_path::AccountSetValidate<#arg>),
+            ],
+        );
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #modifiers::CanInitSeeds<#arg> for #ident #type_generics #where_clause {
+                #[inline]
+                fn init_seeds(&mut self, arg: &#arg, ctx: &#prelude::Context) -> #prelude::Result<()> {
+                    <#ty as #modifiers::CanInitSeeds<#arg>>::init_seeds(&mut self.#member, arg, ctx)
+                }
+            }
+        }
+    });
+    let can_init_account = (!single.skip_can_init_account).then(|| {
+        let generics = with_bounds(
+            &arg_generics,
+            vec![parse_quote!(#ty: #modifiers::CanInitAccount<#arg>)],
+        );
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #modifiers::CanInitAccount<#arg> for #ident #type_generics #where_clause {
+                #[inline]
+                fn init_space(arg: &#arg) -> ::core::option::Option<usize> {
+                    <#ty as #modifiers::CanInitAccount<#arg>>::init_space(arg)
+                }
+
+                #[inline]
+                fn init_account<const IF_NEEDED: bool>(
+                    &mut self,
+                    arg: #arg,
+                    account_seeds: ::core::option::Option<&[&[u8]]>,
+                    ctx: &#prelude::Context,
+                ) -> #prelude::Result<()> {
+                    <#ty as #modifiers::CanInitAccount<#arg>>::init_account::<IF_NEEDED>(
+                        &mut self.#member,
+                        arg,
+                        account_seeds,
+                        ctx,
+                    )
+                }
+            }
+        }
+    });
+
+    quote! {
+        #single_account_set
+        #signed_account
+        #writable_account
+        #has_inner_type
+        #has_owner_program
+        #has_seeds
+        #read_account
+        #can_init_seeds
+        #can_init_account
+    }
+}
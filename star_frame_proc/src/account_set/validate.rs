@@ -0,0 +1,263 @@
+use crate::account_set::{
+    args::HasOne, field_arg, field_arg_ty, inline_attr, AccountSetStruct, FieldStep, SetField, Step,
+};
+use proc_macro2::TokenStream;
+use proc_macro_error2::abort;
+use quote::{quote, ToTokens};
+use syn::{parse_quote, Member};
+
+/// The `address`, `owner`, `has_one`, and `constraint` checks of a field, which run after the field is validated.
+///
+/// Each check breaks out of the `'__check` block with its error, so the field's account path can be added to it.
+fn field_checks(
+    account_set: &AccountSetStruct,
+    field: &SetField,
+    field_step: &FieldStep,
+) -> Vec<TokenStream> {
+    let prelude = &account_set.paths.prelude;
+    let account_set_path = &account_set.paths.account_set;
+    let member = &field.member;
+    let name = &field.name;
+    let fail = |error: TokenStream, message: TokenStream| {
+        quote! {
+            break '__check ::core::result::Result::Err(::star_frame::error!(#error, #message));
+        }
+    };
+
+    let mut checks = vec![];
+    if let Some(address) = &field_step.address {
+        let fail = fail(
+            quote!(::star_frame::errors::ErrorCode::AddressMismatch),
+            quote!(
+                "Expected `{}` to be `{}`, found `{}`",
+                #name,
+                #prelude::DisplayPubkey::display(expected),
+                #prelude::DisplayPubkey::display(actual)
+            ),
+        );
+        checks.push(quote! {
+            let expected: &#prelude::Pubkey = &#address;
+            let actual = #account_set_path::SingleAccountSet::pubkey(&self.#member);
+            if !#prelude::FastPubkeyEq::fast_eq(actual, expected) {
+                #fail
+            }
+        });
+    }
+    if let Some(owner) = &field_step.owner {
+        let fail = fail(
+            quote!(::star_frame::errors::ErrorCode::OwnerMismatch),
+            quote!(
+                "Expected `{}` to be owned by `{}`, found `{}`",
+                #name,
+                #prelude::DisplayPubkey::display(expected),
+                #prelude::DisplayPubkey::display(&actual)
+            ),
+        );
+        checks.push(quote! {
+            let expected = &<#owner as #prelude::StarFrameProgram>::ID;
+            let actual = #account_set_path::SingleAccountSet::owner_pubkey(&self.#member);
+            if !#prelude::FastPubkeyEq::fast_eq(&actual, expected) {
+                #fail
+            }
+        });
+    }
+    for HasOne {
+        field: key,
+        accessor,
+    } in &field_step.has_one
+    {
+        if !account_set
+            .set_fields()
+            .any(|other| matches!(&other.member, Member::Named(ident) if ident == key))
+        {
+            abort!(key, "No account set field named `{}`", key);
+        }
+        let key_str = key.to_string();
+        let fail = fail(
+            quote!(::star_frame::errors::ErrorCode::HasOneMismatch),
+            quote!(
+                "Expected `{}` stored in `{}` to be `{}`, found `{}`",
+                #key_str,
+                #name,
+                #prelude::DisplayPubkey::display(expected),
+                #prelude::DisplayPubkey::display(&actual)
+            ),
+        );
+        let stored = match accessor {
+            Some(accessor) => quote!(self.#member.#accessor()),
+            None => quote! {
+                #account_set_path::modifiers::ReadAccount::read_data(&self.#member)
+                    .map(|data| data.#key)
+            },
+        };
+        checks.push(quote! {
+            let expected = #account_set_path::SingleAccountSet::pubkey(&self.#key);
+            let actual: #prelude::Pubkey = match #stored {
+                ::core::result::Result::Ok(actual) => actual,
+                ::core::result::Result::Err(error) => break '__check ::core::result::Result::Err(error.into()),
+            };
+            if !#prelude::FastPubkeyEq::fast_eq(&actual, expected) {
+                #fail
+            }
+        });
+    }
+    if let Some(constraint) = &field_step.constraint {
+        let error = match &field_step.error {
+            Some(error) => error.to_token_stream(),
+            None => quote!(::star_frame::errors::ErrorCode::ConstraintViolated),
+        };
+        let constraint_str = constraint.to_token_stream().to_string();
+        let fail = fail(
+            error,
+            quote!("Constraint `{}` failed for `{}`", #constraint_str, #name),
+        );
+        checks.push(quote! {
+            if !(#constraint) {
+                #fail
+            }
+        });
+    }
+    checks.into_iter().map(|check| quote!({ #check })).collect()
+}
+
+/// Generates the `AccountSetValidate` or `AccountSetCleanup` impls, which share their shape.
+pub fn validate_impls(account_set: &AccountSetStruct, step: Step) -> TokenStream {
+    let AccountSetStruct {
+        ident,
+        generics,
+        paths,
+        ..
+    } = account_set;
+    let prelude = &paths.prelude;
+    let account_set_path = &paths.account_set;
+    let (_, type_generics, _) = generics.split_for_impl();
+    let (trait_ident, fn_ident) = match step {
+        Step::Validate => (quote!(AccountSetValidate), quote!(validate_accounts)),
+        Step::Cleanup => (quote!(AccountSetCleanup), quote!(cleanup_accounts)),
+        Step::Decode | Step::Idl => unreachable!(),
+    };
+
+    let impls = account_set.impls[step as usize].iter().map(|step_impl| {
+        let mut impl_generics = account_set.impl_generics(step_impl, None);
+        let id = step_impl.id.as_deref();
+        let arg_ty = &step_impl.arg;
+
+        let mut funder = None;
+        let mut recipient = None;
+        let mut fields = vec![];
+        for field in account_set.set_fields() {
+            let member = &field.member;
+            let ty = field.ty;
+            let segment = field.path_segment(account_set_path);
+            let field_step = field.args.step(step, id);
+            if field_step.is_some_and(|field_step| field_step.skip) {
+                continue;
+            }
+
+            let arg = field_arg(field_step);
+            let trait_arg = match field_arg_ty(step_impl, field_step) {
+                Some(field_arg_ty) => {
+                    impl_generics.make_where_clause().predicates.push(parse_quote!(
+                        #ty: #account_set_path::#trait_ident<#field_arg_ty>
+                    ));
+                    quote!(#field_arg_ty)
+                }
+                None => quote!(_),
+            };
+            let call = quote! {
+                <#ty as #account_set_path::#trait_ident<#trait_arg>>::#fn_ident(&mut self.#member, #arg, ctx)
+            };
+            fields.push(if account_set.single.is_some() {
+                quote!(#call?;)
+            } else {
+                quote!(#prelude::ErrorInfo::account_path(#call, #segment)?;)
+            });
+
+            let Some(field_step) = field_step else {
+                continue;
+            };
+            let checks = field_checks(account_set, field, field_step);
+            if !checks.is_empty() {
+                impl_generics
+                    .make_where_clause()
+                    .predicates
+                    .push(parse_quote!(#ty: #account_set_path::SingleAccountSet));
+                if field_step.has_one.iter().any(|has_one| has_one.accessor.is_none()) {
+                    impl_generics
+                        .make_where_clause()
+                        .predicates
+                        .push(parse_quote!(#ty: #account_set_path::modifiers::ReadAccount));
+                }
+                let checked = quote! {
+                    {
+                        let checked: #prelude::Result<()> = '__check: {
+                            #(#checks)*
+                            ::core::result::Result::Ok(())
+                        };
+                        checked
+                    }
+                };
+                fields.push(if account_set.single.is_some() {
+                    quote!(#checked?;)
+                } else {
+                    quote!(#prelude::ErrorInfo::account_path(#checked, #segment)?;)
+                });
+            }
+            for (is_set, slot, trait_ident, label) in [
+                (field_step.funder, &mut funder, quote!(CanFundRent), "funder"),
+                (field_step.recipient, &mut recipient, quote!(CanAddLamports), "recipient"),
+            ] {
+                if !is_set {
+                    continue;
+                }
+                if slot.is_some() {
+                    abort!(field.ty, "Only one field can be the {}", label);
+                }
+                impl_generics.make_where_clause().predicates.push(parse_quote!(
+                    #ty: #account_set_path::#trait_ident + ::core::clone::Clone + 'static
+                ));
+                *slot = Some(member);
+            }
+        }
+
+        let set_funder = funder.map(|member| {
+            quote!(ctx.set_funder(::core::clone::Clone::clone(&self.#member));)
+        });
+        let set_recipient = recipient.map(|member| {
+            quote!(ctx.set_recipient(::core::clone::Clone::clone(&self.#member));)
+        });
+        let before = step_impl
+            .before_validation
+            .as_ref()
+            .map(|before| quote!((#before)?;));
+        let extra = step_impl.extra.as_ref().map(|extra| quote!((#extra)?;));
+        // Checked before the fields are validated, so nothing acts on an aliased account.
+        let check_duplicates = (step == Step::Validate && account_set.check_duplicates).then(|| {
+            impl_generics.make_where_clause().predicates.push(parse_quote!(
+                #ident #type_generics: #account_set_path::AccountSetAccounts
+            ));
+            quote!(#account_set_path::check_duplicate_writable(self)?;)
+        });
+
+        let inline = inline_attr(step_impl);
+        let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #account_set_path::#trait_ident<#arg_ty> for #ident #type_generics #where_clause {
+                #inline
+                #[allow(unused_variables)]
+                fn #fn_ident(&mut self, arg: #arg_ty, ctx: &mut #prelude::Context) -> #prelude::Result<()> {
+                    #before
+                    #check_duplicates
+                    #(#fields)*
+                    #set_funder
+                    #set_recipient
+                    #extra
+                    ::core::result::Result::Ok(())
+                }
+            }
+        }
+    });
+
+    quote!(#(#impls)*)
+}
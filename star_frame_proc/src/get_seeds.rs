@@ -0,0 +1,485 @@
+use crate::util::{get_docs, ignore_cfg_module, new_lifetime, Paths};
+use proc_macro2::TokenStream;
+use proc_macro_error2::abort;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Attribute, Data, DeriveInput, Expr, Field, Fields, GenericArgument, Ident, LitStr,
+    Member, PathArguments, Type, WhereClause,
+};
+
+/// The maximum number of seeds, including the bump. Mirrors `star_frame::account_set::seeded::MAX_SEEDS`.
+const MAX_SEEDS: usize = 16;
+
+#[derive(Debug, Default)]
+pub struct GetSeedsArgs {
+    pub seed_const: Option<Expr>,
+    pub skip_idl: bool,
+}
+
+impl GetSeedsArgs {
+    fn parse(attrs: &[Attribute], ident: &Ident) -> Self {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident(ident)) {
+            let result = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("seed_const") {
+                    out.seed_const = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("skip_idl") {
+                    out.skip_idl = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("Expected `seed_const = <expr>` or `skip_idl`"))
+                }
+            });
+            if let Err(error) = result {
+                abort!(error.span(), error.to_string());
+            }
+        }
+        out
+    }
+}
+
+/// How a field's seed is represented, decided from its type.
+enum SeedKind<'a> {
+    /// Strings like `String` or `&str`, whose length is only known at runtime.
+    Str,
+    /// Byte slices like `Vec<u8>` or `&[u8]`, whose length is only known at runtime.
+    Bytes,
+    /// `PodStr<N>`, a fixed size string zero padded to `N` bytes.
+    PodStr(&'a GenericArgument),
+    /// Any other `Seed`. The type to check the length of, with references stripped.
+    Fixed(&'a Type),
+}
+
+fn single_generic_arg(arguments: &PathArguments) -> Option<&GenericArgument> {
+    match arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter(|arg| !matches!(arg, GenericArgument::Lifetime(_)))
+            .last(),
+        _ => None,
+    }
+}
+
+fn seed_kind(ty: &Type) -> SeedKind<'_> {
+    match ty {
+        Type::Reference(reference) => seed_kind(&reference.elem),
+        Type::Group(group) => seed_kind(&group.elem),
+        Type::Paren(paren) => seed_kind(&paren.elem),
+        Type::Slice(slice) if matches!(&*slice.elem, Type::Path(path) if path.path.is_ident("u8")) => {
+            SeedKind::Bytes
+        }
+        Type::Path(path) if path.qself.is_none() => {
+            let Some(segment) = path.path.segments.last() else {
+                return SeedKind::Fixed(ty);
+            };
+            let arg = single_generic_arg(&segment.arguments);
+            match (segment.ident.to_string().as_str(), arg) {
+                ("str" | "String", _) => SeedKind::Str,
+                ("Vec", Some(GenericArgument::Type(Type::Path(inner))))
+                    if inner.path.is_ident("u8") =>
+                {
+                    SeedKind::Bytes
+                }
+                ("Box" | "Cow" | "Rc" | "Arc", Some(GenericArgument::Type(inner))) => {
+                    match seed_kind(inner) {
+                        kind @ (SeedKind::Str | SeedKind::Bytes) => kind,
+                        _ => SeedKind::Fixed(ty),
+                    }
+                }
+                ("PodStr", Some(len)) => SeedKind::PodStr(len),
+                _ => SeedKind::Fixed(ty),
+            }
+        }
+        _ => SeedKind::Fixed(ty),
+    }
+}
+
+/// The name of a field in errors and the IDL, which is its index for tuple fields.
+fn field_name(index: usize, field: &Field) -> String {
+    field
+        .ident
+        .as_ref()
+        .map_or_else(|| index.to_string(), ToString::to_string)
+}
+
+/// The binding used for a field when matching on an enum variant.
+fn field_binding(index: usize, field: &Field) -> Ident {
+    field
+        .ident
+        .clone()
+        .unwrap_or_else(|| format_ident!("field_{index}"))
+}
+
+/// Destructures `fields` into their bindings, e.g. `{ a, b }` or `(field_0, field_1)`.
+fn fields_pattern(fields: &Fields) -> TokenStream {
+    let bindings = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| field_binding(index, field));
+    match fields {
+        Fields::Named(_) => quote!({ #(#bindings),* }),
+        Fields::Unnamed(_) => quote!((#(#bindings),*)),
+        Fields::Unit => quote!(),
+    }
+}
+
+/// Checks the seed length at compile time if it's known, using `label` in the error message.
+fn seed_len_check(field: &Field, label: &str, prelude: &TokenStream) -> Option<TokenStream> {
+    let ty = match seed_kind(&field.ty) {
+        SeedKind::Fixed(ty) => ty,
+        SeedKind::PodStr(_) => &field.ty,
+        SeedKind::Str | SeedKind::Bytes => return None,
+    };
+    let message = LitStr::new(
+        &format!("`{label}` is longer than the maximum seed length of 32 bytes"),
+        proc_macro2::Span::call_site(),
+    );
+    Some(quote! {
+        if let Some(len) = <#ty as #prelude::Seed>::SEED_LEN {
+            assert!(len <= #prelude::MAX_SEED_LEN, #message);
+        }
+    })
+}
+
+/// The `IdlSeed` of a field, adding any `TypeToIdl` bounds it needs to `where_clause`.
+fn idl_seed(
+    field: &Field,
+    name: &str,
+    where_clause: &mut WhereClause,
+    wc_for: &syn::Lifetime,
+    prelude: &TokenStream,
+) -> TokenStream {
+    let ty = &field.ty;
+    let docs = get_docs(&field.attrs);
+    match seed_kind(ty) {
+        SeedKind::Str => quote! {
+            #prelude::IdlSeed::String {
+                name: #name.to_string(),
+                description: #docs,
+                padded_len: None,
+            }
+        },
+        SeedKind::PodStr(len) => quote! {
+            #prelude::IdlSeed::String {
+                name: #name.to_string(),
+                description: #docs,
+                padded_len: Some(#len),
+            }
+        },
+        SeedKind::Bytes => quote! {
+            #prelude::IdlSeed::Bytes {
+                name: #name.to_string(),
+                description: #docs,
+            }
+        },
+        // references are seeded as the type they point to
+        SeedKind::Fixed(seed_ty) => {
+            where_clause.predicates.push(parse_quote! {
+                #seed_ty: for<#wc_for> #prelude::TypeToIdl
+            });
+            quote! {
+                #prelude::IdlSeed::Variable {
+                    name: #name.to_string(),
+                    description: #docs,
+                    ty: <#seed_ty as #prelude::TypeToIdl>::type_to_idl(idl_definition)?,
+                }
+            }
+        }
+    }
+}
+
+/// The fields of the generated `Find*` seeds type, wrapping each field's type in `FindSeed`.
+fn find_fields(fields: &Fields, public: bool, prelude: &TokenStream) -> TokenStream {
+    let find_fields = fields.iter().map(|field| {
+        let mut field = field.clone();
+        let ty = &field.ty;
+        field.vis = if public {
+            parse_quote!(pub)
+        } else {
+            syn::Visibility::Inherited
+        };
+        field.ty = parse_quote!(#prelude::FindSeed<#ty>);
+        field.attrs.retain(|attr| attr.path().is_ident("doc"));
+        field
+    });
+    match fields {
+        Fields::Named(_) => quote!({ #(#find_fields),* }),
+        Fields::Unnamed(_) => quote!((#(#find_fields),*)),
+        Fields::Unit => quote!(),
+    }
+}
+
+pub fn derive_get_seeds_impl(input: DeriveInput) -> TokenStream {
+    let Paths {
+        get_seeds_ident,
+        prelude,
+        ..
+    } = Paths::default();
+
+    let GetSeedsArgs {
+        skip_idl,
+        seed_const,
+    } = GetSeedsArgs::parse(&input.attrs, &get_seeds_ident);
+
+    let ident = &input.ident;
+    // a new lifetime for the higher ranked `TypeToIdl` bounds
+    let wc_for = new_lifetime(&input.generics, Some("wc"));
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let seeds_lifetime = new_lifetime(&input.generics, Some("seeds"));
+    let find_seeds_ident = format_ident!("Find{ident}");
+    let const_seed: Vec<_> = seed_const.iter().map(|expr| quote!(#expr)).collect();
+    let idl_const_seed: Vec<_> = seed_const
+        .iter()
+        .map(|expr| quote!(#prelude::IdlSeed::Const(#expr.to_vec())))
+        .collect();
+    let find_const_seed: Vec<_> = seed_const
+        .iter()
+        .map(|expr| quote!(#prelude::IdlFindSeed::Const(#expr.to_vec())))
+        .collect();
+
+    // `seed_count` doesn't count the trailing slot for the bump
+    let (seed_count, seeds_body, seed_len_checks, idl_seeds, find_seeds_def, find_seeds_body) =
+        match &input.data {
+            Data::Struct(data_struct) => {
+                let fields = &data_struct.fields;
+                let members: Vec<Member> = fields.members().collect();
+                let names: Vec<String> = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| field_name(index, field))
+                    .collect();
+                let seed_count = fields.len() + usize::from(seed_const.is_some());
+
+                let seeds = const_seed
+                    .iter()
+                    .cloned()
+                    .chain(members.iter().map(|member| quote!(self.#member.seed())));
+                let seeds_body = quote!([#(#seeds,)* &[]]);
+
+                let seed_len_checks: Vec<_> = fields
+                    .iter()
+                    .zip(&names)
+                    .filter_map(|(field, name)| {
+                        seed_len_check(field, &format!("{ident}::{name}"), &prelude)
+                    })
+                    .collect();
+
+                let mut idl_where_clause = input.generics.clone().make_where_clause().clone();
+                let field_seeds: Vec<_> = fields
+                    .iter()
+                    .zip(&names)
+                    .map(|(field, name)| {
+                        idl_seed(field, name, &mut idl_where_clause, &wc_for, &prelude)
+                    })
+                    .collect();
+                let idl_seeds = (
+                    quote!(vec![#(#idl_const_seed,)* #(#field_seeds),*]),
+                    idl_where_clause,
+                );
+
+                let find_fields = find_fields(fields, true, &prelude);
+                let find_seeds_def = match fields {
+                    Fields::Named(_) => quote! {
+                        pub struct #find_seeds_ident #type_generics #where_clause #find_fields
+                    },
+                    Fields::Unnamed(_) => quote! {
+                        pub struct #find_seeds_ident #type_generics #find_fields #where_clause;
+                    },
+                    Fields::Unit => quote! {
+                        pub struct #find_seeds_ident #type_generics #where_clause {}
+                    },
+                };
+                let find_seeds_body = quote! {
+                    vec![#(#find_const_seed,)* #(Into::into(&self.#members)),*]
+                };
+
+                (
+                    seed_count,
+                    seeds_body,
+                    seed_len_checks,
+                    idl_seeds,
+                    find_seeds_def,
+                    find_seeds_body,
+                )
+            }
+            Data::Enum(data_enum) => {
+                if data_enum.variants.is_empty() {
+                    abort!(
+                        ident,
+                        "GetSeeds cannot be derived for enums without variants"
+                    );
+                }
+                if data_enum.variants.len() > usize::from(u8::MAX) + 1 {
+                    abort!(ident, "GetSeeds enums can have at most 256 variants");
+                }
+                let max_fields = data_enum
+                    .variants
+                    .iter()
+                    .map(|variant| variant.fields.len())
+                    .max()
+                    .unwrap_or_default();
+                // the constant seed, the variant tag, and the fields of the largest variant
+                let seed_count = usize::from(seed_const.is_some()) + 1 + max_fields;
+
+                let mut arms = vec![];
+                let mut seed_len_checks = vec![];
+                let mut idl_where_clause = input.generics.clone().make_where_clause().clone();
+                let mut idl_variants = vec![];
+                let mut find_variants = vec![];
+                let mut find_arms = vec![];
+                for (tag, variant) in data_enum.variants.iter().enumerate() {
+                    let tag = u8::try_from(tag).expect("Checked variant count above");
+                    let variant_ident = &variant.ident;
+                    let fields = &variant.fields;
+                    let pattern = fields_pattern(fields);
+                    let bindings: Vec<Ident> = fields
+                        .iter()
+                        .enumerate()
+                        .map(|(index, field)| field_binding(index, field))
+                        .collect();
+                    let names: Vec<String> = fields
+                        .iter()
+                        .enumerate()
+                        .map(|(index, field)| field_name(index, field))
+                        .collect();
+
+                    // Variants with fewer fields are padded with empty seeds, which don't change the address
+                    let padding = (0..=max_fields - fields.len()).map(|_| quote!(&[]));
+                    arms.push(quote! {
+                        Self::#variant_ident #pattern => [
+                            #(#const_seed,)*
+                            &[#tag],
+                            #(#bindings.seed(),)*
+                            #(#padding),*
+                        ]
+                    });
+
+                    seed_len_checks.extend(fields.iter().zip(&names).filter_map(
+                        |(field, name)| {
+                            seed_len_check(
+                                field,
+                                &format!("{ident}::{variant_ident}::{name}"),
+                                &prelude,
+                            )
+                        },
+                    ));
+
+                    let variant_name = variant_ident.to_string();
+                    let docs = get_docs(&variant.attrs);
+                    let field_seeds = fields.iter().zip(&names).map(|(field, name)| {
+                        idl_seed(field, name, &mut idl_where_clause, &wc_for, &prelude)
+                    });
+                    idl_variants.push(quote! {
+                        #prelude::IdlSeedVariant {
+                            name: #variant_name.to_string(),
+                            description: #docs,
+                            tag: vec![#tag],
+                            seeds: vec![#(#field_seeds),*],
+                        }
+                    });
+
+                    let variant_find_fields = find_fields(fields, false, &prelude);
+                    find_variants.push(quote!(#variant_ident #variant_find_fields));
+                    find_arms.push(quote! {
+                        Self::#variant_ident #pattern => vec![
+                            #(#find_const_seed,)*
+                            #prelude::IdlFindSeed::Const(vec![#tag]),
+                            #(Into::into(#bindings)),*
+                        ]
+                    });
+                }
+
+                let seeds_body = quote! {
+                    match self {
+                        #(#arms,)*
+                    }
+                };
+                let idl_seeds = (
+                    quote! {
+                        vec![
+                            #(#idl_const_seed,)*
+                            #prelude::IdlSeed::Tagged(vec![#(#idl_variants),*]),
+                        ]
+                    },
+                    idl_where_clause,
+                );
+                let find_seeds_def = quote! {
+                    pub enum #find_seeds_ident #type_generics #where_clause {
+                        #(#find_variants),*
+                    }
+                };
+                let find_seeds_body = quote! {
+                    match self {
+                        #(#find_arms,)*
+                    }
+                };
+                (
+                    seed_count,
+                    seeds_body,
+                    seed_len_checks,
+                    idl_seeds,
+                    find_seeds_def,
+                    find_seeds_body,
+                )
+            }
+            Data::Union(u) => abort!(u.union_token, "GetSeeds cannot be derived for unions"),
+        };
+
+    if seed_count >= MAX_SEEDS {
+        abort!(
+            ident,
+            "GetSeeds can have at most {} seeds plus the bump, found {}",
+            MAX_SEEDS - 1,
+            seed_count
+        );
+    }
+    let array_size = seed_count + 1;
+
+    let idl_impl = (!skip_idl).then(|| {
+        let (idl_seeds, idl_where_clause) = idl_seeds;
+        ignore_cfg_module(
+            ident,
+            "_get_seeds_idl",
+            quote! {
+                #[cfg(all(feature = "idl", not(target_os = "solana")))]
+                #[automatically_derived]
+                impl #impl_generics #prelude::SeedsToIdl for #ident #type_generics #idl_where_clause {
+                    fn seeds_to_idl(idl_definition: &mut #prelude::IdlDefinition) -> #prelude::IdlResult<#prelude::IdlSeeds> {
+                        Ok(#prelude::IdlSeeds(#idl_seeds))
+                    }
+                }
+
+                #[cfg(all(feature = "idl", not(target_os = "solana")))]
+                #[derive(Debug, Clone)]
+                #find_seeds_def
+
+                #[cfg(all(feature = "idl", not(target_os = "solana")))]
+                #[automatically_derived]
+                impl #impl_generics #prelude::FindIdlSeeds for #find_seeds_ident #type_generics #where_clause {
+                    fn find_seeds(&self) -> #prelude::IdlResult<Vec<#prelude::IdlFindSeed>> {
+                        Ok(#find_seeds_body)
+                    }
+                }
+            },
+        )
+    });
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics #prelude::GetSeeds for #ident #type_generics #where_clause {
+            const SEED_COUNT: usize = #seed_count;
+            type Seeds<#seeds_lifetime> = [&#seeds_lifetime [u8]; #array_size] where Self: #seeds_lifetime;
+            fn seeds(&self) -> Self::Seeds<'_> {
+                use #prelude::Seed;
+                const {
+                    #(#seed_len_checks)*
+                }
+                #seeds_body
+            }
+        }
+
+        #idl_impl
+    }
+}
@@ -1,7 +1,7 @@
 // #![allow(clippy::let_and_return)]
-// mod account_set;
+mod account_set;
 // mod align1;
-// mod get_seeds;
+mod get_seeds;
 // mod hash;
 // mod idl;
 // mod instruction_args;
@@ -9,283 +9,390 @@
 // mod program;
 // mod program_account;
 // mod solana_pubkey;
-// mod star_frame_error;
+mod star_frame_error;
 // mod star_frame_instruction;
 // mod unsize;
-// mod util;
+mod util;
 // mod zero_copy;
 
-// use proc_macro_error2::proc_macro_error;
+use proc_macro_error2::proc_macro_error;
+use syn::{parse_macro_input, DeriveInput, ItemEnum};
 // use syn::{
 //     parse::Nothing, parse_macro_input, punctuated::Punctuated, token::Comma, DeriveInput, Item,
 //     ItemEnum, ItemFn, ItemImpl, LitStr,
 // };
 
-// /// Derives `AccountSet` lifecycle traits and `AccountSetToIdl` for a struct.
-// ///
-// /// The `AccountSet` proc macro generates implementations for the three core traits:
-// /// - `AccountSetDecode` - Decodes accounts from `&[AccountInfo]` arrays
-// /// - `AccountSetValidate` - Validates decoded accounts
-// /// - `AccountSetCleanup` - Performs cleanup operations after instruction execution
-// ///
-// /// It also generates client-side implementations:
-// /// - `CpiAccountSet` - Cross-program invocation account handling
-// /// - `ClientAccountSet` - Client-side account metadata generation
-// /// - `AccountSetToIdl
-// ///
-// /// This macro creates a comprehensive account management system that handles account validation,
-// /// decoding from account info arrays, cleanup operations, and IDL generation for Solana programs.
-// ///
-// /// # Integration with StarFrameInstruction
-// ///
-// /// When using AccountSet with `StarFrameInstruction`, the argument types specified in field-level
-// /// attributes must correspond to the argument types from `InstructionArgs`. The `arg` parameter
-// /// in field attributes should match the types available from the instruction's decode, validate,
-// /// run, and cleanup argument types.
-// ///
-// /// # Struct-level Attributes
-// ///
-// /// ## `#[account_set(skip_client_account_set, skip_cpi_account_set, skip_default_decode, skip_default_validate, skip_default_cleanup, skip_default_idl)]`
-// ///
-// /// Controls which implementations are generated:
-// /// - `skip_client_account_set` - Skips generating `ClientAccountSet` implementation
-// /// - `skip_cpi_account_set` - Skips generating `CpiAccountSet` implementation
-// /// - `skip_default_decode` - Skips generating default `AccountSetDecode` implementation
-// /// - `skip_default_validate` - Skips generating default `AccountSetValidate` implementation
-// /// - `skip_default_cleanup` - Skips generating default `AccountSetCleanup` implementation
-// /// - `skip_default_idl` - Skips generating default IDL implementations
-// ///
-// /// ## `#[decode(id = <str>, arg = <type>, generics = <generics>, inline_always)]`
-// ///
-// /// Define custom decode implementations with specific arguments:
-// /// - `id = <str>` - Unique identifier for this decode variant (optional, defaults to no id)
-// /// - `arg = <type>` - Type of argument passed to decode functions
-// /// - `generics = <generics>` - Additional generic parameters for this decode implementation
-// /// - `inline_always` - Whether to add `#[inline(always)]` to the decode implementation (by default `#[inline]` is added)
-// ///
-// /// ## `#[validate(id = <str>, arg = <type>, generics = <generics>, before_validation = <expr>, extra_validation = <expr>, inline_always)]`
-// ///
-// /// Define custom validation implementations:
-// /// - `id = <str>` - Unique identifier for this validate variant (optional, defaults to no id)
-// /// - `arg = <type>` - Type of argument passed to validate functions
-// /// - `generics = <generics>` - Additional generic parameters for this validate implementation
-// /// - `before_validation = <expr>` - Expression to execute before field validation
-// /// - `extra_validation = <expr>` - Expression to execute after field validation
-// /// - `inline_always` - Whether to add `#[inline(always)]` to the validate implementation (by default `#[inline]` is added)
-// ///
-// /// ## `#[cleanup(id = <str>, generics = <generics>, arg = <type>, extra_cleanup = <expr>, inline_always)]`
-// ///
-// /// Define custom cleanup implementations:
-// /// - `id = <str>` - Unique identifier for this cleanup variant
-// /// - `generics = <generics>` - Generic parameters for this cleanup implementation
-// /// - `arg = <type>` - Type of argument passed to cleanup functions
-// /// - `extra_cleanup = <expr>` - Cleanup expression to execute after field cleanup
-// /// - `inline_always` - Whether to add `#[inline(always)]` to the cleanup implementation (by default `#[inline]` is added)
-// ///
-// /// ## `#[idl(id = <str>, arg = <type>, generics = <generics>)]`
-// ///
-// /// Define custom IDL generation implementations:
-// /// - `id = <str>` - Unique identifier for this IDL variant (optional, defaults to no id)
-// /// - `arg = <type>` - Type of argument passed to IDL functions
-// /// - `generics = <generics>` - Additional generic parameters for this IDL implementation
-// ///
-// /// # Field-level Attributes
-// ///
-// /// ## `#[account_set(skip = <TokenStream>)]`
-// ///
-// /// Skip this field during account set processing. The field will be initialized with the provided default value.
-// ///
-// /// ## `#[single_account_set(signer, writable, meta = <expr>, skip_*)]`
-// ///
-// /// Mark a field as a single account set. This indicates that the AccountSet contains only one account
-// /// and all account set traits should be passed through to this flagged field. Only one field can have this attribute.
-// ///
-// /// Options:
-// /// - `signer` - Mark this account as a signer
-// /// - `writable` - Mark this account as writable
-// /// - `meta = <expr>` - Custom metadata expression
-// /// - `skip_signed_account` - Skip `SignedAccount` trait implementation
-// /// - `skip_writable_account` - Skip `WritableAccount` trait implementation
-// /// - `skip_has_inner_type` - Skip `HasInnerType` trait implementation
-// /// - `skip_has_owner_program` - Skip `HasOwnerProgram` trait implementation
-// /// - `skip_has_seeds` - Skip `HasSeeds` trait implementation
-// /// - `skip_can_init_seeds` - Skip `CanInitSeeds` trait implementation
-// /// - `skip_can_init_account` - Skip `CanInitAccount` trait implementation
-// ///
-// /// When a field is marked with `#[single_account_set]`, the generated AccountSet implementation will:
-// /// - Implement `SingleAccountSet` and delegate to the marked field
-// /// - Pass through `CpiAccountSet` and `ClientAccountSet` implementations
-// /// - Forward trait implementations like `SignedAccount`, `WritableAccount`, `HasSeeds`, etc.
-// ///
-// /// ## `#[validate(id = <str>, funder, recipient, skip, requires = [<field>, ...], arg = <expr>, temp = <expr>, arg_ty = <type>, address = <expr>)]`
-// ///
-// /// Pass arguments to field validation:
-// /// - `id = <str>` - Which validate variant this field participates in, to enable multiple `AccountSetValidate` implementations
-// /// - `funder` - Mark this field as the funder for the Context cache (only one field can be marked as funder)
-// /// - `recipient` - Mark this field as the recipient for the Context cache (only one field can be marked as recipient)
-// /// - `skip` - Skip validation for this field
-// /// - `requires = [<field>, ...]` - List of fields that must be validated before this field
-// /// - `arg = <expr>` - Argument to pass to the field's `AccountSetValidate`` function
-// /// - `temp = <expr>` - Temporary variable expression to use with `arg` (requires `arg` to be specified)
-// /// - `arg_ty = <type>` - Type of the validation argument. Usually inferred, but can be specified to get better error messages
-// /// - `address = <expr>` - Check that the field's key matches this address, expr must return a `&Pubkey`
-// ///
-// /// ## `#[decode(id = <str>, arg = <expr>)]`
-// ///
-// /// Pass arguments to field decoding:
-// /// - `id = <str>` - Which decode variant this field participates in, to enable multiple `AccountSetDecode` implementations
-// /// - `arg = <expr>` - Argument to pass to the field's `AccountSetDecode` function
-// ///
-// /// ## `#[cleanup(id = <str>, arg = <expr>)]`
-// ///
-// /// Pass arguments to field cleanup:
-// /// - `id = <str>` - Which cleanup variant this field participates in, to enable multiple `AccountSetCleanup` implementations
-// /// - `arg = <expr>` - Argument to pass to the field's `AccountSetCleanup` function
-// /// - `normalize_rent` - Mutually exclusive with `arg`, alias for `arg = NormalizeRent(())`
-// ///
-// /// ## `#[idl(id = <str>, arg = <expr>, address = <expr>)]`
-// ///
-// /// Pass arguments to IDL generation:
-// /// - `id = <str>` - Which IDL variant this field participates in, to enable multiple `AccountSetToIdl` implementations
-// /// - `arg = <expr>` - Argument to pass to the field's `AccountSetToIdl` function for IDL generation
-// /// - `address = <expr>` - Address expression for single account IDL generation, expr must return a `Pubkey`
-// ///
-// /// # Examples
-// ///
-// /// ## Basic Account Set
-// ///
-// /// ```
-// /// # fn main() {}
-// /// use star_frame::prelude::*;
-// ///
-// /// #[derive(AccountSet)]
-// /// pub struct BasicAccounts {
-// ///     pub authority: Signer,
-// ///     pub account: Mut<SystemAccount>,
-// ///     pub system_program: Program<System>,
-// /// }
-// /// ```
-// ///
-// /// ## Account Set with Custom Arguments
-// ///
-// /// ```
-// /// # fn main() {}
-// /// use star_frame::prelude::*;
-// ///
-// /// #[derive(AccountSet)]
-// /// #[decode(arg = usize)]
-// /// #[validate(arg = String, extra_validation = self.check_authority(arg))]
-// /// pub struct CustomAccounts {
-// ///     pub authority: Signer,
-// ///     // One of Vec's AccountSetDecode implementations takes in a usize to specify the number of accounts to decode, so it will try to decode `arg * 2` (for some reason?) accounts
-// ///     // which will be passed to the `AccountSetDecode` function from StarFrameInstruction as the decode arg
-// ///     #[decode(arg = arg * 2)]
-// ///     pub accounts: Vec<SystemAccount>,
-// /// }
-// ///
-// /// impl CustomAccounts {
-// ///     fn check_authority(&self, arg: String) -> Result<()> {
-// ///         todo!("check stuff")
-// ///     }
-// /// }
-// /// ```
-// ///
-// /// By setting the decode arg to usize, and validate to String, any `StarFrameInstruction` using this set must have an `InstructionArgs` implementation that returns those types.
-// ///
-// /// ## Single Account Set Newtype
-// ///
-// /// ```
-// /// # fn main() {}
-// /// use star_frame::{prelude::*, derive_more};
-// /// # #[derive(StarFrameProgram)]
-// /// # #[program(instruction_set = (), id = System::ID, no_entrypoint)]
-// /// # pub struct MyProgram;
-// /// # #[zero_copy(pod)]
-// /// # #[derive(ProgramAccount, Debug, Default)]
-// /// # pub struct CounterAccount { pub count: u64 }
-// ///
-// /// #[derive(AccountSet, derive_more::Deref, derive_more::DerefMut, Debug)]
-// /// pub struct WrappedCounter(#[single_account_set] Account<CounterAccount>);
-// /// ```
-// ///
-// /// This creates a newtype wrapper that implements `AccountSet` and passes through all account
-// /// traits to the inner `Account<CounterAccount>`. The `signer` and `writable` flags modify
-// /// the account's metadata for CPI and client usage. This will propagate all of the `account_set::modifier`
-// /// marker traits from the inner account to the newtype.
-// #[proc_macro_error]
-// #[proc_macro_derive(
-//     AccountSet,
-//     attributes(account_set, decode, validate, cleanup, idl, single_account_set)
-// )]
-// pub fn derive_account_set(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-//     let out = account_set::derive_account_set_impl(parse_macro_input!(input as DeriveInput));
-//     out.into()
-// }
+/// Derives the `AccountSet` lifecycle traits and `AccountSetToIdl` for a struct.
+///
+/// The `AccountSet` proc macro generates implementations for the three core traits:
+/// - `AccountSetDecode` - Decodes accounts from `&[AccountInfo]` arrays
+/// - `AccountSetValidate` - Validates decoded accounts
+/// - `AccountSetCleanup` - Performs cleanup operations after instruction execution
+///
+/// `AccountSetAccounts`, which lists the set's accounts for the duplicate account check, is also generated.
+///
+/// `AccountSetToIdl` is also generated behind the user crate's `idl` feature.
+///
+/// Off-chain, structs that aren't generic or a single account set also get a `<Name>ClientAccounts` struct of the keys
+/// a client passes for each field, with a trailing `Accounts` in the name replaced, so `InitializeAccounts` gets
+/// `InitializeClientAccounts`. It implements `ClientAccountSet`, which lists the keys as account metas. `Option`
+/// fields take an `Option<Pubkey>`, and `None` is passed as the program id.
+///
+/// Fields are decoded, validated, and cleaned up in order, and errors from a field have the field's name added to
+/// their account path. A `Many` field takes all of the remaining accounts, so it must be the last field that isn't
+/// skipped. A `Lazy` field is only validated when it's first accessed, and its errors are given its full path then,
+/// through `AccountSetAccounts::prefix_path`, which the derive passes on to every field that isn't skipped or
+/// `allow_duplicate`.
+///
+/// # Integration with StarFrameInstruction
+///
+/// When using AccountSet with `StarFrameInstruction`, the argument types specified in field-level
+/// attributes must correspond to the argument types from `InstructionArgs`. The `arg` parameter
+/// in field attributes should match the types available from the instruction's decode, validate,
+/// run, and cleanup argument types.
+///
+/// # Struct-level Attributes
+///
+/// ## `#[account_set(skip_default_decode, skip_default_validate, skip_default_cleanup, skip_default_idl)]`
+///
+/// Controls which implementations are generated. Without a `#[decode]`, `#[validate]`, `#[cleanup]`, or `#[idl]`
+/// that has no `id`, a default implementation taking `()` is generated, unless it is skipped:
+/// - `skip_default_decode` - Skips generating default `AccountSetDecode` implementation
+/// - `skip_default_validate` - Skips generating default `AccountSetValidate` implementation
+/// - `skip_default_cleanup` - Skips generating default `AccountSetCleanup` implementation
+/// - `skip_default_idl` - Skips generating default IDL implementations
+///
+/// ## `#[account_set(check_duplicates)]`
+///
+/// Checks that no account a field declares writable, like with `Mut`, was passed in more than once before the fields
+/// are validated, including accounts in nested sets and `Many` collections. The entrypoint hands out the same account
+/// for each copy of a key, so two `Mut` fields with the same key would otherwise alias each other. Read-only fields
+/// can share a key with a `Mut` field. Fails with `ErrorCode::DuplicateWritableAccount`, naming the paths of both
+/// accounts. Can't be used on a single account set.
+///
+/// ## `#[decode(id = <str>, arg = <type>, generics = <generics>, inline_always)]`
+///
+/// Define custom decode implementations with specific arguments:
+/// - `id = <str>` - Unique identifier for this decode variant (optional, defaults to no id)
+/// - `arg = <type>` - Type of argument passed to decode functions
+/// - `generics = [<generics> where <predicates>]` - Additional generic parameters for this decode implementation
+/// - `inline_always` - Whether to add `#[inline(always)]` to the decode implementation (by default `#[inline]` is added)
+///
+/// ## `#[validate(id = <str>, arg = <type>, generics = <generics>, before_validation = <expr>, extra_validation = <expr>, inline_always)]`
+///
+/// Define custom validation implementations:
+/// - `id = <str>` - Unique identifier for this validate variant (optional, defaults to no id)
+/// - `arg = <type>` - Type of argument passed to validate functions
+/// - `generics = [<generics> where <predicates>]` - Additional generic parameters for this validate implementation
+/// - `before_validation = <expr>` - Expression to execute before field validation, must return a `Result`
+/// - `extra_validation = <expr>` - Expression to execute after field validation, must return a `Result`
+/// - `inline_always` - Whether to add `#[inline(always)]` to the validate implementation (by default `#[inline]` is added)
+///
+/// ## `#[cleanup(id = <str>, generics = <generics>, arg = <type>, extra_cleanup = <expr>, inline_always)]`
+///
+/// Define custom cleanup implementations:
+/// - `id = <str>` - Unique identifier for this cleanup variant
+/// - `generics = [<generics> where <predicates>]` - Generic parameters for this cleanup implementation
+/// - `arg = <type>` - Type of argument passed to cleanup functions
+/// - `extra_cleanup = <expr>` - Cleanup expression to execute after field cleanup, must return a `Result`
+/// - `inline_always` - Whether to add `#[inline(always)]` to the cleanup implementation (by default `#[inline]` is added)
+///
+/// ## `#[idl(id = <str>, arg = <type>, generics = <generics>)]`
+///
+/// Define custom IDL generation implementations:
+/// - `id = <str>` - Unique identifier for this IDL variant (optional, defaults to no id)
+/// - `arg = <type>` - Type of argument passed to IDL functions
+/// - `generics = [<generics> where <predicates>]` - Additional generic parameters for this IDL implementation
+///
+/// In all of these, the argument is available as `arg`, and the `Context` as `ctx`.
+///
+/// # Field-level Attributes
+///
+/// ## `#[account_set(skip = <expr>)]`
+///
+/// Skip this field during account set processing. The field will be initialized with the provided default value.
+///
+/// ## `#[account_set(allow_duplicate)]`
+///
+/// Leaves the field out of the `check_duplicates` check, for accounts that are meant to alias another field, like a
+/// payer that can also be the authority.
+///
+/// ## `#[single_account_set(signer, writable, skip_*)]`
+///
+/// Mark a field as a single account set. This indicates that the AccountSet contains only one account
+/// and all account set traits should be passed through to this flagged field. Only one field can have this
+/// attribute, and all other fields must be skipped.
+///
+/// Options:
+/// - `signer` - Mark this account as a signer
+/// - `writable` - Mark this account as writable
+/// - `skip_signed_account` - Skip `SignedAccount` trait implementation
+/// - `skip_writable_account` - Skip `WritableAccount` trait implementation
+/// - `skip_has_inner_type` - Skip `HasInnerType` trait implementation
+/// - `skip_has_owner_program` - Skip `HasOwnerProgram` trait implementation
+/// - `skip_has_seeds` - Skip `HasSeeds` trait implementation
+/// - `skip_read_account` - Skip `ReadAccount` trait implementation
+/// - `skip_can_init_seeds` - Skip `CanInitSeeds` trait implementation
+/// - `skip_can_init_account` - Skip `CanInitAccount` trait implementation
+///
+/// When a field is marked with `#[single_account_set]`, the generated AccountSet implementation will:
+/// - Implement `SingleAccountSet` and delegate to the marked field
+/// - Forward trait implementations like `SignedAccount`, `WritableAccount`, `HasSeeds`, etc.
+/// - Pass the argument of the default `AccountSetToIdl` implementation through to the field
+///
+/// To pass any argument through to the field, declare its generic on the struct-level attribute, like
+/// `#[validate(generics = [<A>], arg = A)]` with `#[validate(arg = arg)]` on the field.
+///
+/// ## `#[validate(id = <str>, funder, recipient, skip, arg = <expr>, arg_ty = <type>, address = <expr>, owner = <type>, has_one = <field>, constraint = <expr>, error = <expr>)]`
+///
+/// Pass arguments to field validation:
+/// - `id = <str>` - Which validate variant this field participates in, to enable multiple `AccountSetValidate` implementations
+/// - `funder` - Mark this field as the funder for the Context cache (only one field can be marked as funder)
+/// - `recipient` - Mark this field as the recipient for the Context cache (only one field can be marked as recipient)
+/// - `skip` - Skip validation for this field
+/// - `arg = <expr>` - Argument to pass to the field's `AccountSetValidate` function
+/// - `arg_ty = <type>` - Type of the validation argument. Inferred for `arg`, `arg.0`, and no `arg`, but can be specified to get better error messages
+/// - `address = <expr>` - Check that the field's key matches this address, expr must be a `Pubkey` or `&Pubkey`
+/// - `owner = <type>` - Check that the field's account is owned by the `StarFrameProgram` `<type>`
+/// - `has_one = <field>` - Check that the key the field's account stores matches the key of the sibling `<field>`.
+///   The stored key is the `<field>` of the account's data, read through `ReadAccount` like with `Account<T>`. Can be
+///   repeated
+/// - `has_one = <field> @ <accessor>` - Like `has_one = <field>`, but the stored key is read with the field's
+///   `<accessor>()` method, which must return a `Result<Pubkey>`. For accounts whose data isn't a plain struct
+/// - `constraint = <expr>` - Check that the `bool` expression is true. `error = <expr>` sets the error it fails with,
+///   which defaults to `ErrorCode::ConstraintViolated`
+///
+/// The checks run after the field is validated, and their errors have the field's account path and the expected and
+/// found values. The checks of the validation without an `id` are added to the field in the IDL: the `address` as
+/// the account's address, and the others to its description.
+///
+/// ## `#[decode(id = <str>, arg = <expr>)]`
+///
+/// Pass arguments to field decoding:
+/// - `id = <str>` - Which decode variant this field participates in, to enable multiple `AccountSetDecode` implementations
+/// - `arg = <expr>` - Argument to pass to the field's `AccountSetDecode` function
+///
+/// ## `#[cleanup(id = <str>, arg = <expr>)]`
+///
+/// Pass arguments to field cleanup:
+/// - `id = <str>` - Which cleanup variant this field participates in, to enable multiple `AccountSetCleanup` implementations
+/// - `arg = <expr>` - Argument to pass to the field's `AccountSetCleanup` function
+///
+/// ## `#[idl(id = <str>, arg = <expr>, address = <expr>)]`
+///
+/// Pass arguments to IDL generation:
+/// - `id = <str>` - Which IDL variant this field participates in, to enable multiple `AccountSetToIdl` implementations
+/// - `arg = <expr>` - Argument to pass to the field's `AccountSetToIdl` function for IDL generation
+/// - `address = <expr>` - Address expression for single account IDL generation, expr must be a `Pubkey` or `&Pubkey`.
+///   Defaults to the field's `#[validate(address)]`
+///
+/// # Examples
+///
+/// ## Basic Account Set
+///
+/// ```
+/// # fn main() {}
+/// use star_frame::prelude::*;
+///
+/// #[derive(AccountSet)]
+/// pub struct BasicAccounts {
+///     #[validate(funder)]
+///     pub authority: Mut<Signer>,
+///     pub account: Mut<SystemAccount>,
+/// }
+/// ```
+///
+/// ## Account Set with Custom Arguments
+///
+/// ```
+/// # fn main() {}
+/// use star_frame::prelude::*;
+///
+/// #[derive(AccountSet)]
+/// #[validate(arg = Pubkey, extra_validation = self.check_authority(&arg))]
+/// pub struct CustomAccounts {
+///     pub authority: Signer,
+///     pub account: Mut<SystemAccount>,
+/// }
+///
+/// impl CustomAccounts {
+///     fn check_authority(&self, expected: &Pubkey) -> Result<()> {
+///         ensure!(
+///             self.authority.pubkey() == expected,
+///             ProgramError::InvalidArgument,
+///             "Wrong authority"
+///         );
+///         Ok(())
+///     }
+/// }
+/// ```
+///
+/// By setting the validate arg to `Pubkey`, any `StarFrameInstruction` using this set must have an `InstructionArgs`
+/// implementation that returns that type.
+///
+/// ## Declarative Checks
+///
+/// ```
+/// # fn main() {}
+/// use star_frame::{prelude::*, derive_more};
+///
+/// #[derive(AccountSet)]
+/// pub struct VaultAccounts {
+///     pub authority: Signer,
+///     // Reads `authority` from the `Vault` data
+///     #[validate(has_one = authority)]
+///     pub vault: Account<Vault>,
+///     // Reads `authority` with `Config::authority`
+///     #[validate(owner = VaultProgram, has_one = authority @ authority, constraint = self.config.data_len() >= 32)]
+///     pub config: Config,
+///     #[validate(address = pinocchio_system::ID)]
+///     pub system_program: AccountInfo,
+/// }
+///
+/// #[derive(Debug, Clone, Copy, Pod, Zeroable)]
+/// #[repr(C)]
+/// pub struct Vault {
+///     pub authority: Pubkey,
+///     pub amount: u64,
+/// }
+///
+/// impl ProgramAccount for Vault {
+///     type OwnerProgram = VaultProgram;
+///     const DISCRIMINANT: [u8; 8] = *b"vault\0\0\0";
+/// }
+///
+/// /// Stores its authority in its first 32 bytes, without a discriminant.
+/// #[derive(AccountSet, derive_more::Deref, Debug)]
+/// pub struct Config(#[single_account_set] AccountInfo);
+///
+/// impl Config {
+///     fn authority(&self) -> Result<Pubkey> {
+///         let data = self.0.try_borrow_data()?;
+///         let authority = data.first_chunk::<32>().ok_or(ProgramError::AccountDataTooSmall)?;
+///         Ok(*authority)
+///     }
+/// }
+/// # #[derive(Debug)]
+/// # pub struct VaultProgram;
+/// # impl StarFrameProgram for VaultProgram {
+/// #     type InstructionSet = ();
+/// #     type AccountDiscriminant = [u8; 8];
+/// #     type Errors = ();
+/// #     const ID: Pubkey = [1; 32];
+/// # }
+/// ```
+///
+/// ## Single Account Set Newtype
+///
+/// ```
+/// # fn main() {}
+/// use star_frame::{prelude::*, derive_more};
+///
+/// #[derive(AccountSet, derive_more::Deref, derive_more::DerefMut, Debug)]
+/// pub struct Wallet(#[single_account_set] Mut<SystemAccount>);
+/// ```
+///
+/// This creates a newtype wrapper that implements `AccountSet` and passes through all account
+/// traits to the inner `Mut<SystemAccount>`. The `signer` and `writable` flags modify
+/// the account's metadata. This will propagate all of the `account_set::modifiers`
+/// marker traits from the inner account to the newtype, so `Wallet` is a `WritableAccount`.
+#[proc_macro_error]
+#[proc_macro_derive(
+    AccountSet,
+    attributes(account_set, decode, validate, cleanup, idl, single_account_set)
+)]
+pub fn derive_account_set(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let out = account_set::derive_account_set_impl(parse_macro_input!(input as DeriveInput));
+    out.into()
+}
 
-// /// Derives the `GetSeeds` trait for a struct.
-// ///
-// /// # Attributes
-// ///
-// /// ## 1. `#[get_seeds(seed_const = <expr>, skip_idl)]` (item level attribute)
-// ///
-// /// ### Syntax
-// ///
-// /// Attribute takes an `Expr` which resolves to a `&[u8]` seed for the account.
-// /// If `skip_idl` is present, the `SeedsToIdl` trait and the `IdlFindSeed` struct will not be derived.
-// ///
-// /// ### Usage
-// ///
-// /// Attribute is optional. If the attribute is present, the seed for the account will be the concatenation
-// /// of the seed provided in the attribute and the seeds of the fields of the account.
-// ///
-// /// ```
-// /// # use star_frame::prelude::*;
-// /// // `seed_const` is not present
-// /// // Resulting `account.seeds()` is `vec![account.key.seed(), account.number.seed()];`
-// ///
-// /// #[derive(Debug, GetSeeds, Clone)]
-// /// pub struct TestAccount {
-// ///     key: Pubkey,
-// ///     number: u64,
-// /// }
-// ///
-// /// let account = TestAccount {
-// ///     key: Pubkey::new_unique(),
-// ///     number: 42,
-// /// };
-// /// ```
-// ///
-// /// ```
-// /// # use star_frame::prelude::*;
-// /// // `seed_const` here resolves to the `DISC` constant of the `Cool` struct
-// /// // Resulting `account.seeds()` is `vec![b"TEST_CONST".as_ref()];`
-// /// pub struct Cool {}
-// ///
-// /// impl Cool {
-// ///     const DISC: &'static [u8] = b"TEST_CONST";
-// /// }
-// ///
-// /// #[derive(Debug, GetSeeds, Clone)]
-// /// #[get_seeds(seed_const = Cool::DISC)]
-// /// pub struct TestAccount {}
-// /// ```
-// ///
-// /// ```
-// /// # use star_frame::prelude::*;
-// /// // `seed_const` here resolves to the byte string `b"TEST_CONST"`
-// /// // Resulting `account.seeds()` is `vec![b"TEST_CONST".as_ref(), account.key.seed()];`
-// /// #[derive(Debug, GetSeeds, Clone)]
-// /// #[get_seeds(seed_const = b"TEST_CONST")]
-// /// pub struct TestAccount {
-// ///     key: Pubkey,
-// /// }
-// /// ```
-// #[proc_macro_error]
-// #[proc_macro_derive(GetSeeds, attributes(get_seeds))]
-// pub fn derive_get_seeds(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-//     let out = get_seeds::derive_get_seeds_impl(parse_macro_input!(input as DeriveInput));
-//     out.into()
-// }
+/// Derives the `GetSeeds` trait for a struct or enum.
+///
+/// `SEED_COUNT` is set to the number of seeds, and `seeds()` returns them in a `[&[u8]; SEED_COUNT + 1]` array whose
+/// last slot is left empty, so the bump can be filled in later on without allocating.
+///
+/// Struct fields are seeds in order, for both named and tuple structs. Enums start with a single byte tag seed of
+/// the variant index, followed by the variant's fields. Variants with fewer fields than the largest variant are padded
+/// with empty seeds, which don't change the derived address. The IDL seeds of an enum are a tagged union.
+///
+/// Fields can be any `Seed`, as well as strings and byte slices such as `String`, `&str`, `Vec<u8>`, and `&[u8]`.
+/// Seeds whose length is known at compile time, including `PodStr<N>`, are checked against the 32 byte maximum
+/// seed length, and more than 15 seeds is an error. Strings and byte slices are checked when the seeds are validated.
+///
+/// # Attributes
+///
+/// ## 1. `#[get_seeds(seed_const = <expr>, skip_idl)]` (item level attribute)
+///
+/// ### Syntax
+///
+/// Attribute takes an `Expr` which resolves to a `&[u8]` seed for the account.
+/// If `skip_idl` is present, the `SeedsToIdl` trait and the `IdlFindSeed` struct will not be derived.
+///
+/// ### Usage
+///
+/// Attribute is optional. If the attribute is present, the seed for the account will be the concatenation
+/// of the seed provided in the attribute and the seeds of the fields of the account.
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// // `seed_const` is not present
+/// // Resulting `account.seeds()` is `[account.key.seed(), account.number.seed(), &[]]`
+///
+/// #[derive(Debug, GetSeeds, Clone)]
+/// pub struct TestAccount {
+///     key: Pubkey,
+///     number: u64,
+/// }
+///
+/// let account = TestAccount {
+///     key: Pubkey::new_unique(),
+///     number: 42,
+/// };
+/// ```
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// // `seed_const` here resolves to the `DISC` constant of the `Cool` struct
+/// // Resulting `account.seeds()` is `[b"TEST_CONST".as_ref(), &[]]`
+/// pub struct Cool {}
+///
+/// impl Cool {
+///     const DISC: &'static [u8] = b"TEST_CONST";
+/// }
+///
+/// #[derive(Debug, GetSeeds, Clone)]
+/// #[get_seeds(seed_const = Cool::DISC)]
+/// pub struct TestAccount {}
+/// ```
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// // `seed_const` here resolves to the byte string `b"TEST_CONST"`
+/// // Resulting `account.seeds()` is `[b"TEST_CONST".as_ref(), account.key.seed(), &[]]`
+/// #[derive(Debug, GetSeeds, Clone)]
+/// #[get_seeds(seed_const = b"TEST_CONST")]
+/// pub struct TestAccount {
+///     key: Pubkey,
+/// }
+/// ```
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// // Resulting `User` seeds are `[b"vault".as_ref(), &[0], owner.seed(), &[]]`
+/// // Resulting `Protocol` seeds are `[b"vault".as_ref(), &[1], &[], &[]]`
+/// #[derive(Debug, GetSeeds, Clone)]
+/// #[get_seeds(seed_const = b"vault")]
+/// pub enum VaultSeeds {
+///     User { owner: Pubkey },
+///     Protocol,
+/// }
+/// ```
+#[proc_macro_error]
+#[proc_macro_derive(GetSeeds, attributes(get_seeds))]
+pub fn derive_get_seeds(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let out = get_seeds::derive_get_seeds_impl(parse_macro_input!(input as DeriveInput));
+    out.into()
+}
 
 // /// Derives `Align1` for a valid type.
 // #[proc_macro_error]
@@ -370,13 +477,15 @@
 // ///
 // /// # Attributes
 // ///
-// /// ## `#[program_account(skip_idl, program = <ty>, seeds = <ty>, discriminant = <expr>)]` (item level attribute)
+// /// ## `#[program_account(skip_idl, program = <ty>, seeds = <ty>, bump = <ident>, discriminant = <expr>)]` (item level attribute)
 // ///
 // /// ### Arguments
 // /// - `skip_idl` (presence) - If present, skips generating IDL implementations for this account
 // /// - `program` (optional `Type`) - Specifies the program that owns this account type. Defaults to StarFrameDeclaredProgram at root of your crate
 // ///    (Defined by the `#[derive(StarFrameProgram)]` macro)
 // /// - `seeds` (optional `Type`) - Specifies the seed type used to generate PDAs for this account
+// /// - `bump` (optional `Ident`) - A `u8` field storing the account's canonical PDA bump. `Seeded` reads it to validate
+// ///    the address with a single `create_program_address` instead of `find_program_address`
 // /// - `discriminant` (optional `Expr`) - Custom discriminant value for the account type, overriding the Anchor style sighash
 // ///
 // /// ### Usage
@@ -390,9 +499,10 @@
 // ///
 // /// #[zero_copy(pod)]
 // /// #[derive(ProgramAccount, Debug)]
-// /// #[program_account(seeds = MyAccountSeeds)]
+// /// #[program_account(seeds = MyAccountSeeds, bump = bump)]
 // /// pub struct MyAccount {
 // ///     pub data: u64,
+// ///     pub bump: u8,
 // /// }
 // ///
 // /// #[derive(GetSeeds, Debug, Clone)]
@@ -454,6 +564,7 @@
 // ///     id = <expr>,
 // ///     account_discriminant = <ty>,
 // ///     closed_account_discriminant = <expr>,
+// ///     errors = <ty>,
 // ///     no_entrypoint,
 // ///     no_setup,
 // ///     skip_idl
@@ -465,6 +576,8 @@
 // /// or an expression that resolves to a `Pubkey`
 // /// - `account_discriminant` - The `AccountDiscriminant` type used for the program. Defaults to `[u8; 8]` (similarly to Anchor)
 // /// - `closed_account_discriminant` - The `AccountDiscriminant` value used for closed accounts. Defaults to `[u8::MAX; 8]`
+// /// - `errors` - The program's error enum, derived with `star_frame_error`. CPIs into the program decode its custom
+// /// error codes into this type. Defaults to `()`
 // /// - `no_entrypoint` - If present, the macro will not generate an entrypoint for the program.
 // /// While the generated entrypoint is already feature gated, this may be useful in some cases where features aren't convenient.
 // /// - `no_setup` - If present, the macro will not call the `program_setup!` macro. This is useful in libraries that may contain multiple programs.
@@ -908,42 +1021,67 @@
 //     out.into()
 // }
 
-// /// Derives the `StarFrameError` and `ErrorsToIdl` traits on an enum.
-// ///
-// /// Additionally derives Copy, Clone, Debug, Display, Eq, and PartialEq.
-// ///
-// /// # Attributes
-// ///
-// /// ## `#[star_frame_error(offset = <u16>, skip_idl)]` (item level attribute)
-// ///
-// /// - `offset` - The offset to use for the error code. Defaults to the first 2 bytes of the crate name's sha256 hash.
-// ///   to avoid collisions from other crates. Each variant's discriminant (even if explicitly set) will be offset by this value.
-// /// - `skip_idl` - If present, `ErrorsToIdl` will not be derived.
-// ///
-// /// ## `#[msg("My error message")]` (required variant level attribute)
-// ///
-// /// Used in the Display implementation and as the name for the IDL ErrorNode. The message must be a string literal.
-// ///
-// /// # Example
-// /// ```
-// /// use star_frame::prelude::*;
-// ///
-// /// #[star_frame_error]
-// /// pub enum MyError {
-// ///     #[msg("An invalid argument was provided")]
-// ///     InvalidArgument2 = 0, // The actual error code will be offset by the crate name's sha256 hash
-// /// }
-// /// ```
-// #[proc_macro_error]
-// #[proc_macro_attribute]
-// pub fn star_frame_error(
-//     args: proc_macro::TokenStream,
-//     item: proc_macro::TokenStream,
-// ) -> proc_macro::TokenStream {
-//     let out =
-//         star_frame_error::star_frame_error_impl(parse_macro_input!(item as ItemEnum), args.into());
-//     out.into()
-// }
+/// Derives the `StarFrameError`, `ErrorVariants`, and `ErrorsToIdl` traits on an enum.
+///
+/// Additionally derives Copy, Clone, Debug, Display, Eq, and PartialEq, and sets `#[repr(u32)]`.
+/// The enum is converted into `star_frame`'s `Error` through the blanket `From` implementation for `StarFrameError`s.
+///
+/// # Attributes
+///
+/// ## `#[star_frame_error(offset = <u32>, skip_idl)]` (item level attribute)
+///
+/// - `offset` - The offset to use for the error code. Defaults to the first 2 bytes of the crate name's sha256 hash
+///   shifted into the upper 16 bits, to avoid collisions from other crates. Each variant's discriminant (even if explicitly set)
+///   will be offset by this value. Use `star_frame::errors::ANCHOR_ERROR_CODE_OFFSET` to match Anchor's numbering.
+/// - `skip_idl` - If present, `ErrorsToIdl` will not be derived.
+///
+/// ## `#[msg("My error message")]` (required variant level attribute)
+///
+/// Used in the Display implementation and as the message for the IDL ErrorNode. The message must be a string literal.
+///
+/// # Compile time checks
+///
+/// - Each variant's discriminant must fit in a `u16`, so codes stay within the 65,536 codes starting at the `offset`.
+/// - Codes must not fall in the ranges used by `star_frame`'s `ErrorCode`, or with the `anchor_errors` feature, in the
+///   codes Anchor uses for its own errors (see `star_frame::errors::RESERVED_ERROR_CODE_RANGES`).
+///
+/// # Example
+/// ```
+/// use star_frame::{errors::StarFrameError, prelude::*};
+///
+/// #[star_frame_error]
+/// pub enum MyError {
+///     #[msg("An invalid argument was provided")]
+///     InvalidArgument2 = 0, // The actual error code will be offset by the crate name's sha256 hash
+/// }
+///
+/// #[star_frame_error(offset = 20_000)]
+/// pub enum MyOtherError {
+///     #[msg("Something else went wrong")]
+///     SomethingElse = 1,
+/// }
+/// assert_eq!(MyOtherError::SomethingElse.code(), 20_001);
+/// ```
+///
+/// ```compile_fail
+/// use star_frame::prelude::*;
+///
+/// #[star_frame_error(offset = 0)]
+/// pub enum CollidesWithBuiltin {
+///     #[msg("Uses an account set error code")]
+///     Collides = 1_000,
+/// }
+/// ```
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn star_frame_error(
+    args: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let out =
+        star_frame_error::star_frame_error_impl(parse_macro_input!(item as ItemEnum), args.into());
+    out.into()
+}
 
 // /// Compile time hashing of string literals.
 // ///
@@ -0,0 +1,215 @@
+use proc_macro2::TokenStream;
+use proc_macro_error2::{abort, abort_call_site};
+use quote::quote;
+use sha2::{Digest, Sha256};
+use syn::{parse::Parser, Expr, Fields, ItemEnum, LitStr};
+
+#[derive(Default)]
+struct StarFrameErrorArgs {
+    offset: Option<Expr>,
+    skip_idl: bool,
+}
+
+impl StarFrameErrorArgs {
+    fn parse(args: TokenStream) -> Self {
+        let mut out = Self::default();
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("offset") {
+                out.offset = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("skip_idl") {
+                out.skip_idl = true;
+                Ok(())
+            } else {
+                Err(meta.error("Expected `offset = <u32>` or `skip_idl`"))
+            }
+        });
+        if let Err(error) = parser.parse2(args) {
+            abort!(error.span(), error.to_string());
+        }
+        out
+    }
+}
+
+/// The default offset puts the first 2 bytes of the crate name's sha256 hash in the upper 16 bits of the code,
+/// giving each crate its own range of 65,536 codes.
+fn default_offset() -> u32 {
+    let crate_name = std::env::var("CARGO_CRATE_NAME")
+        .unwrap_or_else(|_| abort_call_site!("`CARGO_CRATE_NAME` is not set, pass an `offset`"));
+    let hash = Sha256::digest(crate_name.as_bytes());
+    u32::from(u16::from_le_bytes([hash[0], hash[1]])) << 16
+}
+
+pub fn star_frame_error_impl(item: ItemEnum, args: TokenStream) -> TokenStream {
+    let crate_name = std::env::var("CARGO_CRATE_NAME").ok();
+    expand(item, args, crate_name.as_deref())
+}
+
+/// `star_frame`'s own `ErrorCode` is what defines the built-in ranges, so it's the only enum that can use them.
+fn checks_reserved_ranges(crate_name: Option<&str>) -> bool {
+    crate_name != Some("star_frame")
+}
+
+fn expand(mut item: ItemEnum, args: TokenStream, crate_name: Option<&str>) -> TokenStream {
+    let StarFrameErrorArgs { offset, skip_idl } = StarFrameErrorArgs::parse(args);
+    let offset = offset.unwrap_or_else(|| {
+        let offset = default_offset();
+        syn::parse_quote!(#offset)
+    });
+
+    if !item.generics.params.is_empty() {
+        abort!(item.generics, "`star_frame_error` enums cannot be generic");
+    }
+    if item.variants.is_empty() {
+        abort!(
+            item.ident,
+            "`star_frame_error` enums must have at least one variant"
+        );
+    }
+
+    let mut messages = vec![];
+    for variant in &mut item.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            abort!(
+                variant.fields,
+                "`star_frame_error` variants cannot have fields"
+            );
+        }
+        let Some(index) = variant
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("msg"))
+        else {
+            abort!(
+                variant,
+                "Missing `#[msg(\"...\")]` attribute on error variant"
+            );
+        };
+        let attr = variant.attrs.remove(index);
+        let message: LitStr = attr
+            .parse_args()
+            .unwrap_or_else(|error| abort!(error.span(), "Expected a string literal message"));
+        messages.push(message);
+    }
+
+    let ident = &item.ident;
+    let variant_idents: Vec<_> = item.variants.iter().map(|v| &v.ident).collect();
+    let variant_names: Vec<_> = variant_idents.iter().map(ToString::to_string).collect();
+
+    // rustc already rejects duplicate discriminants, so only the range of each code needs checking.
+    let out_of_range: Vec<_> = variant_names
+        .iter()
+        .map(|name| {
+            format!("`{ident}::{name}` is outside of the error offset range of 65,536 codes")
+        })
+        .collect();
+    let reserved: Vec<_> = variant_names
+        .iter()
+        .map(|name| {
+            format!("`{ident}::{name}` collides with the error codes reserved by `star_frame`. Change the `offset`")
+        })
+        .collect();
+
+    let reserved_checks = checks_reserved_ranges(crate_name).then(|| {
+        quote! {
+            #({
+                let code = OFFSET + #ident::#variant_idents as u32;
+                let ranges = ::star_frame::errors::RESERVED_ERROR_CODE_RANGES;
+                let mut index = 0;
+                while index < ranges.len() {
+                    assert!(code < ranges[index].0 || code >= ranges[index].1, #reserved);
+                    index += 1;
+                }
+            })*
+        }
+    });
+
+    let idl_impl = (!skip_idl).then(|| {
+        quote! {
+            #[cfg(all(feature = "idl", not(target_os = "solana")))]
+            impl ::star_frame::idl::ErrorsToIdl for #ident {
+                fn errors_to_idl() -> ::star_frame::IdlResult<Vec<::star_frame::star_frame_idl::ErrorNode>> {
+                    Ok(vec![#(
+                        ::star_frame::star_frame_idl::ErrorNode::new(
+                            #variant_names,
+                            ::star_frame::errors::StarFrameError::code(&Self::#variant_idents) as usize,
+                            #messages,
+                        )
+                    ),*])
+                }
+            }
+        }
+    });
+
+    quote! {
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        #[repr(u32)]
+        #item
+
+        impl ::star_frame::errors::StarFrameError for #ident {
+            #[inline]
+            fn code(&self) -> u32 {
+                const OFFSET: u32 = #offset;
+                OFFSET + *self as u32
+            }
+
+            fn name(&self) -> ::std::borrow::Cow<'static, str> {
+                ::std::borrow::Cow::Borrowed(match self {
+                    #(Self::#variant_idents => #variant_names,)*
+                })
+            }
+
+            fn message(&self) -> ::std::borrow::Cow<'static, str> {
+                ::std::borrow::Cow::Borrowed(match self {
+                    #(Self::#variant_idents => #messages,)*
+                })
+            }
+        }
+
+        impl ::star_frame::errors::ErrorVariants for #ident {
+            const VARIANTS: &'static [Self] = &[#(Self::#variant_idents),*];
+        }
+
+        impl ::core::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(&::star_frame::errors::StarFrameError::message(self))
+            }
+        }
+
+        const _: () = {
+            const OFFSET: u32 = #offset;
+            #(
+                assert!(
+                    (#ident::#variant_idents as u32) <= u16::MAX as u32
+                        && OFFSET.checked_add(#ident::#variant_idents as u32).is_some(),
+                    #out_of_range
+                );
+            )*
+            #reserved_checks
+        };
+
+        #idl_impl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_in(crate_name: Option<&str>) -> String {
+        let item = syn::parse_quote! {
+            pub enum MyError {
+                #[msg("Uses an account set error code")]
+                Collides = 1_000,
+            }
+        };
+        expand(item, quote!(offset = 0), crate_name).to_string()
+    }
+
+    #[test]
+    fn test_reserved_range_exemption() {
+        assert!(!expand_in(Some("star_frame")).contains("RESERVED_ERROR_CODE_RANGES"));
+        assert!(expand_in(Some("my_program")).contains("RESERVED_ERROR_CODE_RANGES"));
+        assert!(expand_in(None).contains("RESERVED_ERROR_CODE_RANGES"));
+    }
+}
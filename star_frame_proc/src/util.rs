@@ -0,0 +1,68 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Attribute, Expr, ExprLit, Generics, Ident, Lifetime, Lit, Meta};
+
+/// Paths and idents shared by the generated code.
+pub struct Paths {
+    pub prelude: TokenStream,
+    pub account_set: TokenStream,
+    pub get_seeds_ident: Ident,
+}
+
+impl Default for Paths {
+    fn default() -> Self {
+        Self {
+            prelude: quote!(::star_frame::prelude),
+            account_set: quote!(::star_frame::account_set),
+            get_seeds_ident: format_ident!("get_seeds"),
+        }
+    }
+}
+
+/// Collects the `///` doc comments of an item into a `Vec<String>` expression.
+pub fn get_docs(attrs: &[Attribute]) -> TokenStream {
+    let docs = attrs.iter().filter_map(|attr| {
+        let Meta::NameValue(meta) = &attr.meta else {
+            return None;
+        };
+        if !meta.path.is_ident("doc") {
+            return None;
+        }
+        match &meta.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(doc), ..
+            }) => Some(doc.value().trim().to_string()),
+            _ => None,
+        }
+    });
+    quote!(vec![#(#docs.into()),*])
+}
+
+/// Wraps items that are gated behind the user crate's `idl` feature in a hidden module, so crates without
+/// the feature don't trigger the `unexpected_cfgs` lint. The module's items are re-exported in place.
+pub fn ignore_cfg_module(ident: &Ident, suffix: &str, body: TokenStream) -> TokenStream {
+    let module = format_ident!("__{}{}", ident, suffix);
+    quote! {
+        #[allow(unexpected_cfgs, non_snake_case)]
+        #[doc(hidden)]
+        mod #module {
+            use super::*;
+            #body
+        }
+        #[allow(unused_imports)]
+        pub use #module::*;
+    }
+}
+
+/// Creates a lifetime that doesn't collide with any of the lifetimes in `generics`.
+pub fn new_lifetime(generics: &Generics, name: Option<&str>) -> Lifetime {
+    let name = name.unwrap_or("a");
+    let mut lifetime = format!("'__{name}");
+    while generics
+        .lifetimes()
+        .any(|def| def.lifetime.ident == lifetime[1..])
+    {
+        lifetime.push('_');
+    }
+    Lifetime::new(&lifetime, Span::call_site())
+}
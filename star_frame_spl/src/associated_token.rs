@@ -3,7 +3,7 @@ use crate::token::{
     Token,
 };
 
-use star_frame::{derive_more, empty_star_frame_instruction, prelude::*};
+use star_frame::{derive_more, empty_star_frame_instruction, errors::star_frame_error, prelude::*};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
 pub struct AssociatedToken;
@@ -37,9 +37,19 @@ impl AssociatedToken {
     }
 }
 
+/// The custom errors of the Associated Token Account program, decoded from CPIs into [`AssociatedToken`].
+///
+/// See [`spl_associated_token_account_interface::error::AssociatedTokenAccountError`].
+#[star_frame_error(offset = 0)]
+pub enum AssociatedTokenError {
+    #[msg("Associated token account owner does not match address derivation")]
+    InvalidOwner = 0,
+}
+
 impl StarFrameProgram for AssociatedToken {
     type InstructionSet = instructions::AssociatedTokenInstructionSet;
     type AccountDiscriminant = ();
+    type Errors = AssociatedTokenError;
     /// See [`spl_associated_token_account_interface::program::ID`].
     /// ```
     /// # use star_frame::program::StarFrameProgram;
@@ -71,13 +81,16 @@ mod idl_impl {
     pub type FindAtaSeeds = FindAssociatedTokenSeeds;
 
     impl GetSeeds for AssociatedTokenSeeds {
-        fn seeds(&self) -> [&[u8]; 3] {
-            let seeds: [&[u8]; 3] = [
+        const SEED_COUNT: usize = 3;
+        type Seeds<'a> = [&'a [u8]; 4];
+
+        fn seeds(&self) -> Self::Seeds<'_> {
+            [
                 self.wallet.as_ref(),
                 Token::ID.as_ref(),
                 self.mint.pubkey().as_ref(),
-            ];
-            seeds
+                &[],
+            ]
         }
     }
 
@@ -100,7 +113,7 @@ mod idl_impl {
     }
 
     impl ProgramToIdl for AssociatedToken {
-        type Errors = ();
+        type Errors = AssociatedTokenError;
         fn crate_metadata() -> star_frame::star_frame_idl::CrateMetadata {
             star_frame::star_frame_idl::CrateMetadata {
                 version: star_frame::star_frame_idl::Version::new(3, 0, 4),
@@ -330,7 +343,7 @@ pub mod state {
         fn init_account<const IF_NEEDED: bool>(
             &mut self,
             arg: InitAta<'a, WalletInfo, MintInfo>,
-            account_seeds: Option<Vec<&[u8]>>,
+            account_seeds: Option<&[&[u8]]>,
             ctx: &Context,
         ) -> Result<()> {
             let funder = ctx
@@ -373,7 +386,7 @@ pub mod state {
             }
             self.check_writable()?;
             let funder_seeds = funder.signer_seeds();
-            let seeds: &[&[&[u8]]] = match &funder_seeds {
+            let seeds: &[&[&[u8]]] = match funder_seeds.as_deref() {
                 Some(seeds) => &[seeds],
                 None => &[],
             };
@@ -397,7 +410,8 @@ pub mod state {
                 },
                 None,
             )
-            .invoke_signed(seeds)?;
+            .invoke_signed(seeds)
+            .cpi_err::<AssociatedToken>()?;
 
             Ok(())
         }
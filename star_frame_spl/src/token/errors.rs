@@ -0,0 +1,89 @@
+//! Errors returned by the SPL Token program.
+
+use star_frame::errors::star_frame_error;
+
+/// The custom errors of the SPL Token program, decoded from CPIs into [`Token`](super::Token).
+///
+/// See [`spl_token_interface::error::TokenError`].
+/// ```
+/// # use star_frame::errors::StarFrameError;
+/// # use star_frame_spl::token::errors::TokenError;
+/// assert_eq!(
+///     TokenError::AccountFrozen.code(),
+///     spl_token_interface::error::TokenError::AccountFrozen as u32,
+/// );
+/// ```
+#[star_frame_error(offset = 0)]
+pub enum TokenError {
+    #[msg("Lamport balance below rent-exempt threshold")]
+    NotRentExempt = 0,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Invalid Mint")]
+    InvalidMint,
+    #[msg("Account not associated with this Mint")]
+    MintMismatch,
+    #[msg("Owner does not match")]
+    OwnerMismatch,
+    #[msg("Fixed supply")]
+    FixedSupply,
+    #[msg("Already in use")]
+    AlreadyInUse,
+    #[msg("Invalid number of provided signers")]
+    InvalidNumberOfProvidedSigners,
+    #[msg("Invalid number of required signers")]
+    InvalidNumberOfRequiredSigners,
+    #[msg("State is uninitialized")]
+    UninitializedState,
+    #[msg("Instruction does not support native tokens")]
+    NativeNotSupported,
+    #[msg("Non-native account can only be closed if its balance is zero")]
+    NonNativeHasBalance,
+    #[msg("Invalid instruction")]
+    InvalidInstruction,
+    #[msg("State is invalid for requested operation")]
+    InvalidState,
+    #[msg("Operation overflowed")]
+    Overflow,
+    #[msg("Account does not support specified authority type")]
+    AuthorityTypeNotSupported,
+    #[msg("This token mint cannot freeze accounts")]
+    MintCannotFreeze,
+    #[msg("Account is frozen")]
+    AccountFrozen,
+    #[msg("The provided decimals value different from the Mint decimals")]
+    MintDecimalsMismatch,
+    #[msg("Instruction does not support non-native tokens")]
+    NonNativeNotSupported,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::Token;
+    use star_frame::{
+        errors::{CpiFailure, Error},
+        prelude::*,
+    };
+
+    #[test]
+    fn test_cpi_error_is_typed() {
+        let result: ProgramResult = Err(ProgramError::Custom(1));
+        let error = result.cpi_err::<Token>().unwrap_err();
+        assert!(error.is(TokenError::InsufficientFunds));
+        assert_eq!(error.cpi_program(), Some(&Token::ID));
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(1));
+
+        assert_eq!(error.cpi_failure(), Some(CpiFailure::Callee));
+
+        let error = Error::from_cpi::<Token>(ProgramError::Custom(u32::MAX));
+        assert!(!error.is(TokenError::InsufficientFunds));
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(u32::MAX));
+
+        let error = Error::from_cpi::<Token>(ProgramError::AccountBorrowFailed);
+        assert_eq!(error.cpi_program(), Some(&Token::ID));
+        assert_eq!(error.cpi_failure(), Some(CpiFailure::Invoke));
+        assert!(error.to_string().contains("Failed to invoke CPI into"));
+        assert_eq!(ProgramError::from(error), ProgramError::AccountBorrowFailed);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod errors;
 pub mod instructions;
 pub mod state;
 
@@ -10,6 +11,7 @@ pub struct Token;
 impl StarFrameProgram for Token {
     type InstructionSet = instructions::TokenInstructionSet;
     type AccountDiscriminant = ();
+    type Errors = errors::TokenError;
     /// See [`spl_token_interface::ID`].
     /// ```
     /// # use star_frame::program::StarFrameProgram;
@@ -21,7 +23,7 @@ impl StarFrameProgram for Token {
 
 #[cfg(all(feature = "idl", not(target_os = "solana")))]
 impl ProgramToIdl for Token {
-    type Errors = ();
+    type Errors = errors::TokenError;
     fn crate_metadata() -> star_frame::star_frame_idl::CrateMetadata {
         star_frame::star_frame_idl::CrateMetadata {
             version: star_frame::star_frame_idl::Version::new(4, 0, 0),